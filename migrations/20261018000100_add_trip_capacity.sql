@@ -0,0 +1,5 @@
+ALTER TABLE trip_kind
+    ADD COLUMN IF NOT EXISTS max_participants INT CHECK (max_participants >= 0);
+
+ALTER TABLE trip
+    ADD COLUMN IF NOT EXISTS max_participants INT CHECK (max_participants >= 0);
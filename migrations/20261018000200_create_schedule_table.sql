@@ -0,0 +1,35 @@
+CREATE TABLE IF NOT EXISTS schedule
+(
+    schedule_id      UUID        NOT NULL,
+    trip_kind_id     UUID        NOT NULL,
+    location_id      UUID        NOT NULL,
+    timezone         TEXT        NOT NULL,
+    starts_on        DATE        NOT NULL,
+    ends_on          DATE,
+    weekdays         INT[]       NOT NULL DEFAULT '{}',
+    months           INT[]       NOT NULL DEFAULT '{}',
+    times            TIME[]      NOT NULL,
+    duration_minutes INT         NOT NULL CHECK (duration_minutes > 0),
+    max_participants INT CHECK (max_participants >= 0),
+    cancelled_at     TIMESTAMPTZ,
+
+    PRIMARY KEY (schedule_id),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id),
+    FOREIGN KEY (location_id) REFERENCES location (location_id)
+);
+
+ALTER TABLE trip
+    ADD COLUMN IF NOT EXISTS schedule_id UUID REFERENCES schedule (schedule_id),
+    ADD COLUMN IF NOT EXISTS occurrence  TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS detached    BOOL NOT NULL DEFAULT FALSE;
+
+CREATE UNIQUE INDEX IF NOT EXISTS trip_schedule_occurrence_idx ON trip (schedule_id, occurrence);
+
+CREATE TABLE IF NOT EXISTS schedule_exception
+(
+    schedule_id UUID        NOT NULL,
+    occurrence  TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (schedule_id, occurrence),
+    FOREIGN KEY (schedule_id) REFERENCES schedule (schedule_id)
+);
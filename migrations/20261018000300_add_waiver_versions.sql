@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS waiver_version
+(
+    waiver_id    UUID        NOT NULL,
+    version      INT         NOT NULL CHECK (version > 0),
+    content      TEXT        NOT NULL,
+    effective_at TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (waiver_id, version),
+    FOREIGN KEY (waiver_id) REFERENCES waiver (waiver_id)
+);
+
+INSERT INTO waiver_version (waiver_id, version, content, effective_at)
+SELECT waiver_id, 1, content, TIMESTAMPTZ 'epoch'
+FROM waiver
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE waiver
+    DROP COLUMN IF EXISTS content;
+
+ALTER TABLE participant_waiver
+    ADD COLUMN IF NOT EXISTS version INT NOT NULL DEFAULT 1,
+    ADD FOREIGN KEY (waiver_id, version) REFERENCES waiver_version (waiver_id, version);
+
+ALTER TABLE trip_kind
+    ADD COLUMN IF NOT EXISTS waiver_id UUID REFERENCES waiver (waiver_id);
+
+ALTER TABLE booking
+    ADD COLUMN IF NOT EXISTS checked_in_at TIMESTAMPTZ;
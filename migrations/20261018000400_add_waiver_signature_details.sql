@@ -0,0 +1,15 @@
+ALTER TABLE participant_waiver
+    ADD COLUMN IF NOT EXISTS content_hash          TEXT,
+    ADD COLUMN IF NOT EXISTS signed_at             TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS signer_name           TEXT,
+    ADD COLUMN IF NOT EXISTS signer_ip             TEXT,
+    ADD COLUMN IF NOT EXISTS signer_user_agent     TEXT,
+    ADD COLUMN IF NOT EXISTS guardian_relationship TEXT,
+    ADD COLUMN IF NOT EXISTS signature_blob        TEXT;
+
+UPDATE participant_waiver
+SET signed_at = date_signed::TIMESTAMPTZ
+WHERE signed_at IS NULL;
+
+ALTER TABLE participant_waiver
+    ALTER COLUMN signed_at SET NOT NULL;
@@ -0,0 +1,9 @@
+ALTER TABLE trip_kind
+    ADD COLUMN IF NOT EXISTS min_age                INT CHECK (min_age >= 0),
+    ADD COLUMN IF NOT EXISTS max_age                INT CHECK (max_age >= 0),
+    ADD COLUMN IF NOT EXISTS guardian_required_under INT CHECK (guardian_required_under >= 0),
+    ADD COLUMN IF NOT EXISTS requirements           TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE trip_kind
+    DROP CONSTRAINT IF EXISTS trip_kind_age_range_check,
+    ADD CONSTRAINT trip_kind_age_range_check CHECK (min_age <= max_age);
@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS trip_kind_price
+(
+    trip_kind_id UUID   NOT NULL,
+    category     TEXT   NOT NULL CHECK (category IN ('child', 'adult', 'senior')),
+    price        BIGINT NOT NULL CHECK (price >= 0),
+
+    PRIMARY KEY (trip_kind_id, category),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id)
+);
+
+ALTER TABLE equipment
+    ADD COLUMN IF NOT EXISTS rental_price BIGINT CHECK (rental_price >= 0);
+
+CREATE TABLE IF NOT EXISTS price_modifier
+(
+    price_modifier_id UUID  NOT NULL,
+    name              TEXT  NOT NULL,
+    trip_kind_id      UUID,
+    months            INT[] NOT NULL DEFAULT '{}',
+    weekdays          INT[] NOT NULL DEFAULT '{}',
+    percent           INT   NOT NULL CHECK (percent >= -100),
+
+    PRIMARY KEY (price_modifier_id),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id)
+);
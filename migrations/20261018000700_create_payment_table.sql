@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS payment
+(
+    payment_id     UUID        NOT NULL,
+    booking_id     UUID        NOT NULL,
+    kind           TEXT        NOT NULL CHECK (kind IN ('authorization', 'capture', 'refund')),
+    parent_id      UUID,
+    amount         BIGINT      NOT NULL CHECK (amount > 0),
+    declined       BOOL        NOT NULL DEFAULT FALSE,
+    decline_reason TEXT,
+    reference      TEXT,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (payment_id),
+    FOREIGN KEY (booking_id) REFERENCES booking (booking_id),
+    FOREIGN KEY (parent_id) REFERENCES payment (payment_id)
+);
+
+CREATE INDEX IF NOT EXISTS payment_booking_idx ON payment (booking_id, created_at);
@@ -0,0 +1,14 @@
+ALTER TABLE booking
+    ADD COLUMN IF NOT EXISTS cancelled_at        TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS cancellation_reason TEXT,
+    ADD COLUMN IF NOT EXISTS refund_amount       BIGINT;
+
+CREATE TABLE IF NOT EXISTS cancellation_tier
+(
+    trip_kind_id   UUID NOT NULL,
+    notice_minutes INT  NOT NULL CHECK (notice_minutes >= 0),
+    refund_percent INT  NOT NULL CHECK (refund_percent BETWEEN 0 AND 100),
+
+    PRIMARY KEY (trip_kind_id, notice_minutes),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id)
+);
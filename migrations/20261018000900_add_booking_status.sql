@@ -0,0 +1,11 @@
+ALTER TABLE booking
+    ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'confirmed'
+        CHECK (status IN ('held', 'confirmed', 'checked_in', 'completed', 'no_show', 'cancelled'));
+
+-- Bookings made before statuses existed were treated as confirmed.
+UPDATE booking SET status = 'checked_in' WHERE checked_in_at IS NOT NULL AND status = 'confirmed';
+UPDATE booking SET status = 'cancelled' WHERE cancelled_at IS NOT NULL;
+
+ALTER TABLE booking ALTER COLUMN status SET DEFAULT 'held';
+
+CREATE INDEX IF NOT EXISTS booking_status_idx ON booking (status);
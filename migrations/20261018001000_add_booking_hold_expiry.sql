@@ -0,0 +1,4 @@
+ALTER TABLE booking
+    ADD COLUMN IF NOT EXISTS hold_expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS booking_hold_expiry_idx ON booking (hold_expires_at) WHERE status = 'held';
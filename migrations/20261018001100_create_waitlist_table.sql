@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS waitlist_entry
+(
+    waitlist_entry_id UUID        NOT NULL,
+    customer_id       UUID        NOT NULL,
+    trip_id           UUID        NOT NULL,
+    party_size        INT         NOT NULL CHECK (party_size > 0),
+    status            TEXT        NOT NULL DEFAULT 'waiting'
+        CHECK (status IN ('waiting', 'offered', 'accepted', 'expired', 'withdrawn')),
+    offer_expires_at  TIMESTAMPTZ CHECK ((status = 'offered') = (offer_expires_at IS NOT NULL)),
+    created_at        TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (waitlist_entry_id),
+    FOREIGN KEY (customer_id) REFERENCES customer (customer_id),
+    FOREIGN KEY (trip_id) REFERENCES trip (trip_id)
+);
+
+CREATE INDEX IF NOT EXISTS waitlist_entry_trip_idx ON waitlist_entry (trip_id, created_at);
+
+ALTER TABLE booking
+    ADD COLUMN IF NOT EXISTS waitlist_entry_id UUID REFERENCES waitlist_entry (waitlist_entry_id);
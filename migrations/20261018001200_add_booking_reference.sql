@@ -0,0 +1,19 @@
+-- Customers may hold several bookings on the same trip, e.g. for separate groups.
+-- Duplicate participants across a trip's bookings are prevented by the application instead.
+ALTER TABLE booking
+    DROP CONSTRAINT IF EXISTS booking_customer_id_trip_id_key;
+
+CREATE INDEX IF NOT EXISTS booking_customer_trip_idx ON booking (customer_id, trip_id);
+
+-- Related bookings share a reference, like an order number.
+ALTER TABLE booking
+    ADD COLUMN IF NOT EXISTS reference TEXT;
+
+UPDATE booking
+SET reference = 'TD-' || upper(substr(md5(booking_id::TEXT), 1, 10))
+WHERE reference IS NULL;
+
+ALTER TABLE booking
+    ALTER COLUMN reference SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS booking_reference_idx ON booking (reference);
@@ -0,0 +1,21 @@
+-- Emails are about to be unique regardless of case. Customers whose addresses only differ by
+-- case must have their emails corrected first, or the unique index can't be built.
+DO
+$$
+    DECLARE
+        conflicts TEXT;
+    BEGIN
+        SELECT string_agg(email || ' (' || customer_ids || ')', '; ')
+        INTO conflicts
+        FROM (SELECT lower(email)                                           AS email,
+                     string_agg(customer_id::TEXT, ', ' ORDER BY customer_id) AS customer_ids
+              FROM customer
+              GROUP BY lower(email)
+              HAVING count(*) > 1) AS duplicates;
+
+        IF conflicts IS NOT NULL THEN
+            RAISE EXCEPTION 'customers share an email address differing only by case: %', conflicts
+                USING HINT = 'Correct their emails in the database so they differ by more than case, then restart.';
+        END IF;
+    END
+$$;
@@ -0,0 +1,10 @@
+-- Email domains are case-insensitive, so they are stored in lower case.
+UPDATE customer
+SET email = regexp_replace(email, '@[^@]*$', lower(substring(email FROM '@[^@]*$')))
+WHERE email ~ '@[^@]*$';
+
+-- Local parts are kept as entered, but addresses differing only by case belong to one customer.
+ALTER TABLE customer
+    DROP CONSTRAINT IF EXISTS customer_email_key;
+
+CREATE UNIQUE INDEX IF NOT EXISTS customer_email_lower_key ON customer (lower(email));
@@ -0,0 +1,8 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Trigram indexes support fuzzy (%) and substring (LIKE) matching for customer search.
+CREATE INDEX IF NOT EXISTS customer_name_trgm_idx ON customer USING gin (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS customer_email_trgm_idx ON customer USING gin (lower(email) gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS customer_phone_trgm_idx ON customer USING gin (phone gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS customer_phone_idx ON customer (phone);
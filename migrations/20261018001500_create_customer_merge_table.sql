@@ -0,0 +1,19 @@
+-- A customer_merge is the audit record of one customer being merged into another.
+-- The customer IDs aren't foreign keys, so the record outlives both customers.
+CREATE TABLE IF NOT EXISTS customer_merge
+(
+    customer_merge_id  UUID        NOT NULL,
+    survivor_id        UUID        NOT NULL,
+    merged_id          UUID        NOT NULL,
+    merged_name        TEXT        NOT NULL,
+    merged_email       TEXT        NOT NULL,
+    merged_phone       TEXT        NOT NULL,
+    booking_ids        UUID[]      NOT NULL,
+    waitlist_entry_ids UUID[]      NOT NULL,
+    merged_at          TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (customer_merge_id)
+);
+
+CREATE INDEX IF NOT EXISTS customer_merge_survivor_idx ON customer_merge (survivor_id);
+CREATE INDEX IF NOT EXISTS customer_merge_merged_idx ON customer_merge (merged_id);
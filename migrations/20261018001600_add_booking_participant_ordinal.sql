@@ -0,0 +1,4 @@
+-- A booking's participants are read back in the order they were saved in.
+-- Existing rows all get 0, and fall back to being ordered by participant_id.
+ALTER TABLE booking_participant
+    ADD COLUMN IF NOT EXISTS ordinal INT NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- A customer_merge also records the survivor's details as they were just before the merge.
+-- Merges recorded before then don't have them.
+ALTER TABLE customer_merge
+    ADD COLUMN IF NOT EXISTS survivor_name  TEXT,
+    ADD COLUMN IF NOT EXISTS survivor_email TEXT,
+    ADD COLUMN IF NOT EXISTS survivor_phone TEXT;
@@ -0,0 +1,4 @@
+-- A booking saved over its trip's capacity under the flag overbooking policy is marked,
+-- so staff can find and resolve the overbooking.
+ALTER TABLE booking
+    ADD COLUMN IF NOT EXISTS overbooked BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,9 @@
+-- A trip keeps the time zone its local dates (e.g. for seasonal prices and ages) go by.
+-- Trips materialized from a schedule take the schedule's; others default to UTC.
+ALTER TABLE trip
+    ADD COLUMN IF NOT EXISTS timezone TEXT NOT NULL DEFAULT 'UTC';
+
+UPDATE trip
+SET timezone = schedule.timezone
+FROM schedule
+WHERE trip.schedule_id = schedule.schedule_id;
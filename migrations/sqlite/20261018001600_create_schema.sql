@@ -0,0 +1,305 @@
+-- The SQLite schema mirrors the PostgreSQL one as of its 20261018001500 migration.
+-- UUIDs are stored as 16 byte blobs, timestamps as RFC 3339 text in UTC, dates and times
+-- as ISO 8601 text, booleans as 0 or 1, and arrays as JSON text.
+
+CREATE TABLE IF NOT EXISTS waiver
+(
+    waiver_id BLOB NOT NULL,
+
+    PRIMARY KEY (waiver_id)
+);
+
+CREATE TABLE IF NOT EXISTS waiver_version
+(
+    waiver_id    BLOB    NOT NULL,
+    version      INTEGER NOT NULL CHECK (version > 0),
+    content      TEXT    NOT NULL,
+    effective_at TEXT    NOT NULL,
+
+    PRIMARY KEY (waiver_id, version),
+    FOREIGN KEY (waiver_id) REFERENCES waiver (waiver_id)
+);
+
+CREATE TABLE IF NOT EXISTS trip_kind
+(
+    trip_kind_id            BLOB    NOT NULL,
+    name                    TEXT    NOT NULL UNIQUE,
+    description             TEXT    NOT NULL DEFAULT '',
+    guided                  INTEGER NOT NULL DEFAULT 0,
+    meal_provided           INTEGER NOT NULL DEFAULT 0,
+    max_participants        INTEGER CHECK (max_participants >= 0),
+    waiver_id               BLOB,
+    min_age                 INTEGER CHECK (min_age >= 0),
+    max_age                 INTEGER CHECK (max_age >= 0),
+    guardian_required_under INTEGER CHECK (guardian_required_under >= 0),
+    requirements            TEXT    NOT NULL DEFAULT '[]',
+
+    PRIMARY KEY (trip_kind_id),
+    FOREIGN KEY (waiver_id) REFERENCES waiver (waiver_id),
+    CHECK (min_age <= max_age)
+);
+
+CREATE TABLE IF NOT EXISTS cancellation_tier
+(
+    trip_kind_id   BLOB    NOT NULL,
+    notice_minutes INTEGER NOT NULL CHECK (notice_minutes >= 0),
+    refund_percent INTEGER NOT NULL CHECK (refund_percent BETWEEN 0 AND 100),
+
+    PRIMARY KEY (trip_kind_id, notice_minutes),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id)
+);
+
+CREATE TABLE IF NOT EXISTS location
+(
+    location_id BLOB NOT NULL,
+    name        TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL DEFAULT '',
+
+    PRIMARY KEY (location_id)
+);
+
+CREATE TABLE IF NOT EXISTS schedule
+(
+    schedule_id      BLOB    NOT NULL,
+    trip_kind_id     BLOB    NOT NULL,
+    location_id      BLOB    NOT NULL,
+    timezone         TEXT    NOT NULL,
+    starts_on        TEXT    NOT NULL,
+    ends_on          TEXT,
+    weekdays         TEXT    NOT NULL DEFAULT '[]',
+    months           TEXT    NOT NULL DEFAULT '[]',
+    times            TEXT    NOT NULL,
+    duration_minutes INTEGER NOT NULL CHECK (duration_minutes > 0),
+    max_participants INTEGER CHECK (max_participants >= 0),
+    cancelled_at     TEXT,
+
+    PRIMARY KEY (schedule_id),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id),
+    FOREIGN KEY (location_id) REFERENCES location (location_id)
+);
+
+CREATE TABLE IF NOT EXISTS schedule_exception
+(
+    schedule_id BLOB NOT NULL,
+    occurrence  TEXT NOT NULL,
+
+    PRIMARY KEY (schedule_id, occurrence),
+    FOREIGN KEY (schedule_id) REFERENCES schedule (schedule_id)
+);
+
+CREATE TABLE IF NOT EXISTS trip
+(
+    trip_id          BLOB    NOT NULL,
+    trip_kind_id     BLOB    NOT NULL,
+    location_id      BLOB    NOT NULL,
+    start_time       TEXT    NOT NULL,
+    end_time         TEXT    NOT NULL,
+    max_participants INTEGER CHECK (max_participants >= 0),
+    schedule_id      BLOB,
+    occurrence       TEXT,
+    detached         INTEGER NOT NULL DEFAULT 0,
+    timezone         TEXT    NOT NULL DEFAULT 'UTC',
+
+    PRIMARY KEY (trip_id),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id),
+    FOREIGN KEY (location_id) REFERENCES location (location_id),
+    FOREIGN KEY (schedule_id) REFERENCES schedule (schedule_id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS trip_schedule_occurrence_idx ON trip (schedule_id, occurrence);
+
+CREATE TABLE IF NOT EXISTS equipment
+(
+    equipment_id    BLOB    NOT NULL,
+    name            TEXT    NOT NULL UNIQUE,
+    description     TEXT    NOT NULL DEFAULT '',
+    total_inventory INTEGER NOT NULL DEFAULT 0,
+    rental_price    INTEGER CHECK (rental_price >= 0),
+
+    PRIMARY KEY (equipment_id)
+);
+
+CREATE TABLE IF NOT EXISTS trip_equipment
+(
+    trip_kind_id BLOB NOT NULL,
+    equipment_id BLOB NOT NULL,
+
+    PRIMARY KEY (trip_kind_id, equipment_id),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id),
+    FOREIGN KEY (equipment_id) REFERENCES equipment (equipment_id)
+);
+
+CREATE TABLE IF NOT EXISTS trip_kind_price
+(
+    trip_kind_id BLOB    NOT NULL,
+    category     TEXT    NOT NULL CHECK (category IN ('child', 'adult', 'senior')),
+    price        INTEGER NOT NULL CHECK (price >= 0),
+
+    PRIMARY KEY (trip_kind_id, category),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id)
+);
+
+CREATE TABLE IF NOT EXISTS price_modifier
+(
+    price_modifier_id BLOB    NOT NULL,
+    name              TEXT    NOT NULL,
+    trip_kind_id      BLOB,
+    months            TEXT    NOT NULL DEFAULT '[]',
+    weekdays          TEXT    NOT NULL DEFAULT '[]',
+    percent           INTEGER NOT NULL CHECK (percent >= -100),
+
+    PRIMARY KEY (price_modifier_id),
+    FOREIGN KEY (trip_kind_id) REFERENCES trip_kind (trip_kind_id)
+);
+
+CREATE TABLE IF NOT EXISTS customer
+(
+    customer_id BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    email       TEXT NOT NULL,
+    phone       TEXT NOT NULL,
+
+    PRIMARY KEY (customer_id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS customer_email_lower_key ON customer (lower(email));
+CREATE INDEX IF NOT EXISTS customer_phone_idx ON customer (phone);
+
+-- A customer_merge is the audit record of one customer being merged into another.
+-- The customer IDs aren't foreign keys, so the record outlives both customers.
+CREATE TABLE IF NOT EXISTS customer_merge
+(
+    customer_merge_id  BLOB NOT NULL,
+    survivor_id        BLOB NOT NULL,
+    survivor_name      TEXT,
+    survivor_email     TEXT,
+    survivor_phone     TEXT,
+    merged_id          BLOB NOT NULL,
+    merged_name        TEXT NOT NULL,
+    merged_email       TEXT NOT NULL,
+    merged_phone       TEXT NOT NULL,
+    booking_ids        TEXT NOT NULL,
+    waitlist_entry_ids TEXT NOT NULL,
+    merged_at          TEXT NOT NULL,
+
+    PRIMARY KEY (customer_merge_id)
+);
+
+CREATE INDEX IF NOT EXISTS customer_merge_survivor_idx ON customer_merge (survivor_id);
+CREATE INDEX IF NOT EXISTS customer_merge_merged_idx ON customer_merge (merged_id);
+
+CREATE TABLE IF NOT EXISTS waitlist_entry
+(
+    waitlist_entry_id BLOB    NOT NULL,
+    customer_id       BLOB    NOT NULL,
+    trip_id           BLOB    NOT NULL,
+    party_size        INTEGER NOT NULL CHECK (party_size > 0),
+    status            TEXT    NOT NULL DEFAULT 'waiting'
+        CHECK (status IN ('waiting', 'offered', 'accepted', 'expired', 'withdrawn')),
+    offer_expires_at  TEXT CHECK ((status = 'offered') = (offer_expires_at IS NOT NULL)),
+    created_at        TEXT    NOT NULL,
+
+    PRIMARY KEY (waitlist_entry_id),
+    FOREIGN KEY (customer_id) REFERENCES customer (customer_id),
+    FOREIGN KEY (trip_id) REFERENCES trip (trip_id)
+);
+
+CREATE INDEX IF NOT EXISTS waitlist_entry_trip_idx ON waitlist_entry (trip_id, created_at);
+
+CREATE TABLE IF NOT EXISTS booking
+(
+    booking_id          BLOB NOT NULL,
+    reference           TEXT NOT NULL,
+    customer_id         BLOB NOT NULL,
+    trip_id             BLOB NOT NULL,
+    status              TEXT NOT NULL DEFAULT 'held'
+        CHECK (status IN ('held', 'confirmed', 'checked_in', 'completed', 'no_show', 'cancelled')),
+    hold_expires_at     TEXT,
+    checked_in_at       TEXT,
+    cancelled_at        TEXT,
+    cancellation_reason TEXT,
+    refund_amount       INTEGER,
+    waitlist_entry_id   BLOB,
+    overbooked          INTEGER NOT NULL DEFAULT FALSE,
+
+    PRIMARY KEY (booking_id),
+    FOREIGN KEY (customer_id) REFERENCES customer (customer_id),
+    FOREIGN KEY (trip_id) REFERENCES trip (trip_id),
+    FOREIGN KEY (waitlist_entry_id) REFERENCES waitlist_entry (waitlist_entry_id)
+);
+
+CREATE INDEX IF NOT EXISTS booking_customer_trip_idx ON booking (customer_id, trip_id);
+CREATE INDEX IF NOT EXISTS booking_reference_idx ON booking (reference);
+CREATE INDEX IF NOT EXISTS booking_status_idx ON booking (status);
+CREATE INDEX IF NOT EXISTS booking_hold_expiry_idx ON booking (hold_expires_at) WHERE status = 'held';
+
+CREATE TABLE IF NOT EXISTS participant
+(
+    participant_id BLOB NOT NULL,
+    name           TEXT NOT NULL,
+    dob            TEXT NOT NULL,
+    notes          TEXT NOT NULL DEFAULT '',
+
+    PRIMARY KEY (participant_id)
+);
+
+CREATE TABLE IF NOT EXISTS booking_participant
+(
+    booking_id     BLOB NOT NULL,
+    participant_id BLOB NOT NULL,
+
+    PRIMARY KEY (booking_id, participant_id),
+    FOREIGN KEY (booking_id) REFERENCES booking (booking_id),
+    FOREIGN KEY (participant_id) REFERENCES participant (participant_id)
+);
+
+CREATE TABLE IF NOT EXISTS booking_equipment
+(
+    booking_id   BLOB    NOT NULL,
+    equipment_id BLOB    NOT NULL,
+    quantity     INTEGER NOT NULL,
+
+    PRIMARY KEY (booking_id, equipment_id),
+    FOREIGN KEY (booking_id) REFERENCES booking (booking_id),
+    FOREIGN KEY (equipment_id) REFERENCES equipment (equipment_id)
+);
+
+CREATE TABLE IF NOT EXISTS participant_waiver
+(
+    participant_waiver_id BLOB    NOT NULL,
+    participant_id        BLOB    NOT NULL,
+    waiver_id             BLOB    NOT NULL,
+    version               INTEGER NOT NULL DEFAULT 1,
+    date_signed           TEXT    NOT NULL,
+    content_hash          TEXT,
+    signed_at             TEXT    NOT NULL,
+    signer_name           TEXT,
+    signer_ip             TEXT,
+    signer_user_agent     TEXT,
+    guardian_relationship TEXT,
+    signature_blob        TEXT,
+
+    PRIMARY KEY (participant_waiver_id),
+    FOREIGN KEY (participant_id) REFERENCES participant (participant_id),
+    FOREIGN KEY (waiver_id) REFERENCES waiver (waiver_id),
+    FOREIGN KEY (waiver_id, version) REFERENCES waiver_version (waiver_id, version)
+);
+
+CREATE TABLE IF NOT EXISTS payment
+(
+    payment_id     BLOB    NOT NULL,
+    booking_id     BLOB    NOT NULL,
+    kind           TEXT    NOT NULL CHECK (kind IN ('authorization', 'capture', 'refund')),
+    parent_id      BLOB,
+    amount         INTEGER NOT NULL CHECK (amount > 0),
+    declined       INTEGER NOT NULL DEFAULT 0,
+    decline_reason TEXT,
+    reference      TEXT,
+    created_at     TEXT    NOT NULL,
+
+    PRIMARY KEY (payment_id),
+    FOREIGN KEY (booking_id) REFERENCES booking (booking_id),
+    FOREIGN KEY (parent_id) REFERENCES payment (payment_id)
+);
+
+CREATE INDEX IF NOT EXISTS payment_booking_idx ON payment (booking_id, created_at);
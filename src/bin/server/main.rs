@@ -1,24 +1,201 @@
-use tide::config::Config;
+use chrono::{Days, NaiveTime, Utc};
+use chrono_tz::Tz;
+use std::time::Duration;
+use tide::config::{Config, Storage};
 use tide::domain::booking;
+use tide::domain::booking::models::equipment::{
+    Equipment, EquipmentDescription, EquipmentId, EquipmentName,
+};
+use tide::domain::booking::models::pricing::{Money, ParticipantCategory};
+use tide::domain::booking::models::trip::{
+    CancellationPolicy, Eligibility, Location, LocationDescription, LocationId, LocationName,
+    RefundTier, Trip, TripId, TripKind, TripKindId,
+};
+use tide::domain::booking::ports::{BookingService, UnitOfWork};
+use tide::domain::scheduling;
+use tide::domain::scheduling::ports::{ScheduleRepository, ScheduleService};
 use tide::inbound::http::{HttpConfig, HttpServer};
+use tide::outbound::fake_payments::FakePaymentGateway;
+use tide::outbound::filesystem::LocalBlobStore;
+use tide::outbound::memory::Memory;
 use tide::outbound::postgres::{PgConfig, Postgres};
+use tide::outbound::sqlite::{Sqlite, SqliteConfig};
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+const MATERIALIZE_SCHEDULES_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const RELEASE_HOLDS_INTERVAL: Duration = Duration::from_secs(30);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
     let config = Config::from_env()?;
 
+    match &config.storage {
+        Storage::Postgres { url } => {
+            let pg_config = PgConfig { url };
+            run(Postgres::from_config(pg_config).await?, &config).await
+        }
+        Storage::Sqlite { url } => {
+            let sqlite_config = SqliteConfig { url };
+            run(Sqlite::from_config(sqlite_config).await?, &config).await
+        }
+        Storage::Memory => {
+            let memory = Memory::new();
+            seed_demo_catalog(&memory);
+            run(memory, &config).await
+        }
+    }
+}
+
+/// Runs the application on top of the given storage adapter until the server stops.
+async fn run<R>(repo: R, config: &Config) -> anyhow::Result<()>
+where
+    R: UnitOfWork + ScheduleRepository,
+{
     // Initialize outbound adapters needed by core services
-    let pg_config = PgConfig { url: &config.db_url };
-    let postgres = Postgres::from_config(pg_config).await?;
+    let blobs = LocalBlobStore::new(&config.blob_store_path).await?;
+    let payments = FakePaymentGateway::new().with_delay(config.fake_payment_delay);
 
     // Initialize core services
-    let bookings = booking::service::Service::new(postgres.clone());
+    let bookings = booking::service::Service::new(repo.clone(), blobs, payments)
+        .with_overbooking_policy(config.overbooking)
+        .with_hold_duration(config.hold_duration)
+        .with_waitlist_offer_duration(config.waitlist_offer_duration)
+        .with_phone_region(config.phone_region);
+    let schedules = scheduling::service::Service::new(repo);
+
+    if config.normalize_phones {
+        let result = bookings.normalize_customer_phones().await?;
+        tracing::info!(
+            normalized = result.normalized.len(),
+            invalid = result.invalid.len(),
+            "normalized customer phone numbers"
+        );
+        for id in result.invalid {
+            tracing::warn!(customer_id = %id.0, "customer has an invalid phone number");
+        }
+        return Ok(());
+    }
+
+    // Initialize background jobs
+    tokio::spawn(materialize_schedules(schedules.clone()));
+    tokio::spawn(release_expired_holds(bookings.clone()));
 
     // Initialize inbound adapters to consume core services
-    let server_config = HttpConfig { port: "8080" };
-    let server = HttpServer::new(server_config, bookings).await?;
+    let server_config = HttpConfig {
+        port: "8080",
+        trusted_proxies: &config.trusted_proxies,
+    };
+    let server = HttpServer::new(server_config, bookings, schedules).await?;
     server.serve().await?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Keeps the rolling horizon of scheduled trips materialized.
+async fn materialize_schedules(schedules: impl ScheduleService) {
+    let mut interval = tokio::time::interval(MATERIALIZE_SCHEDULES_INTERVAL);
+    loop {
+        interval.tick().await;
+        match schedules.materialize_schedules().await {
+            Ok(written) => tracing::info!(written, "materialized scheduled trips"),
+            Err(e) => tracing::error!("failed to materialize scheduled trips: {e}"),
+        }
+    }
+}
+
+/// Frees the seats and rentals of held bookings that were never confirmed, then offers
+/// free seats to waitlisted parties, including seats added by growing a trip's capacity.
+async fn release_expired_holds(bookings: impl BookingService) {
+    let mut interval = tokio::time::interval(RELEASE_HOLDS_INTERVAL);
+    loop {
+        interval.tick().await;
+        match bookings.release_expired_holds().await {
+            Ok(released) if released.is_empty() => {}
+            Ok(released) => tracing::info!(released = released.len(), "released expired holds"),
+            Err(e) => tracing::error!("failed to release expired holds: {e}"),
+        }
+        match bookings.promote_waitlists().await {
+            Ok(offered) if offered.is_empty() => {}
+            Ok(offered) => tracing::info!(offered = offered.len(), "offered seats to waitlist"),
+            Err(e) => tracing::error!("failed to offer seats to waitlist: {e}"),
+        }
+    }
+}
+
+/// Fills a fresh in-memory store with a location, a trip kind with prices, rental equipment
+/// and a week of daily trips, so the API can be tried out without any setup. The trips are
+/// logged, since the API has no way to list them.
+fn seed_demo_catalog(memory: &Memory) {
+    let location = LocationId(Uuid::now_v7());
+    memory.insert_location(Location {
+        id: location.clone(),
+        name: LocationName("Harbour Beach".to_string()),
+        description: LocationDescription("Launch site next to the harbour wall.".to_string()),
+    });
+
+    let kind = TripKind {
+        id: TripKindId(Uuid::now_v7()),
+        name: "Sea Kayak Tour".to_string(),
+        description: "A guided three hour paddle along the coast.".to_string(),
+        guided: true,
+        meal_provided: false,
+        max_participants: Some(8),
+        waiver: None,
+        eligibility: Eligibility {
+            min_age: Some(8),
+            guardian_required_under: Some(16),
+            ..Eligibility::default()
+        },
+        cancellation_policy: CancellationPolicy {
+            tiers: vec![
+                RefundTier {
+                    notice: chrono::Duration::days(7),
+                    percent: 100,
+                },
+                RefundTier {
+                    notice: chrono::Duration::days(2),
+                    percent: 50,
+                },
+            ],
+        },
+    };
+    for (category, price) in [
+        (ParticipantCategory::Child, Money(4_500)),
+        (ParticipantCategory::Adult, Money(7_500)),
+        (ParticipantCategory::Senior, Money(6_000)),
+    ] {
+        memory.insert_base_price(kind.id.clone(), category, price);
+    }
+
+    memory.insert_equipment(Equipment {
+        id: EquipmentId(Uuid::now_v7()),
+        name: EquipmentName("Wetsuit".to_string()),
+        description: EquipmentDescription("Full length 5mm wetsuit.".to_string()),
+        total_inventory: 12,
+        rental_price: Some(Money(1_500)),
+    });
+
+    let today = Utc::now().date_naive();
+    let start = NaiveTime::from_hms_opt(9, 0, 0).expect("valid time");
+    for day in 1..=7 {
+        let id = TripId(Uuid::now_v7());
+        let start_time = (today + Days::new(day)).and_time(start).and_utc();
+        tracing::info!(trip_id = %id.0, %start_time, "added demo trip");
+        memory.insert_trip(Trip {
+            id,
+            kind: kind.clone(),
+            location: location.clone(),
+            start_time,
+            end_time: start_time + chrono::Duration::hours(3),
+            max_participants: None,
+            timezone: Tz::UTC,
+        });
+    }
+}
@@ -1,29 +1,155 @@
-use anyhow::Context;
+use crate::domain::booking::models::booking::OverbookingPolicy;
+use crate::domain::booking::models::customer::PhoneRegion;
+use anyhow::{bail, Context};
 use std::env;
+use std::net::IpAddr;
+use std::time::Duration;
 
 const SERVER_PORT_KEY: &str = "SERVER_PORT";
 const DB_CONNECTION_KEY: &str = "DB_URL";
+const SQLITE_URL_KEY: &str = "SQLITE_URL";
+const DEFAULT_SQLITE_URL: &str = "sqlite://tide.db";
+const OVERBOOKING_POLICY_KEY: &str = "OVERBOOKING_POLICY";
+const BLOB_STORE_PATH_KEY: &str = "BLOB_STORE_PATH";
+const DEFAULT_BLOB_STORE_PATH: &str = "blobs";
+const FAKE_PAYMENT_DELAY_KEY: &str = "FAKE_PAYMENT_DELAY_MS";
+const HOLD_DURATION_KEY: &str = "HOLD_DURATION_MINUTES";
+const DEFAULT_HOLD_DURATION_MINUTES: i64 = 15;
+const PHONE_REGION_KEY: &str = "DEFAULT_PHONE_REGION";
+const WAITLIST_OFFER_DURATION_KEY: &str = "WAITLIST_OFFER_MINUTES";
+const DEFAULT_WAITLIST_OFFER_DURATION_MINUTES: i64 = 24 * 60;
+/// The longest hold or waitlist offer duration allowed, a day.
+const MAX_DURATION_MINUTES: i64 = 24 * 60;
+const TRUSTED_PROXIES_KEY: &str = "TRUSTED_PROXIES";
+const STORAGE_ARG: &str = "--storage=";
+const NORMALIZE_PHONES_ARG: &str = "--normalize-phones";
 
 /// [Config] contains the necessary application config to run the application.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     pub server_port: String,
-    pub db_url: String,
+    pub storage: Storage,
+    pub overbooking: OverbookingPolicy,
+    pub blob_store_path: String,
+    /// How long the fake payment provider takes to respond to each request.
+    pub fake_payment_delay: Duration,
+    /// How long held bookings reserve their seats and rentals before they must be confirmed.
+    pub hold_duration: chrono::Duration,
+    /// How long waitlisted parties have to book the seats they are offered.
+    pub waitlist_offer_duration: chrono::Duration,
+    /// The country of customer phone numbers entered without a country code.
+    pub phone_region: PhoneRegion,
+    /// The addresses of the reverse proxies trusted to report client addresses in the
+    /// `X-Forwarded-For` header.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Whether to rewrite stored phone numbers in E.164 format and exit, instead of serving.
+    pub normalize_phones: bool,
 }
 
 impl Config {
-    /// Creates a Config from the process environment.
+    /// Creates a Config from the process environment and command line arguments.
     pub fn from_env() -> anyhow::Result<Self> {
         let server_port = load_env(SERVER_PORT_KEY)?;
-        let db_url = load_env(DB_CONNECTION_KEY)?;
+        let storage = env::args().find_map(|arg| arg.strip_prefix(STORAGE_ARG).map(String::from));
+        let storage = match storage.as_deref() {
+            None | Some("postgres") => Storage::Postgres {
+                url: load_env(DB_CONNECTION_KEY)?,
+            },
+            Some("sqlite") => Storage::Sqlite {
+                url: env::var(SQLITE_URL_KEY).unwrap_or_else(|_| DEFAULT_SQLITE_URL.to_string()),
+            },
+            Some("memory") => Storage::Memory,
+            Some(other) => {
+                bail!("--storage must be \"postgres\", \"sqlite\" or \"memory\", got \"{other}\"")
+            }
+        };
+        let normalize_phones = env::args().any(|arg| arg == NORMALIZE_PHONES_ARG);
+        let overbooking = match env::var(OVERBOOKING_POLICY_KEY).as_deref() {
+            Err(_) | Ok("reject") => OverbookingPolicy::Reject,
+            Ok("flag") => OverbookingPolicy::Flag,
+            Ok(other) => {
+                bail!("{OVERBOOKING_POLICY_KEY} must be \"reject\" or \"flag\", got \"{other}\"")
+            }
+        };
+
+        let blob_store_path =
+            env::var(BLOB_STORE_PATH_KEY).unwrap_or_else(|_| DEFAULT_BLOB_STORE_PATH.to_string());
+
+        let fake_payment_delay = match env::var(FAKE_PAYMENT_DELAY_KEY) {
+            Err(_) => Duration::ZERO,
+            Ok(millis) => Duration::from_millis(millis.parse().with_context(|| {
+                format!("{FAKE_PAYMENT_DELAY_KEY} must be a number of milliseconds")
+            })?),
+        };
+
+        let hold_duration = load_minutes(HOLD_DURATION_KEY, DEFAULT_HOLD_DURATION_MINUTES)?;
+        let waitlist_offer_duration = load_minutes(
+            WAITLIST_OFFER_DURATION_KEY,
+            DEFAULT_WAITLIST_OFFER_DURATION_MINUTES,
+        )?;
+
+        let phone_region = match env::var(PHONE_REGION_KEY) {
+            Err(_) => PhoneRegion::default(),
+            Ok(region) => region.parse().with_context(|| {
+                format!("{PHONE_REGION_KEY} must be an ISO 3166-1 alpha-2 country code")
+            })?,
+        };
+
+        let trusted_proxies = match env::var(TRUSTED_PROXIES_KEY) {
+            Err(_) => vec![],
+            Ok(proxies) => proxies
+                .split(',')
+                .map(|proxy| proxy.trim().parse())
+                .collect::<Result<_, _>>()
+                .with_context(|| {
+                    format!("{TRUSTED_PROXIES_KEY} must be a comma-separated list of IP addresses")
+                })?,
+        };
 
         Ok(Self {
             server_port,
-            db_url,
+            storage,
+            overbooking,
+            blob_store_path,
+            fake_payment_delay,
+            hold_duration,
+            waitlist_offer_duration,
+            phone_region,
+            trusted_proxies,
+            normalize_phones,
         })
     }
 }
 
+/// [Storage] selects the outbound adapter the application keeps its data in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Storage {
+    Postgres {
+        url: String,
+    },
+    /// Keeps all data in a single SQLite database file, for sites without a database server.
+    Sqlite {
+        url: String,
+    },
+    /// Keeps all data in process memory, seeded with a demo catalog, and loses it on exit.
+    Memory,
+}
+
 fn load_env(key: &str) -> anyhow::Result<String> {
     env::var(key).with_context(|| format!("failed to load environment variable {key}"))
 }
+
+/// Loads a duration given in minutes, between 1 and [MAX_DURATION_MINUTES].
+fn load_minutes(key: &str, default: i64) -> anyhow::Result<chrono::Duration> {
+    let minutes = match env::var(key) {
+        Err(_) => default,
+        Ok(minutes) => minutes
+            .parse()
+            .with_context(|| format!("{key} must be a number of minutes"))?,
+    };
+    if !(1..=MAX_DURATION_MINUTES).contains(&minutes) {
+        bail!("{key} must be between 1 and {MAX_DURATION_MINUTES} minutes, got {minutes}");
+    }
+
+    Ok(chrono::Duration::minutes(minutes))
+}
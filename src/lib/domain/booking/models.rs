@@ -1,5 +1,9 @@
+pub mod blob;
 pub mod booking;
 pub mod customer;
 pub mod trip;
 pub mod equipment;
-pub mod waiver;
\ No newline at end of file
+pub mod payment;
+pub mod pricing;
+pub mod waitlist;
+pub mod waiver;
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+/// A [BlobKey] identifies a binary object (e.g. a signature image) in a [BlobStore].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlobKey(pub String);
+
+#[derive(Debug, Error)]
+pub enum BlobError {
+    #[error("\"{0}\" is not a valid blob key")]
+    InvalidKey(String),
+    #[error(transparent)]
+    Unknown(anyhow::Error),
+}
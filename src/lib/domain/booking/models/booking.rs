@@ -1,6 +1,13 @@
 use crate::domain::booking::models::customer::CustomerId;
-use crate::domain::booking::models::trip::TripId;
+use crate::domain::booking::models::equipment::EquipmentId;
+use crate::domain::booking::models::pricing::Money;
+use crate::domain::booking::models::trip::{EligibilityViolation, TripId};
+use crate::domain::booking::models::waitlist::WaitlistEntryId;
 use crate::domain::booking::models::waiver::WaiverId;
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
 use uuid::Uuid;
 
 /// A [Booking] represents the intent for a group of [Participant]s to participate in a [Trip].
@@ -8,14 +15,144 @@ use uuid::Uuid;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Booking {
     pub id: BookingId,
+    /// Shared by related bookings, e.g. separate groups booked together by one customer.
+    pub reference: BookingReference,
     pub customer: CustomerId,
     pub trip: TripId,
     pub participants: Vec<Participant>,
+    pub status: BookingStatus,
+    /// When a held booking stops reserving its seats and rentals, if it is held.
+    pub hold_expires_at: Option<DateTime<Utc>>,
+    pub checked_in_at: Option<DateTime<Utc>>,
+    pub cancellation: Option<Cancellation>,
+    /// The waitlist offer whose seats this booking took, if it was made from one.
+    pub waitlist_entry: Option<WaitlistEntryId>,
+    /// Whether the booking was saved over its trip's capacity under [OverbookingPolicy::Flag],
+    /// so staff can find and resolve the overbooking.
+    pub overbooked: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BookingId(pub Uuid);
 
+/// [BookingReference] groups related [Booking]s under a number customers can quote,
+/// like an order number, e.g. `TD-7K3M9QX2AB`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BookingReference(pub String);
+
+impl BookingReference {
+    const PREFIX: &'static str = "TD-";
+    /// Crockford's base32 alphabet, which leaves out letters easily mistaken for digits.
+    const ALPHABET: &'static [u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+    /// Generates a new random reference.
+    pub fn generate() -> Self {
+        let random = Uuid::new_v4().as_u128();
+        let code = (0..10)
+            .map(|i| Self::ALPHABET[(random >> (i * 5)) as usize & 31] as char)
+            .collect::<String>();
+
+        Self(format!("{}{code}", Self::PREFIX))
+    }
+
+    /// Normalizes a reference entered by a customer, which may not be in upper case.
+    pub fn parse(reference: &str) -> Self {
+        Self(reference.trim().to_uppercase())
+    }
+}
+
+impl fmt::Display for BookingReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// [BookingStatus] is the stage of a [Booking]'s lifecycle.
+///
+/// ```text
+/// Held ──> Confirmed ──> CheckedIn ──> Completed
+///   │          ├───────> NoShow
+///   └──────────┴───────> Cancelled
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BookingStatus {
+    /// The booking is tentative, e.g. awaiting payment.
+    #[default]
+    Held,
+    Confirmed,
+    CheckedIn,
+    Completed,
+    NoShow,
+    Cancelled,
+}
+
+impl BookingStatus {
+    /// Whether a booking may move from this status to `next`.
+    pub fn can_transition_to(self, next: BookingStatus) -> bool {
+        use BookingStatus::*;
+
+        matches!(
+            (self, next),
+            (Held, Confirmed)
+                | (Held, Cancelled)
+                | (Confirmed, CheckedIn)
+                | (Confirmed, NoShow)
+                | (Confirmed, Cancelled)
+                | (CheckedIn, Completed)
+        )
+    }
+
+    /// Whether bookings in this status may still be edited.
+    pub fn is_open(self) -> bool {
+        matches!(self, BookingStatus::Held | BookingStatus::Confirmed)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BookingStatus::Held => "held",
+            BookingStatus::Confirmed => "confirmed",
+            BookingStatus::CheckedIn => "checked_in",
+            BookingStatus::Completed => "completed",
+            BookingStatus::NoShow => "no_show",
+            BookingStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for BookingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BookingStatus {
+    type Err = BookingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "held" => Ok(BookingStatus::Held),
+            "confirmed" => Ok(BookingStatus::Confirmed),
+            "checked_in" => Ok(BookingStatus::CheckedIn),
+            "completed" => Ok(BookingStatus::Completed),
+            "no_show" => Ok(BookingStatus::NoShow),
+            "cancelled" => Ok(BookingStatus::Cancelled),
+            other => Err(BookingError::InvalidStatus(other.to_string())),
+        }
+    }
+}
+
+/// The [Cancellation] reason recorded for bookings whose hold expired before being confirmed.
+pub const HOLD_EXPIRED_REASON: &str = "hold expired";
+
+/// A [Cancellation] records why and when a [Booking] was cancelled, and how much of what
+/// the customer paid is owed back to them under the trip's [CancellationPolicy].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cancellation {
+    pub reason: String,
+    pub cancelled_at: DateTime<Utc>,
+    pub refund: Money,
+}
+
 /// A [Participant] is a person who participates in a [Trip] as a member of a [Booking].
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Participant {
@@ -26,28 +163,375 @@ pub struct Participant {
     pub waiver: Option<WaiverId>,
 }
 
+impl Participant {
+    /// The participant's age in whole years on a given date.
+    pub fn age_on(&self, date: chrono::NaiveDate) -> u32 {
+        date.years_since(self.dob).unwrap_or(0)
+    }
+
+    /// Whether two participants, e.g. of different bookings, are the same person.
+    pub fn is_same_person(&self, other: &Participant) -> bool {
+        self.dob == other.dob && self.name.to_lowercase() == other.name.to_lowercase()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ParticipantId(pub Uuid);
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BookingFilters {
+    pub reference: Option<BookingReference>,
     pub customer: Option<CustomerId>,
     pub trip: Option<TripId>,
     pub participant: Option<ParticipantId>,
+    pub status: Option<BookingStatus>,
 }
 
 impl BookingFilters {
     pub fn is_empty(&self) -> bool {
-        self.customer.is_none() && self.trip.is_none() && self.participant.is_none()
+        self.reference.is_none()
+            && self.customer.is_none()
+            && self.trip.is_none()
+            && self.participant.is_none()
+            && self.status.is_none()
     }
 }
 
+/// [OverbookingPolicy] decides what happens when saving a [Booking] would exceed its [Trip]'s capacity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OverbookingPolicy {
+    /// The booking is rejected with [BookingError::TripFull].
+    #[default]
+    Reject,
+    /// The booking is saved anyway and marked as [Booking::overbooked], so staff can resolve
+    /// the overbooking.
+    Flag,
+}
+
+/// [CreateBookingRequest] books a [Trip], optionally taking the seats a [WaitlistEntry]
+/// of the same customer has been offered.
+///
+/// The booking is added to the customer's existing bookings with the given reference,
+/// or gets a new reference if none is given.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CreateBookingRequest {
-    customer_id: CustomerId,
-    trip_id: Uuid,
+    pub reference: Option<BookingReference>,
+    pub customer: CustomerId,
+    pub trip: TripId,
+    pub participants: Vec<CreateParticipantRequest>,
+    pub waitlist_entry: Option<WaitlistEntryId>,
+    /// Whether the booking is [BookingStatus::Held], e.g. during checkout, until it's
+    /// confirmed. Otherwise it is confirmed straight away.
+    pub hold: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateParticipantRequest {
+    pub name: String,
+    pub dob: chrono::NaiveDate,
+    pub notes: String,
 }
 
+impl TryFrom<CreateBookingRequest> for Booking {
+    type Error = BookingError;
+
+    fn try_from(request: CreateBookingRequest) -> Result<Self, Self::Error> {
+        let participants = request
+            .participants
+            .into_iter()
+            .map(|p| {
+                Ok(Participant {
+                    id: ParticipantId(Uuid::now_v7()),
+                    name: participant_name(&p.name)?,
+                    dob: p.dob,
+                    notes: p.notes,
+                    waiver: None,
+                })
+            })
+            .collect::<Result<_, BookingError>>()?;
+
+        Ok(Self {
+            id: BookingId(Uuid::now_v7()),
+            reference: request.reference.unwrap_or_else(BookingReference::generate),
+            customer: request.customer,
+            trip: request.trip,
+            participants,
+            status: if request.hold {
+                BookingStatus::Held
+            } else {
+                BookingStatus::Confirmed
+            },
+            hold_expires_at: None,
+            checked_in_at: None,
+            cancellation: None,
+            waitlist_entry: request.waitlist_entry,
+            overbooked: false,
+        })
+    }
+}
+
+/// [EditBookingRequest] replaces the participants of an existing [Booking].
+///
+/// Participants with an `id` that already belongs to the booking are updated in place
+/// (keeping any signed waiver), and participants without one are added as new.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditBookingRequest {
+    pub id: BookingId,
+    pub participants: Vec<EditParticipantRequest>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditParticipantRequest {
+    pub id: Option<ParticipantId>,
+    pub name: String,
+    pub dob: chrono::NaiveDate,
+    pub notes: String,
+}
+
+/// [RescheduleBookingRequest] moves a [Booking], along with its participants and rentals,
+/// to another [Trip] of a compatible kind.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RescheduleBookingRequest {
+    pub id: BookingId,
+    pub trip: TripId,
+}
+
+/// [CancelBookingRequest] cancels a [Booking], keeping it for reporting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CancelBookingRequest {
+    pub id: BookingId,
+    pub reason: String,
+}
+
+impl Booking {
+    /// Moves this booking to another [BookingStatus], if the lifecycle allows it.
+    pub fn transition(self, to: BookingStatus) -> Result<Self, BookingError> {
+        if !self.status.can_transition_to(to) {
+            return Err(BookingError::InvalidTransition {
+                booking: self.id,
+                from: self.status,
+                to,
+            });
+        }
+
+        Ok(Self {
+            status: to,
+            hold_expires_at: None,
+            ..self
+        })
+    }
+
+    /// Rejects this booking if one of its participants is already on another booking of the
+    /// same trip, given as that booking's ID and participant.
+    ///
+    /// Only open bookings are checked, so closing a booking never fails because of this.
+    pub fn ensure_not_double_booked<'a>(
+        &self,
+        others: impl IntoIterator<Item = (&'a BookingId, &'a Participant)>,
+    ) -> Result<(), BookingError> {
+        if !self.status.is_open() {
+            return Ok(());
+        }
+
+        for (other, participant) in others {
+            if other != &self.id
+                && self
+                    .participants
+                    .iter()
+                    .any(|p| p.is_same_person(participant))
+            {
+                return Err(BookingError::AlreadyBooked {
+                    name: participant.name.clone(),
+                    booking: other.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether this booking is held, but its hold has run out at `at`.
+    pub fn hold_expired(&self, at: DateTime<Utc>) -> bool {
+        self.status == BookingStatus::Held
+            && self.hold_expires_at.is_some_and(|expiry| expiry <= at)
+    }
+
+    /// Applies an [EditBookingRequest] to this booking at `at`, returning the edited booking.
+    ///
+    /// Closed bookings and expired holds can't be edited.
+    pub fn edit(
+        self,
+        request: EditBookingRequest,
+        at: DateTime<Utc>,
+    ) -> Result<Self, BookingError> {
+        if !self.status.is_open() {
+            return Err(BookingError::Closed(self.id, self.status));
+        }
+        if self.hold_expired(at) {
+            return Err(BookingError::HoldExpired(self.id));
+        }
+
+        let participants = request
+            .participants
+            .into_iter()
+            .map(|p| {
+                let waiver = match &p.id {
+                    None => None,
+                    Some(id) => self
+                        .participants
+                        .iter()
+                        .find(|existing| &existing.id == id)
+                        .ok_or_else(|| BookingError::UnknownParticipant(id.clone()))?
+                        .waiver
+                        .clone(),
+                };
+
+                Ok(Participant {
+                    id: p.id.unwrap_or_else(|| ParticipantId(Uuid::now_v7())),
+                    name: participant_name(&p.name)?,
+                    dob: p.dob,
+                    notes: p.notes,
+                    waiver,
+                })
+            })
+            .collect::<Result<_, BookingError>>()?;
+
+        Ok(Self {
+            participants,
+            ..self
+        })
+    }
+}
+
+fn participant_name(name: &str) -> Result<String, BookingError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        Err(BookingError::InvalidParticipantName(trimmed.to_owned()))
+    } else {
+        Ok(trimmed.to_owned())
+    }
+}
+
+#[derive(Debug, Error)]
 pub enum BookingError {
+    #[error("booking {} does not exist", .0.0)]
+    NotFound(BookingId),
+    #[error("customer {} does not exist", .0.0)]
+    CustomerNotFound(CustomerId),
+    #[error("trip {} does not exist", .0.0)]
+    TripNotFound(TripId),
+    #[error("booking {} has payments, so it can't be deleted", .0.0)]
+    HasPayments(BookingId),
+    #[error("booking {} is {} and can no longer be changed", .0.0, .1)]
+    Closed(BookingId, BookingStatus),
+    #[error("the hold on booking {} has expired", .0.0)]
+    HoldExpired(BookingId),
+    #[error("booking {} is no longer {from}", .booking.0)]
+    StatusChanged {
+        booking: BookingId,
+        from: BookingStatus,
+    },
+    #[error("booking {} can't go from {from} to {to}", .booking.0)]
+    InvalidTransition {
+        booking: BookingId,
+        from: BookingStatus,
+        to: BookingStatus,
+    },
+    #[error("\"{0}\" is not a valid booking status")]
+    InvalidStatus(String),
+    #[error("a reason must be given for cancelling a booking")]
+    MissingCancellationReason,
+    #[error("trip {} has already started", .0.0)]
+    TripStarted(TripId),
+    #[error("trip {} hasn't started yet", .0.0)]
+    TripNotStarted(TripId),
+    #[error("trip {} hasn't ended yet", .0.0)]
+    TripNotEnded(TripId),
+    #[error("trip {} only has {available} of {capacity} seats available", .trip.0)]
+    TripFull {
+        trip: TripId,
+        capacity: i32,
+        available: i32,
+    },
+    #[error("customer has no bookings with reference {0}")]
+    UnknownReference(BookingReference),
+    #[error("{name} is already booked on this trip in booking {}", .booking.0)]
+    AlreadyBooked { name: String, booking: BookingId },
+    #[error("trip {} is not compatible with trip {}", .to.0, .from.0)]
+    IncompatibleTrip { from: TripId, to: TripId },
+    #[error("only {available} of equipment {} available on the new trip, but {requested} rented", .equipment.0)]
+    InsufficientInventory {
+        equipment: EquipmentId,
+        requested: i32,
+        available: i32,
+    },
+    #[error("waitlist entry {} has no open offer for this booking", .0.0)]
+    NoWaitlistOffer(WaitlistEntryId),
+    #[error("{requested} participants exceed the {offered} seats offered to waitlist entry {}", .entry.0)]
+    ExceedsWaitlistOffer {
+        entry: WaitlistEntryId,
+        offered: i32,
+        requested: usize,
+    },
+    #[error("participant {} is not part of this booking", .0.0)]
+    UnknownParticipant(ParticipantId),
+    #[error("\"{0}\" is not a valid participant name")]
+    InvalidParticipantName(String),
+    #[error("at least one filter must be provided")]
+    MissingFilters,
+    #[error("{} participant(s) are not eligible for this trip", .0.len())]
+    Ineligible(Vec<EligibilityViolation>),
+    #[error("{} participant(s) must sign the current waiver before checking in", .0.len())]
+    WaiversMissing(Vec<ParticipantId>),
+    #[error(transparent)]
     Unknown(anyhow::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use BookingStatus::*;
+
+    const STATUSES: [BookingStatus; 6] = [Held, Confirmed, CheckedIn, Completed, NoShow, Cancelled];
+
+    #[test]
+    fn can_transition_to_follows_the_lifecycle() {
+        let allowed = [
+            (Held, Confirmed),
+            (Held, Cancelled),
+            (Confirmed, CheckedIn),
+            (Confirmed, NoShow),
+            (Confirmed, Cancelled),
+            (CheckedIn, Completed),
+        ];
+
+        for from in STATUSES {
+            for to in STATUSES {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    allowed.contains(&(from, to)),
+                    "{from} -> {to}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn closed_statuses_are_final() {
+        for from in [Completed, NoShow, Cancelled] {
+            assert!(!from.is_open());
+            assert!(STATUSES.iter().all(|to| !from.can_transition_to(*to)));
+        }
+    }
+
+    #[test]
+    fn statuses_round_trip_through_strings() {
+        for status in STATUSES {
+            assert_eq!(status.as_str().parse::<BookingStatus>().unwrap(), status);
+        }
+        assert!(matches!(
+            "pending".parse::<BookingStatus>(),
+            Err(BookingError::InvalidStatus(s)) if s == "pending"
+        ));
+    }
+}
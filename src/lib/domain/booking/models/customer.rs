@@ -1,3 +1,7 @@
+use crate::domain::booking::models::booking::BookingId;
+use crate::domain::booking::models::waitlist::WaitlistEntryId;
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -36,51 +40,90 @@ pub struct EmailAddress(pub String);
 impl TryFrom<&String> for EmailAddress {
     type Error = CustomerError;
 
+    /// Parses an RFC 5322 address (without a display name), lower-casing the domain.
+    ///
+    /// The local part is kept as given, but no two customers may have addresses
+    /// that only differ by case.
     fn try_from(str: &String) -> Result<Self, Self::Error> {
         let trimmed = str.trim();
-        if trimmed.len() <= 2 {
-            Err(CustomerError::InvalidEmail(trimmed.to_owned()))
-        } else {
-            Ok(Self(trimmed.to_owned()))
-        }
+        let options = email_address::Options::default()
+            .with_required_tld()
+            .without_domain_literal()
+            .without_display_text();
+        let address = email_address::EmailAddress::parse_with_options(trimmed, options)
+            .map_err(|_| CustomerError::InvalidEmail(trimmed.to_owned()))?;
+
+        Ok(Self(format!(
+            "{}@{}",
+            address.local_part(),
+            address.domain().to_lowercase()
+        )))
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PhoneNumber(pub String);
 
-impl TryFrom<&String> for PhoneNumber {
-    type Error = CustomerError;
-
-    fn try_from(str: &String) -> Result<Self, Self::Error> {
+impl PhoneNumber {
+    /// Parses a phone number into E.164 format, e.g. `+14155550123`.
+    ///
+    /// Numbers without a leading `+` and country code are assumed to be from `region`.
+    pub fn parse(str: &str, region: PhoneRegion) -> Result<Self, CustomerError> {
         let trimmed = str.trim();
-        if trimmed.len() <= 2 {
-            Err(CustomerError::InvalidPhone(trimmed.to_owned()))
-        } else {
-            Ok(Self(trimmed.to_owned()))
-        }
+        let number = phonenumber::parse(Some(region.0), trimmed)
+            .ok()
+            .filter(phonenumber::is_valid)
+            .ok_or_else(|| CustomerError::InvalidPhone(trimmed.to_owned()))?;
+
+        Ok(Self(number.format().mode(phonenumber::Mode::E164).to_string()))
+    }
+}
+
+/// [PhoneRegion] is the country that phone numbers entered without a country code are from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhoneRegion(pub phonenumber::country::Id);
+
+impl Default for PhoneRegion {
+    fn default() -> Self {
+        Self(phonenumber::country::Id::US)
     }
 }
 
+impl FromStr for PhoneRegion {
+    type Err = CustomerError;
+
+    /// Parses an ISO 3166-1 alpha-2 country code, e.g. `US` or `GB`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .to_uppercase()
+            .parse()
+            .map(Self)
+            .map_err(|_| CustomerError::InvalidPhoneRegion(s.to_owned()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CreateCustomerRequest {
     pub name: String,
     pub email: String,
     pub phone: String,
 }
 
-impl TryFrom<CreateCustomerRequest> for Customer {
-    type Error = CustomerError;
-
-    fn try_from(request: CreateCustomerRequest) -> Result<Self, Self::Error> {
-        Ok(Self{
+impl Customer {
+    /// Creates a new customer from a [CreateCustomerRequest], reading phone numbers
+    /// without a country code as being from `region`.
+    pub fn new(request: CreateCustomerRequest, region: PhoneRegion) -> Result<Self, CustomerError> {
+        Ok(Self {
             id: CustomerId(Uuid::now_v7()),
             name: CustomerName::try_from(&request.name)?,
             email: EmailAddress::try_from(&request.email)?,
-            phone: PhoneNumber::try_from(&request.phone)?,
+            phone: PhoneNumber::parse(&request.phone, region)?,
         })
     }
 }
 
+/// [EditCustomerRequest] partially updates a [Customer]; fields left as [None] are unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EditCustomerRequest {
     pub id: CustomerId,
     pub name: Option<String>,
@@ -88,8 +131,207 @@ pub struct EditCustomerRequest {
     pub phone: Option<String>,
 }
 
+impl Customer {
+    /// Applies an [EditCustomerRequest] to this customer, validating only the fields being changed.
+    pub fn edit(
+        self,
+        request: EditCustomerRequest,
+        region: PhoneRegion,
+    ) -> Result<Self, CustomerError> {
+        Ok(Self {
+            name: match &request.name {
+                Some(name) => CustomerName::try_from(name)?,
+                None => self.name,
+            },
+            email: match &request.email {
+                Some(email) => EmailAddress::try_from(email)?,
+                None => self.email,
+            },
+            phone: match &request.phone {
+                Some(phone) => PhoneNumber::parse(phone, region)?,
+                None => self.phone,
+            },
+            ..self
+        })
+    }
+}
+
+/// [Page] selects a window of a list of results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Page {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Page {
+    pub const DEFAULT_LIMIT: i64 = 20;
+    pub const MAX_LIMIT: i64 = 100;
+
+    /// Validates a requested page, defaulting to the first [Page::DEFAULT_LIMIT] results.
+    pub fn new(limit: Option<i64>, offset: Option<i64>) -> Result<Self, CustomerError> {
+        let limit = limit.unwrap_or(Self::DEFAULT_LIMIT);
+        if !(1..=Self::MAX_LIMIT).contains(&limit) {
+            return Err(CustomerError::InvalidPageLimit(limit));
+        }
+        let offset = offset.unwrap_or(0);
+        if offset < 0 {
+            return Err(CustomerError::InvalidPageOffset(offset));
+        }
+
+        Ok(Self { limit, offset })
+    }
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            limit: Self::DEFAULT_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+/// [CustomerSearch] finds customers whose name, email address or phone number
+/// resembles a query, e.g. as typed by front-desk staff.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomerSearch {
+    pub query: String,
+    /// The digits to look for in phone numbers, if the query looks like (part of) one.
+    pub phone_digits: Option<String>,
+    pub page: Page,
+}
+
+impl CustomerSearch {
+    pub const MIN_QUERY_LEN: usize = 2;
+    /// The fewest digits worth matching against phone numbers.
+    pub const MIN_PHONE_DIGITS: usize = 4;
+
+    /// Creates a search for `query`, reading phone numbers without a country code
+    /// as being from `region`.
+    pub fn new(query: &str, region: PhoneRegion, page: Page) -> Result<Self, CustomerError> {
+        let query = query.trim();
+        if query.chars().count() < Self::MIN_QUERY_LEN {
+            return Err(CustomerError::SearchTooShort(query.to_owned()));
+        }
+
+        // A full number is matched in E.164 format, so national prefixes don't get in the way.
+        let phone_digits = match PhoneNumber::parse(query, region) {
+            Ok(phone) => phone.0.trim_start_matches('+').to_owned(),
+            Err(_) => query.chars().filter(char::is_ascii_digit).collect(),
+        };
+
+        Ok(Self {
+            query: query.to_owned(),
+            phone_digits: (phone_digits.len() >= Self::MIN_PHONE_DIGITS).then_some(phone_digits),
+            page,
+        })
+    }
+}
+
+/// [CustomerSearchResults] is a page of customers matching a [CustomerSearch], best match first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomerSearchResults {
+    pub customers: Vec<Customer>,
+    /// The number of matches across all pages.
+    pub total: i64,
+}
+
+/// [PhoneNormalization] reports which stored phone numbers were rewritten in E.164 format.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PhoneNormalization {
+    /// The customers whose phone numbers were rewritten.
+    pub normalized: Vec<CustomerId>,
+    /// The customers whose phone numbers aren't valid, and were left as they were.
+    pub invalid: Vec<CustomerId>,
+}
+
+/// [DuplicateCandidate] is a pair of customers who likely represent the same person.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateCandidate {
+    pub first: Customer,
+    pub second: Customer,
+    /// Why the pair was flagged; never empty.
+    pub reasons: Vec<DuplicateReason>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DuplicateReason {
+    SamePhone,
+    SimilarName,
+    /// The email addresses only differ by domain or case, e.g. `jo@gmail.com` and `Jo@work.com`.
+    /// Common user names make this weak evidence on its own, so it only backs up another reason.
+    SameEmailUser,
+}
+
+impl DuplicateReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DuplicateReason::SamePhone => "same_phone",
+            DuplicateReason::SimilarName => "similar_name",
+            DuplicateReason::SameEmailUser => "same_email_user",
+        }
+    }
+}
+
+/// [MergeCustomersRequest] merges one customer into another, e.g. a [DuplicateCandidate].
+///
+/// The surviving customer keeps its own details unless told to take the merged customer's.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeCustomersRequest {
+    pub survivor: CustomerId,
+    pub merged: CustomerId,
+    pub use_merged_name: bool,
+    pub use_merged_email: bool,
+    pub use_merged_phone: bool,
+}
+
+impl Customer {
+    /// Takes the details of `merged` chosen by a [MergeCustomersRequest].
+    pub fn absorb(self, merged: &Customer, request: &MergeCustomersRequest) -> Self {
+        Self {
+            name: if request.use_merged_name {
+                merged.name.clone()
+            } else {
+                self.name
+            },
+            email: if request.use_merged_email {
+                merged.email.clone()
+            } else {
+                self.email
+            },
+            phone: if request.use_merged_phone {
+                merged.phone.clone()
+            } else {
+                self.phone
+            },
+            ..self
+        }
+    }
+}
+
+/// A [CustomerMerge] records that a customer was merged into another, and deleted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomerMerge {
+    pub id: CustomerMergeId,
+    pub survivor: CustomerId,
+    /// The survivor as it was just before the merge, if it was recorded.
+    pub survivor_before: Option<Customer>,
+    /// The merged customer as it was just before the merge.
+    pub merged: Customer,
+    /// The bookings moved to the survivor.
+    pub bookings: Vec<BookingId>,
+    /// The waitlist entries moved to the survivor.
+    pub waitlist_entries: Vec<WaitlistEntryId>,
+    pub merged_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CustomerMergeId(pub Uuid);
+
 #[derive(Debug, Error)]
 pub enum CustomerError {
+    #[error("customer {} does not exist", .0.0)]
+    NotFound(CustomerId),
     #[error("\"{0}\" is not a valid name")]
     InvalidName(String),
     #[error("\"{0}\" is not a valid email address")]
@@ -98,6 +340,100 @@ pub enum CustomerError {
     EmailTaken(String),
     #[error("\"{0}\" is not a valid phone number")]
     InvalidPhone(String),
+    #[error("\"{0}\" is not a valid phone region")]
+    InvalidPhoneRegion(String),
+    #[error("\"{0}\" is too short to search for")]
+    SearchTooShort(String),
+    #[error("{0} is not a valid page limit")]
+    InvalidPageLimit(i64),
+    #[error("{0} is not a valid page offset")]
+    InvalidPageOffset(i64),
+    #[error("customer {} can't be merged into itself", .0.0)]
+    MergeIntoSelf(CustomerId),
     #[error(transparent)]
     Unknown(anyhow::Error)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email(str: &str) -> Result<EmailAddress, CustomerError> {
+        EmailAddress::try_from(&str.to_string())
+    }
+
+    fn region(code: &str) -> PhoneRegion {
+        code.parse().unwrap()
+    }
+
+    #[test]
+    fn emails_keep_the_local_part_and_lower_case_the_domain() {
+        assert_eq!(
+            email("  Ana.Reyes@Example.COM ").unwrap(),
+            EmailAddress("Ana.Reyes@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn emails_must_be_bare_addresses_on_a_named_domain() {
+        for invalid in [
+            "Ana Reyes <ana@example.com>",
+            "ana@[192.168.0.1]",
+            "ana@localhost",
+            "ana.example.com",
+            "",
+        ] {
+            assert!(
+                matches!(email(invalid), Err(CustomerError::InvalidEmail(_))),
+                "{invalid:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn phones_without_a_country_code_are_from_the_region() {
+        let us = PhoneRegion::default();
+        assert_eq!(
+            PhoneNumber::parse("(650) 253-0000", us).unwrap(),
+            PhoneNumber("+16502530000".to_string())
+        );
+        assert_eq!(
+            PhoneNumber::parse("020 7031 3000", region("gb")).unwrap(),
+            PhoneNumber("+442070313000".to_string())
+        );
+    }
+
+    #[test]
+    fn phones_with_a_country_code_ignore_the_region() {
+        assert_eq!(
+            PhoneNumber::parse(" +44 20 7031 3000 ", PhoneRegion::default()).unwrap(),
+            PhoneNumber("+442070313000".to_string())
+        );
+        assert_eq!(
+            PhoneNumber::parse("+1 650 253 0000", region("GB")).unwrap(),
+            PhoneNumber("+16502530000".to_string())
+        );
+    }
+
+    #[test]
+    fn phones_must_be_valid_numbers() {
+        for invalid in [
+            "",
+            "not a number",
+            "123",
+            "+1 650 253 00001",
+            "+999 1234 5678",
+        ] {
+            assert!(
+                matches!(
+                    PhoneNumber::parse(invalid, PhoneRegion::default()),
+                    Err(CustomerError::InvalidPhone(_))
+                ),
+                "{invalid:?} should be rejected"
+            );
+        }
+        assert!(matches!(
+            "XX".parse::<PhoneRegion>(),
+            Err(CustomerError::InvalidPhoneRegion(_))
+        ));
+    }
+}
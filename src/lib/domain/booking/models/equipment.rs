@@ -1,6 +1,9 @@
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use thiserror::Error;
 use uuid::Uuid;
 use crate::domain::booking::models::booking::BookingId;
+use crate::domain::booking::models::pricing::Money;
 
 /// [Equipment] is an item that is included with (or can be rented for) a [Booking].
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -8,6 +11,10 @@ pub struct Equipment {
     pub id: EquipmentId,
     pub name: EquipmentName,
     pub description: EquipmentDescription,
+    /// The number of units the company owns, shared across all overlapping trips.
+    pub total_inventory: i32,
+    /// The price of renting one unit for a single trip, or [None] if it isn't for rent.
+    pub rental_price: Option<Money>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -25,6 +32,31 @@ pub struct BookingRentals {
     pub rentals: HashMap<EquipmentId, i32>
 }
 
+/// [EquipmentAvailability] is the number of units of each [Equipment] that are not
+/// rented out to any trip overlapping a given time window.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EquipmentAvailability {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub available: HashMap<EquipmentId, i32>,
+}
+
+#[derive(Debug, Error)]
 pub enum EquipmentError {
+    #[error("booking {} does not exist", .0.0)]
+    BookingNotFound(BookingId),
+    #[error("{quantity} is not a valid rental quantity for equipment {}", .equipment.0)]
+    InvalidQuantity { equipment: EquipmentId, quantity: i32 },
+    #[error("equipment {} does not exist", .0.0)]
+    NotFound(EquipmentId),
+    #[error("only {available} of equipment {} available, but {requested} requested", .equipment.0)]
+    InsufficientInventory {
+        equipment: EquipmentId,
+        requested: i32,
+        available: i32,
+    },
+    #[error("time window must not end before it starts")]
+    InvalidTimeWindow,
+    #[error(transparent)]
     Unknown(anyhow::Error),
 }
\ No newline at end of file
@@ -0,0 +1,132 @@
+use crate::domain::booking::models::booking::BookingId;
+use crate::domain::booking::models::pricing::Money;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A [Payment] is an entry in a [Booking]'s payment ledger, recording a single
+/// authorization, capture or refund attempt made with the payment provider.
+///
+/// Entries are never updated; the amount paid for a booking is derived from its ledger.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Payment {
+    pub id: PaymentId,
+    pub booking: BookingId,
+    pub kind: PaymentKind,
+    /// The entry this one settles: the authorization of a capture, or the capture of a refund.
+    pub parent: Option<PaymentId>,
+    pub amount: Money,
+    pub status: PaymentStatus,
+    /// The provider's reference for the operation, if it succeeded.
+    pub reference: Option<ProviderReference>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PaymentId(pub Uuid);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PaymentKind {
+    /// Funds are held on the customer's payment method, but not yet collected.
+    Authorization,
+    /// Some or all of an authorization is collected, e.g. as a deposit or full payment.
+    Capture,
+    /// Some or all of a capture is returned to the customer.
+    Refund,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PaymentStatus {
+    Succeeded,
+    Declined(String),
+}
+
+/// [ProviderReference] identifies an operation with the payment provider.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProviderReference(pub String);
+
+/// [PaymentMethod] is an opaque token for the customer's card (or other method),
+/// issued by the payment provider to the client.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PaymentMethod(pub String);
+
+/// [PaymentLedger] is every [Payment] made for a booking, oldest first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymentLedger {
+    pub booking: BookingId,
+    pub payments: Vec<Payment>,
+}
+
+impl PaymentLedger {
+    /// The amount of a successful entry that hasn't been settled by its children yet,
+    /// i.e. the uncaptured part of an authorization or the unrefunded part of a capture.
+    pub fn remaining(&self, payment: &Payment) -> Money {
+        let settled = self
+            .succeeded()
+            .filter(|p| p.parent.as_ref() == Some(&payment.id))
+            .map(|p| p.amount)
+            .sum::<Money>();
+
+        Money(payment.amount.0 - settled.0)
+    }
+
+    /// The net amount collected from the customer so far.
+    pub fn paid(&self) -> Money {
+        self.succeeded()
+            .map(|p| match p.kind {
+                PaymentKind::Authorization => Money::default(),
+                PaymentKind::Capture => p.amount,
+                PaymentKind::Refund => Money(-p.amount.0),
+            })
+            .sum()
+    }
+
+    fn succeeded(&self) -> impl Iterator<Item = &Payment> {
+        self.payments
+            .iter()
+            .filter(|p| p.status == PaymentStatus::Succeeded)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthorizePaymentRequest {
+    pub booking: BookingId,
+    pub amount: Money,
+    pub method: PaymentMethod,
+}
+
+/// [CapturePaymentRequest] captures an authorization, in full if no amount is given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapturePaymentRequest {
+    pub authorization: PaymentId,
+    pub amount: Option<Money>,
+}
+
+/// [RefundPaymentRequest] refunds a capture, in full if no amount is given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefundPaymentRequest {
+    pub capture: PaymentId,
+    pub amount: Option<Money>,
+}
+
+#[derive(Debug, Error)]
+pub enum PaymentError {
+    #[error("booking {} does not exist", .0.0)]
+    BookingNotFound(BookingId),
+    #[error("payment {} does not exist", .0.0)]
+    NotFound(PaymentId),
+    #[error("{} is not a valid payment amount", .0.0)]
+    InvalidAmount(Money),
+    #[error("payment {} can't be {}", .0.0, .1)]
+    InvalidOperation(PaymentId, &'static str),
+    #[error("only {} of payment {} remains, but {} requested", .remaining.0, .payment.0, .requested.0)]
+    ExceedsRemaining {
+        payment: PaymentId,
+        requested: Money,
+        remaining: Money,
+    },
+    #[error("payment was declined: {0}")]
+    Declined(String),
+    #[error(transparent)]
+    Unknown(anyhow::Error),
+}
@@ -0,0 +1,418 @@
+use crate::domain::booking::models::booking::CreateParticipantRequest;
+use crate::domain::booking::models::equipment::EquipmentId;
+use crate::domain::booking::models::trip::{Trip, TripId, TripKindId};
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::HashMap;
+use std::iter::Sum;
+use std::ops::{Add, Mul};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Participants younger than this (on the date of the trip) are priced as children.
+pub const CHILD_UNDER_AGE: u32 = 13;
+/// Participants this age or older (on the date of the trip) are priced as seniors.
+pub const SENIOR_FROM_AGE: u32 = 65;
+
+/// [Money] is an amount in the smallest unit of the company's currency (e.g. cents).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(pub i64);
+
+impl Money {
+    /// The given percentage of this amount, rounded half away from zero.
+    pub fn percent(self, percent: i32) -> Self {
+        let scaled = self.0 * i64::from(percent);
+        Self(scaled.signum() * ((scaled.abs() + 50) / 100))
+    }
+}
+
+impl Add for Money {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Mul<i32> for Money {
+    type Output = Self;
+
+    fn mul(self, quantity: i32) -> Self {
+        Self(self.0 * i64::from(quantity))
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// [ParticipantCategory] decides which base price of a [TripKind] a participant pays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParticipantCategory {
+    Child,
+    Adult,
+    Senior,
+}
+
+impl ParticipantCategory {
+    /// The category of a participant with the given date of birth on the date of a trip.
+    pub fn of(dob: NaiveDate, trip_date: NaiveDate) -> Self {
+        match trip_date.years_since(dob).unwrap_or(0) {
+            age if age < CHILD_UNDER_AGE => Self::Child,
+            age if age >= SENIOR_FROM_AGE => Self::Senior,
+            _ => Self::Adult,
+        }
+    }
+}
+
+/// A [PriceModifier] adjusts the price of trips taking place in certain months
+/// (e.g. a peak season) or on certain days of the week.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PriceModifier {
+    pub id: PriceModifierId,
+    pub name: String,
+    /// The [TripKind] this modifier is limited to, or [None] if it applies to every kind.
+    pub trip_kind: Option<TripKindId>,
+    /// Months (1-12) the modifier applies in. Empty means every month.
+    pub months: Vec<u32>,
+    /// Days of the week the modifier applies on. Empty means every day.
+    pub weekdays: Vec<Weekday>,
+    /// The adjustment as a percentage of the subtotal, e.g. `20` for a 20% surcharge
+    /// or `-10` for a 10% discount.
+    pub percent: i32,
+}
+
+impl PriceModifier {
+    /// Whether this modifier applies to a trip of the given kind on the given date.
+    pub fn applies_to(&self, trip_kind: &TripKindId, date: NaiveDate) -> bool {
+        self.trip_kind.as_ref().is_none_or(|kind| kind == trip_kind)
+            && (self.months.is_empty() || self.months.contains(&date.month()))
+            && (self.weekdays.is_empty() || self.weekdays.contains(&date.weekday()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PriceModifierId(pub Uuid);
+
+/// A [PriceList] holds everything needed to price bookings on trips of one [TripKind].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PriceList {
+    pub base_prices: HashMap<ParticipantCategory, Money>,
+    /// Per-trip rental prices of every [Equipment] that may be rented.
+    pub rentals: HashMap<EquipmentId, Money>,
+    pub modifiers: Vec<PriceModifier>,
+}
+
+impl PriceList {
+    /// Prices a prospective booking on a trip.
+    ///
+    /// Every modifier that applies to the trip adjusts the subtotal of all line items,
+    /// and the total never drops below zero.
+    pub fn quote(&self, trip: &Trip, request: &QuoteRequest) -> Result<Quote, PricingError> {
+        let trip_date = trip.local_date();
+
+        let participants = request.participants.iter().map(|p| {
+            let category = ParticipantCategory::of(p.dob, trip_date);
+            let price =
+                self.base_prices.get(&category).copied().ok_or_else(|| {
+                    PricingError::MissingBasePrice(trip.kind.id.clone(), category)
+                })?;
+
+            Ok(QuoteLine {
+                item: QuoteItem::Participant {
+                    name: p.name.clone(),
+                    category,
+                },
+                unit_price: price,
+                quantity: 1,
+                amount: price,
+            })
+        });
+
+        let mut rentals = request.rentals.iter().collect::<Vec<_>>();
+        rentals.sort();
+        let rentals = rentals.into_iter().map(|(equipment, &quantity)| {
+            if quantity <= 0 {
+                return Err(PricingError::InvalidQuantity {
+                    equipment: equipment.clone(),
+                    quantity,
+                });
+            }
+            let price = self
+                .rentals
+                .get(equipment)
+                .copied()
+                .ok_or_else(|| PricingError::NotRentable(equipment.clone()))?;
+
+            Ok(QuoteLine {
+                item: QuoteItem::Rental(equipment.clone()),
+                unit_price: price,
+                quantity,
+                amount: price * quantity,
+            })
+        });
+
+        let lines = participants
+            .chain(rentals)
+            .collect::<Result<Vec<_>, PricingError>>()?;
+        let subtotal = lines.iter().map(|line| line.amount).sum::<Money>();
+
+        let adjustments = self
+            .modifiers
+            .iter()
+            .filter(|m| m.applies_to(&trip.kind.id, trip_date))
+            .map(|m| QuoteAdjustment {
+                modifier: m.id.clone(),
+                name: m.name.clone(),
+                percent: m.percent,
+                amount: subtotal.percent(m.percent),
+            })
+            .collect::<Vec<_>>();
+        let total = subtotal + adjustments.iter().map(|a| a.amount).sum::<Money>();
+
+        Ok(Quote {
+            trip: trip.id.clone(),
+            lines,
+            subtotal,
+            adjustments,
+            total: total.max(Money::default()),
+            requirements: trip.kind.eligibility.requirements.clone(),
+        })
+    }
+}
+
+/// [QuoteRequest] describes a prospective booking to be priced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuoteRequest {
+    pub trip: TripId,
+    pub participants: Vec<CreateParticipantRequest>,
+    pub rentals: HashMap<EquipmentId, i32>,
+}
+
+/// A [Quote] is the itemized price of a prospective booking.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quote {
+    pub trip: TripId,
+    pub lines: Vec<QuoteLine>,
+    pub subtotal: Money,
+    pub adjustments: Vec<QuoteAdjustment>,
+    pub total: Money,
+    /// The trip's free-form [Eligibility](super::trip::Eligibility) requirements, which
+    /// participants are expected to meet but aren't checked.
+    pub requirements: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuoteLine {
+    pub item: QuoteItem,
+    pub unit_price: Money,
+    pub quantity: i32,
+    pub amount: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuoteItem {
+    Participant {
+        name: String,
+        category: ParticipantCategory,
+    },
+    Rental(EquipmentId),
+}
+
+/// A [QuoteAdjustment] is the effect of a single [PriceModifier] on a [Quote].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuoteAdjustment {
+    pub modifier: PriceModifierId,
+    pub name: String,
+    pub percent: i32,
+    pub amount: Money,
+}
+
+#[derive(Debug, Error)]
+pub enum PricingError {
+    #[error("trip {} does not exist", .0.0)]
+    TripNotFound(TripId),
+    #[error("trip kind {} has no {:?} price", .0.0, .1)]
+    MissingBasePrice(TripKindId, ParticipantCategory),
+    #[error("equipment {} is not available for rent", .0.0)]
+    NotRentable(EquipmentId),
+    #[error("{quantity} is not a valid rental quantity for equipment {}", .equipment.0)]
+    InvalidQuantity {
+        equipment: EquipmentId,
+        quantity: i32,
+    },
+    #[error(transparent)]
+    Unknown(anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::booking::models::trip::{Eligibility, LocationId, TripKind};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn trip(start_time: DateTime<Utc>, timezone: Tz) -> Trip {
+        Trip {
+            id: TripId(Uuid::now_v7()),
+            kind: TripKind {
+                id: TripKindId(Uuid::now_v7()),
+                name: "Sea Kayak Tour".to_string(),
+                description: String::new(),
+                guided: true,
+                meal_provided: false,
+                max_participants: None,
+                waiver: None,
+                eligibility: Eligibility::default(),
+                cancellation_policy: Default::default(),
+            },
+            location: LocationId(Uuid::now_v7()),
+            start_time,
+            end_time: start_time + Duration::hours(3),
+            max_participants: None,
+            timezone,
+        }
+    }
+
+    fn modifier(percent: i32) -> PriceModifier {
+        PriceModifier {
+            id: PriceModifierId(Uuid::now_v7()),
+            name: format!("{percent}%"),
+            trip_kind: None,
+            months: vec![],
+            weekdays: vec![],
+            percent,
+        }
+    }
+
+    fn quote_request(trip: &Trip, dob: NaiveDate) -> QuoteRequest {
+        QuoteRequest {
+            trip: trip.id.clone(),
+            participants: vec![CreateParticipantRequest {
+                name: "Ana Reyes".to_string(),
+                dob,
+                notes: String::new(),
+            }],
+            rentals: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn percent_rounds_half_away_from_zero() {
+        assert_eq!(Money(1_000).percent(15), Money(150));
+        assert_eq!(Money(125).percent(10), Money(13));
+        assert_eq!(Money(124).percent(10), Money(12));
+        assert_eq!(Money(125).percent(-10), Money(-13));
+        assert_eq!(Money(-125).percent(10), Money(-13));
+        assert_eq!(Money(5).percent(-10), Money(-1));
+        assert_eq!(Money(4).percent(-10), Money(0));
+        assert_eq!(Money(1_000).percent(0), Money(0));
+    }
+
+    #[test]
+    fn categories_change_on_the_13th_and_65th_birthdays() {
+        let dob = date(2000, 6, 15);
+
+        assert_eq!(
+            ParticipantCategory::of(dob, date(2013, 6, 14)),
+            ParticipantCategory::Child
+        );
+        assert_eq!(
+            ParticipantCategory::of(dob, date(2013, 6, 15)),
+            ParticipantCategory::Adult
+        );
+        assert_eq!(
+            ParticipantCategory::of(dob, date(2065, 6, 14)),
+            ParticipantCategory::Adult
+        );
+        assert_eq!(
+            ParticipantCategory::of(dob, date(2065, 6, 15)),
+            ParticipantCategory::Senior
+        );
+    }
+
+    #[test]
+    fn modifiers_apply_to_their_kind_months_and_weekdays() {
+        let kind = TripKindId(Uuid::now_v7());
+        let other_kind = TripKindId(Uuid::now_v7());
+        // A Saturday in July.
+        let saturday = date(2026, 7, 4);
+        let monday = date(2026, 7, 6);
+
+        let everywhere = modifier(10);
+        assert!(everywhere.applies_to(&kind, saturday));
+        assert!(everywhere.applies_to(&other_kind, monday));
+
+        let peak_weekends = PriceModifier {
+            trip_kind: Some(kind.clone()),
+            months: vec![6, 7, 8],
+            weekdays: vec![Weekday::Sat, Weekday::Sun],
+            ..modifier(20)
+        };
+        assert!(peak_weekends.applies_to(&kind, saturday));
+        assert!(!peak_weekends.applies_to(&kind, monday));
+        assert!(!peak_weekends.applies_to(&kind, date(2026, 10, 3)));
+        assert!(!peak_weekends.applies_to(&other_kind, saturday));
+    }
+
+    #[test]
+    fn quote_totals_never_drop_below_zero() {
+        let trip = trip(Utc::now() + Duration::days(1), Tz::UTC);
+        let prices = PriceList {
+            base_prices: HashMap::from([(ParticipantCategory::Adult, Money(1_000))]),
+            rentals: HashMap::new(),
+            modifiers: vec![modifier(-60), modifier(-60)],
+        };
+
+        let quote = prices
+            .quote(&trip, &quote_request(&trip, date(1990, 1, 1)))
+            .unwrap();
+        assert_eq!(quote.subtotal, Money(1_000));
+        assert_eq!(
+            quote
+                .adjustments
+                .iter()
+                .map(|a| a.amount)
+                .collect::<Vec<_>>(),
+            vec![Money(-600), Money(-600)]
+        );
+        assert_eq!(quote.total, Money(0));
+    }
+
+    #[test]
+    fn quotes_go_by_the_trips_local_date() {
+        // Saturday evening in UTC is already Sunday morning in Auckland.
+        let start_time = Utc.with_ymd_and_hms(2026, 7, 4, 23, 30, 0).unwrap();
+        let trip = trip(start_time, Tz::Pacific__Auckland);
+        let prices = PriceList {
+            base_prices: HashMap::from([
+                (ParticipantCategory::Child, Money(500)),
+                (ParticipantCategory::Adult, Money(1_000)),
+            ]),
+            rentals: HashMap::new(),
+            modifiers: vec![PriceModifier {
+                weekdays: vec![Weekday::Sun],
+                ..modifier(10)
+            }],
+        };
+
+        // Turns 13 on the local date of the trip, but not on its date in UTC.
+        let quote = prices
+            .quote(&trip, &quote_request(&trip, date(2013, 7, 5)))
+            .unwrap();
+        assert_eq!(
+            quote.lines[0].item,
+            QuoteItem::Participant {
+                name: "Ana Reyes".to_string(),
+                category: ParticipantCategory::Adult,
+            }
+        );
+        assert_eq!(quote.adjustments.len(), 1);
+        assert_eq!(quote.total, Money(1_100));
+    }
+}
@@ -1,14 +1,41 @@
-use chrono::{DateTime, Utc};
+use crate::domain::booking::models::booking::{Participant, ParticipantId};
+use crate::domain::booking::models::waiver::WaiverId;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use thiserror::Error;
 use uuid::Uuid;
 
 /// A [Trip] is a scheduled/available [TripKind] that customers may make bookings for.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Trip {
     pub id: TripId,
     pub kind: TripKind,
     pub location: LocationId,
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
+    /// Overrides the [TripKind]'s maximum participant count for this trip only.
+    pub max_participants: Option<i32>,
+    /// The time zone the trip's local date is in, e.g. that of its schedule.
+    pub timezone: Tz,
+}
+
+impl Trip {
+    /// The local date the trip starts on, which seasonal prices and ages go by.
+    pub fn local_date(&self) -> NaiveDate {
+        self.start_time.with_timezone(&self.timezone).date_naive()
+    }
+
+    /// The maximum number of participants that may be booked on this trip,
+    /// or [None] if the trip has no limit.
+    pub fn capacity(&self) -> Option<i32> {
+        self.max_participants.or(self.kind.max_participants)
+    }
+
+    /// Whether bookings on this trip may be moved to `other`. Only trips of the same
+    /// [TripKind] share the prices, waiver and policies the booking was made under.
+    pub fn is_compatible_with(&self, other: &Trip) -> bool {
+        self.kind.id == other.kind.id
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -22,6 +49,120 @@ pub struct TripKind {
     pub description: String,
     pub guided: bool,
     pub meal_provided: bool,
+    /// The default maximum participant count for trips of this kind.
+    pub max_participants: Option<i32>,
+    /// The [Waiver] every participant must sign before checking in, if any.
+    pub waiver: Option<WaiverId>,
+    pub eligibility: Eligibility,
+    pub cancellation_policy: CancellationPolicy,
+}
+
+/// A [CancellationPolicy] decides how much of what a customer paid is refunded when
+/// their booking is cancelled, based on how long before the trip starts they cancel.
+///
+/// The tier with the longest notice that the cancellation satisfies applies.
+/// Cancellations that satisfy no tier, or happen after the trip starts, aren't refunded.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CancellationPolicy {
+    pub tiers: Vec<RefundTier>,
+}
+
+impl CancellationPolicy {
+    /// The percentage of payments refunded when cancelling at `at` for a trip starting at `start`.
+    pub fn refund_percent(&self, start: DateTime<Utc>, at: DateTime<Utc>) -> i32 {
+        if at >= start {
+            return 0;
+        }
+
+        let notice = start - at;
+        self.tiers
+            .iter()
+            .filter(|tier| notice >= tier.notice)
+            .max_by_key(|tier| tier.notice)
+            .map_or(0, |tier| tier.percent)
+    }
+}
+
+/// A [RefundTier] refunds `percent` of payments for cancellations made at least
+/// `notice` before the trip starts, e.g. 100% with 7 days notice.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RefundTier {
+    pub notice: Duration,
+    pub percent: i32,
+}
+
+/// [Eligibility] describes who may participate in trips of a [TripKind].
+///
+/// Ages are evaluated on the date the trip starts.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Eligibility {
+    pub min_age: Option<u32>,
+    pub max_age: Option<u32>,
+    /// Participants younger than this must be accompanied by someone at least this old
+    /// on the same booking.
+    pub guardian_required_under: Option<u32>,
+    /// Free-form requirements participants are expected to meet, e.g. "able to swim".
+    pub requirements: Vec<String>,
+}
+
+impl Eligibility {
+    /// Checks a group of participants booked together against these rules,
+    /// returning a violation for every rule each participant breaks.
+    pub fn violations(
+        &self,
+        participants: &[Participant],
+        trip_date: NaiveDate,
+    ) -> Vec<EligibilityViolation> {
+        let unaccompanied_under = self
+            .guardian_required_under
+            .filter(|under| participants.iter().all(|p| p.age_on(trip_date) < *under));
+
+        participants
+            .iter()
+            .flat_map(|p| {
+                let age = p.age_on(trip_date);
+                let reasons = [
+                    self.min_age
+                        .filter(|min_age| age < *min_age)
+                        .map(|min_age| Ineligibility::TooYoung { min_age }),
+                    self.max_age
+                        .filter(|max_age| age > *max_age)
+                        .map(|max_age| Ineligibility::TooOld { max_age }),
+                    unaccompanied_under.map(|under| Ineligibility::GuardianRequired { under }),
+                ];
+
+                reasons
+                    .into_iter()
+                    .flatten()
+                    .map(move |reason| EligibilityViolation {
+                        participant: p.id.clone(),
+                        name: p.name.clone(),
+                        age,
+                        reason,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// [EligibilityViolation] explains why a [Participant] may not join a trip.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EligibilityViolation {
+    pub participant: ParticipantId,
+    pub name: String,
+    /// The participant's age on the date of the trip.
+    pub age: u32,
+    pub reason: Ineligibility,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum Ineligibility {
+    #[error("participants must be at least {min_age} years old")]
+    TooYoung { min_age: u32 },
+    #[error("participants must be at most {max_age} years old")]
+    TooOld { max_age: u32 },
+    #[error("participants under {under} must be accompanied by someone {under} or older on the same booking")]
+    GuardianRequired { under: u32 },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -44,6 +185,7 @@ pub struct LocationName(pub String);
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LocationDescription(pub String);
 
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TripFilters {
     pub kind: Option<TripKindId>,
     pub location: Option<LocationId>,
@@ -56,6 +198,122 @@ impl TripFilters {
     }
 }
 
+#[derive(Debug, Error)]
 pub enum TripError {
+    #[error("at least one filter must be provided")]
+    MissingFilters,
+    #[error("date range must not end before it starts")]
+    InvalidDateRange,
+    #[error(transparent)]
     Unknown(anyhow::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eligibility() -> Eligibility {
+        Eligibility {
+            min_age: Some(8),
+            max_age: Some(80),
+            guardian_required_under: Some(16),
+            requirements: vec![],
+        }
+    }
+
+    fn participant(name: &str, dob: NaiveDate) -> Participant {
+        Participant {
+            id: ParticipantId(Uuid::now_v7()),
+            name: name.to_string(),
+            dob,
+            notes: String::new(),
+            waiver: None,
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn reasons(
+        violations: &[EligibilityViolation],
+        participant: &Participant,
+    ) -> Vec<Ineligibility> {
+        violations
+            .iter()
+            .filter(|v| v.participant == participant.id)
+            .map(|v| v.reason.clone())
+            .collect()
+    }
+
+    #[test]
+    fn ages_are_checked_against_the_limits_on_the_trip_date() {
+        let trip_date = date(2026, 7, 4);
+        let adult = participant("Ana", date(1990, 1, 1));
+        let eight = participant("Bea", date(2018, 7, 4));
+        let seven = participant("Cal", date(2018, 7, 5));
+        let eighty = participant("Dee", date(1945, 7, 5));
+        let eighty_one = participant("Eve", date(1945, 7, 4));
+        let participants = [adult, eight, seven, eighty, eighty_one];
+
+        let violations = eligibility().violations(&participants, trip_date);
+
+        assert_eq!(
+            violations,
+            vec![
+                EligibilityViolation {
+                    participant: participants[2].id.clone(),
+                    name: "Cal".to_string(),
+                    age: 7,
+                    reason: Ineligibility::TooYoung { min_age: 8 },
+                },
+                EligibilityViolation {
+                    participant: participants[4].id.clone(),
+                    name: "Eve".to_string(),
+                    age: 81,
+                    reason: Ineligibility::TooOld { max_age: 80 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn young_participants_need_someone_of_the_guardian_age() {
+        let trip_date = date(2026, 7, 4);
+        let child = participant("Bea", date(2016, 1, 1));
+        let fifteen = participant("Cal", date(2010, 7, 5));
+        let sixteen = participant("Dee", date(2010, 7, 4));
+        let eligibility = eligibility();
+
+        let alone = [child.clone(), fifteen.clone()];
+        let violations = eligibility.violations(&alone, trip_date);
+        let required = vec![Ineligibility::GuardianRequired { under: 16 }];
+        assert_eq!(reasons(&violations, &child), required);
+        assert_eq!(reasons(&violations, &fifteen), required);
+
+        let accompanied = [child.clone(), sixteen];
+        assert_eq!(eligibility.violations(&accompanied, trip_date), vec![]);
+
+        let unrestricted = Eligibility {
+            guardian_required_under: None,
+            ..eligibility
+        };
+        assert_eq!(unrestricted.violations(&alone, trip_date), vec![]);
+    }
+
+    #[test]
+    fn every_broken_rule_is_reported() {
+        let trip_date = date(2026, 7, 4);
+        let toddler = participant("Bea", date(2024, 1, 1));
+
+        let violations = eligibility().violations(std::slice::from_ref(&toddler), trip_date);
+
+        assert_eq!(
+            reasons(&violations, &toddler),
+            vec![
+                Ineligibility::TooYoung { min_age: 8 },
+                Ineligibility::GuardianRequired { under: 16 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use crate::domain::booking::models::customer::CustomerId;
+use crate::domain::booking::models::trip::TripId;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A [WaitlistEntry] is a party waiting for seats on a full [Trip].
+///
+/// When seats free up, entries are offered them in the order they joined. An offer reserves
+/// the seats until it expires, and is accepted by creating a [Booking] that references it.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WaitlistEntry {
+    pub id: WaitlistEntryId,
+    pub customer: CustomerId,
+    pub trip: TripId,
+    pub party_size: i32,
+    pub status: WaitlistStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WaitlistEntry {
+    /// Whether this entry holds an offer that can still be accepted at `at`.
+    pub fn has_open_offer(&self, at: DateTime<Utc>) -> bool {
+        matches!(self.status, WaitlistStatus::Offered { expires_at } if expires_at > at)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WaitlistEntryId(pub Uuid);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WaitlistStatus {
+    Waiting,
+    /// Seats are reserved for the party until the offer expires.
+    Offered {
+        expires_at: DateTime<Utc>,
+    },
+    /// The party booked the seats they were offered.
+    Accepted,
+    /// The party didn't book the seats they were offered in time.
+    Expired,
+    /// The party left the waitlist.
+    Withdrawn,
+}
+
+impl WaitlistStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WaitlistStatus::Waiting => "waiting",
+            WaitlistStatus::Offered { .. } => "offered",
+            WaitlistStatus::Accepted => "accepted",
+            WaitlistStatus::Expired => "expired",
+            WaitlistStatus::Withdrawn => "withdrawn",
+        }
+    }
+}
+
+/// [WaitlistPosition] is a [WaitlistEntry] along with its place in line, if it is waiting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaitlistPosition {
+    pub entry: WaitlistEntry,
+    /// 1 for the next party to be offered seats.
+    pub position: Option<usize>,
+}
+
+impl WaitlistPosition {
+    /// Numbers the waiting entries of a trip's waitlist, given in the order they joined.
+    pub fn rank(entries: Vec<WaitlistEntry>) -> Vec<WaitlistPosition> {
+        let mut waiting = 0;
+        entries
+            .into_iter()
+            .map(|entry| {
+                let position = (entry.status == WaitlistStatus::Waiting).then(|| {
+                    waiting += 1;
+                    waiting
+                });
+                WaitlistPosition { entry, position }
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JoinWaitlistRequest {
+    pub customer: CustomerId,
+    pub trip: TripId,
+    pub party_size: i32,
+}
+
+#[derive(Debug, Error)]
+pub enum WaitlistError {
+    #[error("waitlist entry {} does not exist", .0.0)]
+    NotFound(WaitlistEntryId),
+    #[error("customer {} does not exist", .0.0)]
+    CustomerNotFound(CustomerId),
+    #[error("trip {} does not exist", .0.0)]
+    TripNotFound(TripId),
+    #[error("trip {} has already started", .0.0)]
+    TripStarted(TripId),
+    #[error("{0} is not a valid party size")]
+    InvalidPartySize(i32),
+    #[error("waitlist entry {} is no longer waiting", .0.0)]
+    NotWaiting(WaitlistEntryId),
+    #[error(transparent)]
+    Unknown(anyhow::Error),
+}
@@ -1,12 +1,124 @@
+use crate::domain::booking::models::blob::BlobKey;
+use crate::domain::booking::models::booking::ParticipantId;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+use thiserror::Error;
 use uuid::Uuid;
 
 /// A [Waiver] is a legal release of liability that a [Participant]
 /// must sign before participating in a [Trip].
+///
+/// Waivers are versioned: each [Waiver] value is one version of the document,
+/// and the version in force is the latest one whose `effective_at` has passed.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Waiver {
     pub id: WaiverId,
+    pub version: i32,
     pub content: String,
+    pub effective_at: DateTime<Utc>,
+}
+
+impl Waiver {
+    /// The hex-encoded SHA-256 hash of this version's content, recorded with each signature
+    /// as proof of exactly what was signed.
+    pub fn content_hash(&self) -> String {
+        format!("{:x}", Sha256::digest(self.content.as_bytes()))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct WaiverId(pub Uuid);
+
+/// A [WaiverSignature] records that a [Participant] signed a specific version of a [Waiver].
+///
+/// Signatures collected on paper before electronic signing have no `content_hash`,
+/// `signer` or `signature_image`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WaiverSignature {
+    pub id: WaiverSignatureId,
+    pub participant: ParticipantId,
+    pub waiver: WaiverId,
+    pub version: i32,
+    pub content_hash: Option<String>,
+    pub signed_at: DateTime<Utc>,
+    pub signer: Option<Signer>,
+    pub signature_image: Option<BlobKey>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WaiverSignatureId(pub Uuid);
+
+/// A [Signer] is the person who electronically signed a [Waiver], either the [Participant]
+/// themselves or, for a minor, their guardian.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Signer {
+    pub name: String,
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+    /// The guardian's relationship to the participant (e.g. "parent"),
+    /// or [None] if the participant signed for themselves.
+    pub guardian_relationship: Option<String>,
+}
+
+/// [SignWaiverRequest] electronically signs the current version of a [Waiver] for a [Participant].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignWaiverRequest {
+    pub waiver: WaiverId,
+    pub participant: ParticipantId,
+    /// The version the signer was shown.
+    pub version: i32,
+    /// The hash of the content the signer was shown, which must match the version's content.
+    pub content_hash: String,
+    pub signer: Signer,
+    /// A PNG image of the captured signature.
+    pub signature_image: Vec<u8>,
+}
+
+/// A [MissingWaiver] is a [Participant] who hasn't signed the version of a [Waiver]
+/// currently required for their [Trip].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MissingWaiver {
+    pub participant: ParticipantId,
+    pub waiver: WaiverId,
+    pub version: i32,
+}
+
+/// [CreateWaiverRequest] publishes a new [Waiver], or a new version of an existing one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateWaiverRequest {
+    /// The waiver to publish a new version of, or [None] to create a new waiver.
+    pub id: Option<WaiverId>,
+    pub content: String,
+    /// When the new version comes into force, or [None] for immediately.
+    pub effective_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Error)]
+pub enum WaiverError {
+    #[error("waiver {} does not exist", .0.0)]
+    NotFound(WaiverId),
+    #[error("waiver content must not be empty")]
+    EmptyContent,
+    #[error("participant {} does not exist", .0.0)]
+    ParticipantNotFound(ParticipantId),
+    #[error("version {0} of the waiver is not the version currently in force")]
+    OutdatedVersion(i32),
+    #[error("the signed content does not match the waiver's content")]
+    ContentMismatch,
+    #[error("signer name must not be empty")]
+    InvalidSignerName,
+    #[error("signature image must be a PNG")]
+    InvalidSignatureImage,
+    #[error("waiver {} is not the one participant {}'s trip requires", waiver.0, participant.0)]
+    NotRequired {
+        waiver: WaiverId,
+        participant: ParticipantId,
+    },
+    #[error("participant {} is a minor, so a guardian must sign", .0.0)]
+    GuardianRequired(ParticipantId),
+    #[error("participant {} is an adult, so they must sign for themselves", .0.0)]
+    GuardianNotAllowed(ParticipantId),
+    #[error(transparent)]
+    Unknown(anyhow::Error),
+}
@@ -1,18 +1,305 @@
-use crate::domain::booking::models::booking::{Booking, BookingError, BookingFilters, BookingId};
-use crate::domain::booking::models::customer::{Customer, CustomerError, CustomerId};
-use crate::domain::booking::models::trip::{Trip, TripError, TripFilters, TripId};
+use crate::domain::booking::models::blob::{BlobError, BlobKey};
+use crate::domain::booking::models::booking::{
+    Booking, BookingError, BookingFilters, BookingId, BookingStatus, CancelBookingRequest,
+    CreateBookingRequest, EditBookingRequest, OverbookingPolicy, Participant, ParticipantId,
+    RescheduleBookingRequest,
+};
+use crate::domain::booking::models::customer::{
+    CreateCustomerRequest, Customer, CustomerError, CustomerId, CustomerMerge, CustomerSearch,
+    CustomerSearchResults, DuplicateCandidate, EditCustomerRequest, MergeCustomersRequest, Page,
+    PhoneNormalization,
+};
+use crate::domain::booking::models::payment::{
+    AuthorizePaymentRequest, CapturePaymentRequest, Payment, PaymentError, PaymentId,
+    PaymentLedger, PaymentMethod, ProviderReference, RefundPaymentRequest,
+};
+use crate::domain::booking::models::pricing::{
+    Money, PriceList, PricingError, Quote, QuoteRequest,
+};
+use crate::domain::booking::models::trip::{Trip, TripError, TripFilters, TripId, TripKindId};
+use crate::domain::booking::models::waitlist::{
+    JoinWaitlistRequest, WaitlistEntry, WaitlistEntryId, WaitlistError, WaitlistPosition,
+};
+use crate::domain::booking::models::waiver::{
+    CreateWaiverRequest, MissingWaiver, SignWaiverRequest, Waiver, WaiverError, WaiverId,
+    WaiverSignature,
+};
+use chrono::{DateTime, Utc};
 use std::future::Future;
-use crate::domain::booking::models::equipment::{BookingRentals, EquipmentError};
+use crate::domain::booking::models::equipment::{
+    BookingRentals, EquipmentAvailability, EquipmentError,
+};
 
 /// [BookingService] is able to handle use-case interactions with the booking domain.
 pub trait BookingService: Clone + Send + Sync + 'static {
+    /// create_booking creates a new [Booking] from a [CreateBookingRequest], which is confirmed
+    /// unless the request asks for it to be held.
+    fn create_booking(
+        &self,
+        request: CreateBookingRequest,
+    ) -> impl Future<Output = Result<Booking, BookingError>> + Send;
+
+    /// find_booking gets a [Booking] by ID if it exists.
     fn find_booking(
         &self,
         id: BookingId,
     ) -> impl Future<Output = Result<Option<Booking>, BookingError>> + Send;
+
+    /// find_bookings gets all [Booking]s that match a given set of filters/criteria.
+    ///
+    /// At least one filter must be provided.
+    fn find_bookings(
+        &self,
+        filters: &BookingFilters,
+    ) -> impl Future<Output = Result<Vec<Booking>, BookingError>> + Send;
+
+    /// edit_booking applies an [EditBookingRequest] to an existing [Booking].
+    ///
+    /// Fails with [BookingError::HoldExpired] if the booking is a hold that has run out.
+    fn edit_booking(
+        &self,
+        request: EditBookingRequest,
+    ) -> impl Future<Output = Result<Booking, BookingError>> + Send;
+
+    /// delete_booking deletes an existing [Booking] & its participants/rentals.
+    fn delete_booking(
+        &self,
+        id: BookingId,
+    ) -> impl Future<Output = Result<(), BookingError>> + Send;
+
+    /// reschedule_booking moves an open [Booking], along with its participants and rentals,
+    /// to another [Trip] of the same kind. The freed seats are offered to the old trip's waitlist.
+    ///
+    /// Fails with [BookingError::HoldExpired] if the booking is a hold that has run out.
+    fn reschedule_booking(
+        &self,
+        request: RescheduleBookingRequest,
+    ) -> impl Future<Output = Result<Booking, BookingError>> + Send;
+
+    /// confirm_booking confirms a held [Booking] whose hold hasn't expired.
+    fn confirm_booking(
+        &self,
+        id: BookingId,
+    ) -> impl Future<Output = Result<Booking, BookingError>> + Send;
+
+    /// release_expired_holds cancels every held [Booking] whose hold has expired,
+    /// returning their IDs.
+    fn release_expired_holds(
+        &self,
+    ) -> impl Future<Output = Result<Vec<BookingId>, BookingError>> + Send;
+
+    /// complete_booking marks a checked-in [Booking] as completed once its trip is over,
+    /// failing with [BookingError::TripNotEnded] before then.
+    fn complete_booking(
+        &self,
+        id: BookingId,
+    ) -> impl Future<Output = Result<Booking, BookingError>> + Send;
+
+    /// mark_booking_no_show marks a confirmed [Booking] whose participants never checked in,
+    /// failing with [BookingError::TripNotStarted] before its trip has started.
+    fn mark_booking_no_show(
+        &self,
+        id: BookingId,
+    ) -> impl Future<Output = Result<Booking, BookingError>> + Send;
+
+    /// cancel_booking cancels an existing [Booking], recording the refund owed under
+    /// its trip's cancellation policy. Cancelled bookings are kept for reporting.
+    ///
+    /// The freed seats are offered to the trip's waitlist.
+    fn cancel_booking(
+        &self,
+        request: CancelBookingRequest,
+    ) -> impl Future<Output = Result<Booking, BookingError>> + Send;
+
+    /// join_waitlist adds a party to the waitlist of a [Trip], offering them seats
+    /// straight away if enough are free.
+    fn join_waitlist(
+        &self,
+        request: JoinWaitlistRequest,
+    ) -> impl Future<Output = Result<WaitlistPosition, WaitlistError>> + Send;
+
+    /// find_waitlist_entry gets a [WaitlistEntry] and its place in line if it exists.
+    fn find_waitlist_entry(
+        &self,
+        id: WaitlistEntryId,
+    ) -> impl Future<Output = Result<Option<WaitlistPosition>, WaitlistError>> + Send;
+
+    /// find_waitlist gets every [WaitlistEntry] of a [Trip], in the order they joined.
+    fn find_waitlist(
+        &self,
+        trip: TripId,
+    ) -> impl Future<Output = Result<Vec<WaitlistPosition>, WaitlistError>> + Send;
+
+    /// leave_waitlist withdraws a waiting party, giving up any seats they were offered.
+    fn leave_waitlist(
+        &self,
+        id: WaitlistEntryId,
+    ) -> impl Future<Output = Result<WaitlistEntry, WaitlistError>> + Send;
+
+    /// promote_waitlists offers the free seats of every upcoming [Trip] to its waitlist,
+    /// e.g. after holds were released or the trip's capacity grew, returning the new offers.
+    fn promote_waitlists(
+        &self,
+    ) -> impl Future<Output = Result<Vec<WaitlistEntry>, WaitlistError>> + Send;
+
+    /// find_missing_waivers gets the participants of a [Booking] who haven't signed the
+    /// current version of the [Waiver] their trip requires.
+    fn find_missing_waivers(
+        &self,
+        id: BookingId,
+    ) -> impl Future<Output = Result<Vec<MissingWaiver>, BookingError>> + Send;
+
+    /// check_in_booking checks a confirmed [Booking] in for its trip.
+    ///
+    /// Fails with [BookingError::WaiversMissing] until every participant has signed
+    /// the current version of the trip's required [Waiver].
+    fn check_in_booking(
+        &self,
+        id: BookingId,
+    ) -> impl Future<Output = Result<Booking, BookingError>> + Send;
+
+    /// create_waiver publishes a new [Waiver], or a new version of an existing one.
+    fn create_waiver(
+        &self,
+        request: CreateWaiverRequest,
+    ) -> impl Future<Output = Result<Waiver, WaiverError>> + Send;
+
+    /// find_current_waiver gets the version of a [Waiver] that is currently in force.
+    fn find_current_waiver(
+        &self,
+        id: WaiverId,
+    ) -> impl Future<Output = Result<Option<Waiver>, WaiverError>> + Send;
+
+    /// sign_waiver electronically signs the current version of a [Waiver] for a [Participant],
+    /// storing the captured signature image alongside an audit trail of who signed and how.
+    ///
+    /// The waiver must be the one the participant's trip kind requires, and participants younger
+    /// than the kind's `guardian_required_under` age must have a guardian sign for them.
+    fn sign_waiver(
+        &self,
+        request: SignWaiverRequest,
+    ) -> impl Future<Output = Result<WaiverSignature, WaiverError>> + Send;
+
+    /// create_customer creates a new [Customer] from a [CreateCustomerRequest].
+    fn create_customer(
+        &self,
+        request: CreateCustomerRequest,
+    ) -> impl Future<Output = Result<Customer, CustomerError>> + Send;
+
+    /// find_customer gets a [Customer] by ID if it exists.
+    fn find_customer(
+        &self,
+        id: CustomerId,
+    ) -> impl Future<Output = Result<Option<Customer>, CustomerError>> + Send;
+
+    /// edit_customer applies an [EditCustomerRequest] to an existing [Customer].
+    fn edit_customer(
+        &self,
+        request: EditCustomerRequest,
+    ) -> impl Future<Output = Result<Customer, CustomerError>> + Send;
+
+    /// search_customers finds a page of customers whose name, email address or phone number
+    /// resembles `query`, best match first.
+    fn search_customers(
+        &self,
+        query: &str,
+        page: Page,
+    ) -> impl Future<Output = Result<CustomerSearchResults, CustomerError>> + Send;
+
+    /// find_duplicate_customers gets a page of customer pairs who likely represent the same
+    /// person, most likely first.
+    fn find_duplicate_customers(
+        &self,
+        page: Page,
+    ) -> impl Future<Output = Result<Vec<DuplicateCandidate>, CustomerError>> + Send;
+
+    /// merge_customers moves the bookings and waitlist entries of one [Customer] to another
+    /// and deletes it, recording a [CustomerMerge].
+    fn merge_customers(
+        &self,
+        request: MergeCustomersRequest,
+    ) -> impl Future<Output = Result<CustomerMerge, CustomerError>> + Send;
+
+    /// find_customer_merges gets every [CustomerMerge] into or of a customer, oldest first.
+    fn find_customer_merges(
+        &self,
+        id: CustomerId,
+    ) -> impl Future<Output = Result<Vec<CustomerMerge>, CustomerError>> + Send;
+
+    /// normalize_customer_phones rewrites every stored phone number in E.164 format, e.g.
+    /// those saved before numbers were normalized, reading numbers without a country code
+    /// as being from the configured region.
+    fn normalize_customer_phones(
+        &self,
+    ) -> impl Future<Output = Result<PhoneNormalization, CustomerError>> + Send;
+
+    /// find_trip gets a [Trip] by ID if it exists.
+    fn find_trip(&self, id: TripId) -> impl Future<Output = Result<Option<Trip>, TripError>> + Send;
+
+    /// find_trips gets all [Trip]s that match a given set of filters/criteria.
+    ///
+    /// At least one filter must be provided.
+    fn find_trips(
+        &self,
+        filters: &TripFilters,
+    ) -> impl Future<Output = Result<Vec<Trip>, TripError>> + Send;
+
+    /// find_booking_rentals gets the [BookingRentals] for an existing booking.
+    fn find_booking_rentals(
+        &self,
+        booking_id: BookingId,
+    ) -> impl Future<Output = Result<BookingRentals, EquipmentError>> + Send;
+
+    /// save_booking_rentals replaces all rentals for an existing booking.
+    fn save_booking_rentals(
+        &self,
+        booking_rentals: &BookingRentals,
+    ) -> impl Future<Output = Result<(), EquipmentError>> + Send;
+
+    /// find_equipment_availability gets the remaining inventory of all equipment
+    /// across trips overlapping the given time window.
+    fn find_equipment_availability(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> impl Future<Output = Result<EquipmentAvailability, EquipmentError>> + Send;
+
+    /// quote_booking prices a prospective booking without saving it.
+    fn quote_booking(
+        &self,
+        request: &QuoteRequest,
+    ) -> impl Future<Output = Result<Quote, PricingError>> + Send;
+
+    /// authorize_payment holds funds on the customer's payment method for a booking.
+    ///
+    /// Declined authorizations are recorded in the booking's ledger before
+    /// [PaymentError::Declined] is returned.
+    fn authorize_payment(
+        &self,
+        request: AuthorizePaymentRequest,
+    ) -> impl Future<Output = Result<Payment, PaymentError>> + Send;
+
+    /// capture_payment collects some or all of an authorization, e.g. as a deposit.
+    fn capture_payment(
+        &self,
+        request: CapturePaymentRequest,
+    ) -> impl Future<Output = Result<Payment, PaymentError>> + Send;
+
+    /// refund_payment returns some or all of a capture to the customer.
+    fn refund_payment(
+        &self,
+        request: RefundPaymentRequest,
+    ) -> impl Future<Output = Result<Payment, PaymentError>> + Send;
+
+    /// find_payment_ledger gets every [Payment] made for an existing booking.
+    fn find_payment_ledger(
+        &self,
+        booking: BookingId,
+    ) -> impl Future<Output = Result<PaymentLedger, PaymentError>> + Send;
 }
 
-/// [BookingRepository] is able to access and persist booking domain models.
+/// [BookingRepository] is able to access and persist bookings, along with their
+/// waitlists, prices and payments.
 pub trait BookingRepository: Clone + Send + Sync + 'static {
     /// find_booking gets a [Booking] by ID if it exists.
     fn find_booking(
@@ -21,41 +308,207 @@ pub trait BookingRepository: Clone + Send + Sync + 'static {
     ) -> impl Future<Output = Result<Option<Booking>, BookingError>> + Send;
 
     /// find_bookings gets all [Booking]s that match a given set of filters/criteria.
+    ///
+    /// Gets nothing if no filters are provided, rather than every booking.
     fn find_bookings(
         &self,
         filters: &BookingFilters,
     ) -> impl Future<Output = Result<Vec<Booking>, BookingError>> + Send;
 
-    /// save_booking atomically saves a booking & its participants.
+    /// save_booking atomically saves a booking & its participants, returning whether it was
+    /// saved as [Booking::overbooked].
+    ///
+    /// If the booking adds participants beyond its trip's remaining capacity, the
+    /// [OverbookingPolicy] decides whether it is rejected with [BookingError::TripFull] or
+    /// marked as overbooked. A booking that is already marked stays marked.
+    /// Seats offered to the waitlist count as taken, except those of the booking's own
+    /// waitlist entry, which is marked as accepted.
+    ///
+    /// An open booking is rejected with [BookingError::AlreadyBooked] if one of its
+    /// participants is on another booking of the trip that isn't cancelled or an expired hold.
+    /// Both checks must be safe against concurrent bookings on the same trip.
     fn save_booking(
         &self,
         booking: &Booking,
+        overbooking: OverbookingPolicy,
+    ) -> impl Future<Output = Result<bool, BookingError>> + Send;
+
+    /// reschedule_booking atomically moves a booking to the trip it now references,
+    /// returning whether it was saved as [Booking::overbooked].
+    ///
+    /// Its participants are checked against the new trip's capacity and bookings as in
+    /// [Self::save_booking], and its rentals against the equipment available across trips
+    /// overlapping the new trip, failing with [BookingError::InsufficientInventory]. The
+    /// checks must be safe against concurrent bookings and rentals.
+    ///
+    /// Fails with [BookingError::StatusChanged] if the booking no longer has the status it
+    /// was read with, e.g. because it was cancelled concurrently.
+    fn reschedule_booking(
+        &self,
+        booking: &Booking,
+        overbooking: OverbookingPolicy,
+    ) -> impl Future<Output = Result<bool, BookingError>> + Send;
+
+    /// confirm_hold atomically confirms a held [Booking] whose hold hasn't expired at `at`.
+    ///
+    /// Fails with [BookingError::HoldExpired] if the booking is no longer held, e.g. because
+    /// its expired hold was cancelled concurrently.
+    fn confirm_hold(
+        &self,
+        id: BookingId,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), BookingError>> + Send;
+
+    /// save_booking_status atomically saves the status, check-in and cancellation of a
+    /// [Booking] that still has the status `from` it was read with.
+    ///
+    /// Fails with [BookingError::StatusChanged] otherwise, e.g. because it was cancelled
+    /// concurrently, so concurrent transitions can't skip the booking lifecycle.
+    fn save_booking_status(
+        &self,
+        booking: &Booking,
+        from: BookingStatus,
     ) -> impl Future<Output = Result<(), BookingError>> + Send;
 
+    /// cancel_expired_holds cancels every held [Booking] whose hold expired before `at`,
+    /// returning their IDs. Bookings confirmed concurrently must not be cancelled.
+    fn cancel_expired_holds(
+        &self,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<BookingId>, BookingError>> + Send;
+
     /// delete_booking atomically deletes a booking & its participants/rentals.
     fn delete_booking(
         &self,
         id: BookingId,
     ) -> impl Future<Output = Result<(), BookingError>> + Send;
 
+    /// find_participant gets a [Participant] by ID if it exists.
+    fn find_participant(
+        &self,
+        id: ParticipantId,
+    ) -> impl Future<Output = Result<Option<Participant>, BookingError>> + Send;
+
+    /// find_waitlist_entry gets a [WaitlistEntry] by ID if it exists.
+    fn find_waitlist_entry(
+        &self,
+        id: WaitlistEntryId,
+    ) -> impl Future<Output = Result<Option<WaitlistEntry>, WaitlistError>> + Send;
+
+    /// find_waitlist gets every [WaitlistEntry] of a trip, in the order they joined.
+    fn find_waitlist(
+        &self,
+        trip: TripId,
+    ) -> impl Future<Output = Result<Vec<WaitlistEntry>, WaitlistError>> + Send;
+
+    /// save_waitlist_entry creates or updates a waitlist entry.
+    fn save_waitlist_entry(
+        &self,
+        entry: &WaitlistEntry,
+    ) -> impl Future<Output = Result<(), WaitlistError>> + Send;
+
+    /// find_waitlisted_trips gets the trips starting after `at` that have waiting
+    /// entries, or offers that expired before `at`.
+    fn find_waitlisted_trips(
+        &self,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<TripId>, WaitlistError>> + Send;
+
+    /// offer_waitlist_seats atomically expires a trip's offers that ran out before `at`, then
+    /// offers its free seats to waiting entries in the order they joined, until the next
+    /// party doesn't fit. New offers expire at `expires_at`; they are returned.
+    /// The capacity check must be safe against concurrent bookings on the same trip.
+    fn offer_waitlist_seats(
+        &self,
+        trip: TripId,
+        at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<WaitlistEntry>, WaitlistError>> + Send;
+
+    /// find_price_list gets the [PriceList] for trips of the given kind.
+    fn find_price_list(
+        &self,
+        trip_kind: TripKindId,
+    ) -> impl Future<Output = Result<PriceList, PricingError>> + Send;
+
+    /// find_payment gets a [Payment] by ID if it exists.
+    fn find_payment(
+        &self,
+        id: PaymentId,
+    ) -> impl Future<Output = Result<Option<Payment>, PaymentError>> + Send;
+
+    /// find_payment_ledger gets every [Payment] made for a booking, oldest first.
+    fn find_payment_ledger(
+        &self,
+        booking: BookingId,
+    ) -> impl Future<Output = Result<PaymentLedger, PaymentError>> + Send;
+
+    /// save_payment appends a [Payment] to its booking's ledger.
+    fn save_payment(
+        &self,
+        payment: &Payment,
+    ) -> impl Future<Output = Result<(), PaymentError>> + Send;
+}
+
+/// [CustomerRepository] is able to access and persist customers and their merge history.
+pub trait CustomerRepository: Clone + Send + Sync + 'static {
     /// find_customer gets a [Customer] by ID if it exists.
     fn find_customer(
         &self,
         id: CustomerId,
     ) -> impl Future<Output = Result<Option<Customer>, CustomerError>> + Send;
 
+    /// find_customers gets a page of every [Customer], ordered by ID.
+    fn find_customers(
+        &self,
+        page: Page,
+    ) -> impl Future<Output = Result<Vec<Customer>, CustomerError>> + Send;
+
     /// save_customer creates or updates a customer.
     fn save_customer(
         &self,
         customer: &Customer,
     ) -> impl Future<Output = Result<(), CustomerError>> + Send;
 
+    /// search_customers finds a page of customers matching a [CustomerSearch], best match first.
+    fn search_customers(
+        &self,
+        search: &CustomerSearch,
+    ) -> impl Future<Output = Result<CustomerSearchResults, CustomerError>> + Send;
+
+    /// find_duplicate_customers gets a page of customer pairs who likely represent the same
+    /// person, most likely first.
+    fn find_duplicate_customers(
+        &self,
+        page: Page,
+    ) -> impl Future<Output = Result<Vec<DuplicateCandidate>, CustomerError>> + Send;
+
+    /// merge_customers atomically moves the bookings and waitlist entries of the merged
+    /// customer to the survivor, deletes the merged customer, gives the survivor the details
+    /// the request takes from it and records the [CustomerMerge].
+    ///
+    /// Both customers are read under the merge's locks, so concurrent edits to either aren't
+    /// lost.
+    fn merge_customers(
+        &self,
+        request: &MergeCustomersRequest,
+    ) -> impl Future<Output = Result<CustomerMerge, CustomerError>> + Send;
+
+    /// find_customer_merges gets every [CustomerMerge] into or of a customer, oldest first.
+    fn find_customer_merges(
+        &self,
+        id: CustomerId,
+    ) -> impl Future<Output = Result<Vec<CustomerMerge>, CustomerError>> + Send;
+
     /// delete_customer deletes a customer by id.
     fn delete_customer(
         &self,
         id: CustomerId,
     ) -> impl Future<Output = Result<(), CustomerError>> + Send;
+}
 
+/// [TripRepository] is able to access the trips on offer.
+pub trait TripRepository: Clone + Send + Sync + 'static {
     /// find_trip gets a [Trip] by ID if it exists.
     fn find_trip(
         &self,
@@ -63,20 +516,131 @@ pub trait BookingRepository: Clone + Send + Sync + 'static {
     ) -> impl Future<Output = Result<Option<Trip>, TripError>> + Send;
 
     /// find_trips gets all [Trip]s that match a given set of filters/criteria.
+    ///
+    /// Gets nothing if no filters are provided, rather than every trip.
     fn find_trips(
         &self,
         trip_filters: &TripFilters,
     ) -> impl Future<Output = Result<Vec<Trip>, TripError>> + Send;
-    
+}
+
+/// [RentalRepository] is able to access and persist equipment rentals.
+pub trait RentalRepository: Clone + Send + Sync + 'static {
     /// find_booking_rentals gets the [BookingRentals] for a given booking.
     fn find_booking_rentals(
         &self,
         booking_id: BookingId,
     ) -> impl Future<Output = Result<BookingRentals, EquipmentError>> + Send;
-    
+
     /// save_booking_rentals saves or updates all rentals for a booking.
+    ///
+    /// Rentals that would exceed an item's inventory across all trips overlapping the
+    /// booking's trip are rejected with [EquipmentError::InsufficientInventory].
+    /// The inventory check must be safe against concurrent rentals of the same equipment.
     fn save_booking_rentals(
         &self,
         booking_rentals: &BookingRentals
     ) -> impl Future<Output = Result<(), EquipmentError>> + Send;
+
+    /// find_equipment_availability gets the remaining inventory of all equipment
+    /// across trips overlapping the given time window.
+    fn find_equipment_availability(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> impl Future<Output = Result<EquipmentAvailability, EquipmentError>> + Send;
+}
+
+/// [WaiverRepository] is able to access and persist waivers and their signatures.
+pub trait WaiverRepository: Clone + Send + Sync + 'static {
+    /// find_current_waiver gets the latest version of a [Waiver] that is in force at `at`.
+    fn find_current_waiver(
+        &self,
+        id: WaiverId,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Option<Waiver>, WaiverError>> + Send;
+
+    /// find_latest_waiver gets the latest version of a [Waiver], even if it isn't in force yet.
+    fn find_latest_waiver(
+        &self,
+        id: WaiverId,
+    ) -> impl Future<Output = Result<Option<Waiver>, WaiverError>> + Send;
+
+    /// save_waiver saves a new version of a waiver.
+    fn save_waiver(&self, waiver: &Waiver) -> impl Future<Output = Result<(), WaiverError>> + Send;
+
+    /// save_waiver_signature records a new [WaiverSignature].
+    fn save_waiver_signature(
+        &self,
+        signature: &WaiverSignature,
+    ) -> impl Future<Output = Result<(), WaiverError>> + Send;
+
+    /// find_waiver_signatures gets every [WaiverSignature] of the given participants.
+    fn find_waiver_signatures(
+        &self,
+        participants: &[ParticipantId],
+    ) -> impl Future<Output = Result<Vec<WaiverSignature>, WaiverError>> + Send;
+}
+
+/// [UnitOfWork] is able to run a use case's calls to several repositories atomically,
+/// e.g. in a single database transaction.
+///
+/// [UnitOfWork::begin] returns repositories bound to a new unit of work. Their changes are
+/// applied by [UnitOfWork::commit], and discarded if they are dropped without committing.
+/// Once a call fails, the unit of work may be aborted, so the use case should give up on it.
+pub trait UnitOfWork:
+    BookingRepository + CustomerRepository + TripRepository + RentalRepository + WaiverRepository
+{
+    /// begin starts a new unit of work, returning repositories bound to it.
+    fn begin(&self) -> impl Future<Output = anyhow::Result<Self>> + Send;
+
+    /// commit atomically applies every change made within a unit of work.
+    ///
+    /// Committing repositories that aren't bound to a unit of work does nothing.
+    fn commit(self) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+/// [BlobStore] is able to store and retrieve binary objects, such as signature images.
+pub trait BlobStore: Clone + Send + Sync + 'static {
+    /// put_blob stores a new blob, returning the [BlobKey] it can be retrieved with.
+    fn put_blob(
+        &self,
+        bytes: &[u8],
+        extension: &str,
+    ) -> impl Future<Output = Result<BlobKey, BlobError>> + Send;
+
+    /// get_blob gets the contents of a blob if it exists.
+    fn get_blob(
+        &self,
+        key: &BlobKey,
+    ) -> impl Future<Output = Result<Option<Vec<u8>>, BlobError>> + Send;
+
+    /// delete_blob deletes a blob, doing nothing if it doesn't exist.
+    fn delete_blob(&self, key: &BlobKey) -> impl Future<Output = Result<(), BlobError>> + Send;
+}
+
+/// [PaymentGateway] is able to move money through an external payment provider.
+///
+/// Operations the provider refuses fail with [PaymentError::Declined].
+pub trait PaymentGateway: Clone + Send + Sync + 'static {
+    /// authorize holds `amount` on a payment method without collecting it.
+    fn authorize(
+        &self,
+        method: &PaymentMethod,
+        amount: Money,
+    ) -> impl Future<Output = Result<ProviderReference, PaymentError>> + Send;
+
+    /// capture collects `amount` of a previous authorization.
+    fn capture(
+        &self,
+        authorization: &ProviderReference,
+        amount: Money,
+    ) -> impl Future<Output = Result<ProviderReference, PaymentError>> + Send;
+
+    /// refund returns `amount` of a previous capture to the customer.
+    fn refund(
+        &self,
+        capture: &ProviderReference,
+        amount: Money,
+    ) -> impl Future<Output = Result<ProviderReference, PaymentError>> + Send;
 }
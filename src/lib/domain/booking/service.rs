@@ -1,19 +1,1768 @@
-use crate::domain::booking::models::booking::{Booking, BookingError, BookingId};
-use crate::domain::booking::ports::{BookingRepository, BookingService};
+use crate::domain::booking::models::booking::{
+    Booking, BookingError, BookingFilters, BookingId, BookingStatus, CancelBookingRequest,
+    Cancellation, CreateBookingRequest, EditBookingRequest, OverbookingPolicy, Participant,
+    RescheduleBookingRequest,
+};
+use crate::domain::booking::models::customer::{
+    CreateCustomerRequest, Customer, CustomerError, CustomerId, CustomerMerge, CustomerSearch,
+    CustomerSearchResults, DuplicateCandidate, EditCustomerRequest, MergeCustomersRequest, Page,
+    PhoneNormalization, PhoneNumber, PhoneRegion,
+};
+use crate::domain::booking::models::equipment::{
+    BookingRentals, EquipmentAvailability, EquipmentError,
+};
+use crate::domain::booking::models::payment::{
+    AuthorizePaymentRequest, CapturePaymentRequest, Payment, PaymentError, PaymentId, PaymentKind,
+    PaymentLedger, PaymentStatus, ProviderReference, RefundPaymentRequest,
+};
+use crate::domain::booking::models::pricing::{Money, PricingError, Quote, QuoteRequest};
+use crate::domain::booking::models::trip::{Trip, TripError, TripFilters, TripId, TripKind};
+use crate::domain::booking::models::waitlist::{
+    JoinWaitlistRequest, WaitlistEntry, WaitlistEntryId, WaitlistError, WaitlistPosition,
+    WaitlistStatus,
+};
+use crate::domain::booking::models::waiver::{
+    CreateWaiverRequest, MissingWaiver, SignWaiverRequest, Waiver, WaiverError, WaiverId,
+    WaiverSignature, WaiverSignatureId,
+};
+use crate::domain::booking::ports::{BlobStore, BookingService, PaymentGateway, UnitOfWork};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const DEFAULT_HOLD_DURATION: Duration = Duration::minutes(15);
+const DEFAULT_WAITLIST_OFFER_DURATION: Duration = Duration::hours(24);
 
 #[derive(Debug, Clone)]
-pub struct Service<R: BookingRepository> {
+pub struct Service<R: UnitOfWork, B: BlobStore, P: PaymentGateway> {
     repo: R,
+    blobs: B,
+    payments: P,
+    overbooking: OverbookingPolicy,
+    hold_duration: Duration,
+    waitlist_offer_duration: Duration,
+    phone_region: PhoneRegion,
 }
 
-impl<R: BookingRepository> Service<R> {
-    pub fn new(repo: R) -> Self {
-        Self { repo }
+impl<R: UnitOfWork, B: BlobStore, P: PaymentGateway> Service<R, B, P> {
+    pub fn new(repo: R, blobs: B, payments: P) -> Self {
+        Self {
+            repo,
+            blobs,
+            payments,
+            overbooking: OverbookingPolicy::default(),
+            hold_duration: DEFAULT_HOLD_DURATION,
+            waitlist_offer_duration: DEFAULT_WAITLIST_OFFER_DURATION,
+            phone_region: PhoneRegion::default(),
+        }
+    }
+
+    /// Sets the [OverbookingPolicy] applied when a booking exceeds its trip's capacity.
+    pub fn with_overbooking_policy(self, overbooking: OverbookingPolicy) -> Self {
+        Self {
+            overbooking,
+            ..self
+        }
+    }
+
+    /// Sets how long held bookings reserve their seats and rentals before they must be confirmed.
+    pub fn with_hold_duration(self, hold_duration: Duration) -> Self {
+        Self {
+            hold_duration,
+            ..self
+        }
+    }
+
+    /// Sets the [PhoneRegion] of customer phone numbers entered without a country code.
+    pub fn with_phone_region(self, phone_region: PhoneRegion) -> Self {
+        Self {
+            phone_region,
+            ..self
+        }
+    }
+
+    /// Sets how long waitlisted parties have to book the seats they are offered.
+    pub fn with_waitlist_offer_duration(self, waitlist_offer_duration: Duration) -> Self {
+        Self {
+            waitlist_offer_duration,
+            ..self
+        }
     }
 }
 
-impl<R: BookingRepository> BookingService for Service<R> {
+impl<R: UnitOfWork, B: BlobStore, P: PaymentGateway> BookingService for Service<R, B, P> {
+    async fn create_booking(&self, request: CreateBookingRequest) -> Result<Booking, BookingError> {
+        let joins_reference = request.reference.is_some();
+        let mut booking = Booking::try_from(request)?;
+        if booking.status == BookingStatus::Held {
+            booking.hold_expires_at = Some(Utc::now() + self.hold_duration);
+        }
+
+        let repo = self.repo.begin().await.map_err(BookingError::Unknown)?;
+        let customer = repo
+            .find_customer(booking.customer.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?;
+        if customer.is_none() {
+            return Err(BookingError::CustomerNotFound(booking.customer));
+        }
+
+        let trip = repo
+            .find_trip(booking.trip.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+        if trip.start_time <= Utc::now() {
+            return Err(BookingError::TripStarted(trip.id));
+        }
+        ensure_eligible(&trip, &booking)?;
+        if let Some(id) = &booking.waitlist_entry {
+            self.ensure_waitlist_offer(&repo, id, &booking).await?;
+        }
+        if joins_reference {
+            self.ensure_reference_owned(&repo, &booking).await?;
+        }
+
+        booking.overbooked = repo.save_booking(&booking, self.overbooking).await?;
+        repo.commit().await.map_err(BookingError::Unknown)?;
+
+        Ok(booking)
+    }
+
     async fn find_booking(&self, id: BookingId) -> Result<Option<Booking>, BookingError> {
         self.repo.find_booking(id).await
     }
+
+    async fn find_bookings(&self, filters: &BookingFilters) -> Result<Vec<Booking>, BookingError> {
+        if filters.is_empty() {
+            return Err(BookingError::MissingFilters);
+        }
+
+        self.repo.find_bookings(filters).await
+    }
+
+    async fn edit_booking(&self, request: EditBookingRequest) -> Result<Booking, BookingError> {
+        let repo = self.repo.begin().await.map_err(BookingError::Unknown)?;
+        let mut booking = repo
+            .find_booking(request.id.clone())
+            .await?
+            .ok_or_else(|| BookingError::NotFound(request.id.clone()))?
+            .edit(request, Utc::now())?;
+
+        let trip = repo
+            .find_trip(booking.trip.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+        ensure_eligible(&trip, &booking)?;
+
+        booking.overbooked = repo.save_booking(&booking, self.overbooking).await?;
+        repo.commit().await.map_err(BookingError::Unknown)?;
+
+        Ok(booking)
+    }
+
+    async fn delete_booking(&self, id: BookingId) -> Result<(), BookingError> {
+        let repo = self.repo.begin().await.map_err(BookingError::Unknown)?;
+        if repo.find_booking(id.clone()).await?.is_none() {
+            return Err(BookingError::NotFound(id));
+        }
+        let ledger = repo
+            .find_payment_ledger(id.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?;
+        if !ledger.payments.is_empty() {
+            return Err(BookingError::HasPayments(id));
+        }
+
+        repo.delete_booking(id).await?;
+        repo.commit().await.map_err(BookingError::Unknown)
+    }
+
+    async fn cancel_booking(&self, request: CancelBookingRequest) -> Result<Booking, BookingError> {
+        let reason = request.reason.trim();
+        if reason.is_empty() {
+            return Err(BookingError::MissingCancellationReason);
+        }
+
+        let booking = self
+            .repo
+            .find_booking(request.id.clone())
+            .await?
+            .ok_or(BookingError::NotFound(request.id))?;
+        let from = booking.status;
+        let booking = booking.transition(BookingStatus::Cancelled)?;
+
+        let trip = self
+            .repo
+            .find_trip(booking.trip.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+        let paid = self
+            .repo
+            .find_payment_ledger(booking.id.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .paid();
+
+        let now = Utc::now();
+        let percent = trip
+            .kind
+            .cancellation_policy
+            .refund_percent(trip.start_time, now);
+        let booking = Booking {
+            cancellation: Some(Cancellation {
+                reason: reason.to_string(),
+                cancelled_at: now,
+                refund: paid.percent(percent),
+            }),
+            ..booking
+        };
+        self.repo.save_booking_status(&booking, from).await?;
+
+        // The booking is cancelled either way; offers missed here are made by the next sweep.
+        if let Err(e) = self.offer_waitlist_seats(trip.id).await {
+            tracing::warn!(booking_id = %booking.id.0, "failed to offer freed seats: {e}");
+        }
+
+        Ok(booking)
+    }
+
+    async fn join_waitlist(
+        &self,
+        request: JoinWaitlistRequest,
+    ) -> Result<WaitlistPosition, WaitlistError> {
+        if request.party_size <= 0 {
+            return Err(WaitlistError::InvalidPartySize(request.party_size));
+        }
+
+        let customer = self
+            .repo
+            .find_customer(request.customer.clone())
+            .await
+            .map_err(|e| WaitlistError::Unknown(e.into()))?;
+        if customer.is_none() {
+            return Err(WaitlistError::CustomerNotFound(request.customer));
+        }
+
+        let trip = self
+            .repo
+            .find_trip(request.trip.clone())
+            .await
+            .map_err(|e| WaitlistError::Unknown(e.into()))?
+            .ok_or_else(|| WaitlistError::TripNotFound(request.trip.clone()))?;
+        if trip.start_time <= Utc::now() {
+            return Err(WaitlistError::TripStarted(trip.id));
+        }
+
+        let entry = WaitlistEntry {
+            id: WaitlistEntryId(Uuid::now_v7()),
+            customer: request.customer,
+            trip: trip.id,
+            party_size: request.party_size,
+            status: WaitlistStatus::Waiting,
+            created_at: Utc::now(),
+        };
+        self.repo.save_waitlist_entry(&entry).await?;
+        self.offer_waitlist_seats(entry.trip.clone()).await?;
+
+        self.find_waitlist_entry(entry.id.clone())
+            .await?
+            .ok_or(WaitlistError::NotFound(entry.id))
+    }
+
+    async fn find_waitlist_entry(
+        &self,
+        id: WaitlistEntryId,
+    ) -> Result<Option<WaitlistPosition>, WaitlistError> {
+        let Some(entry) = self.repo.find_waitlist_entry(id).await? else {
+            return Ok(None);
+        };
+
+        let waitlist = self.repo.find_waitlist(entry.trip.clone()).await?;
+
+        Ok(WaitlistPosition::rank(waitlist)
+            .into_iter()
+            .find(|p| p.entry.id == entry.id))
+    }
+
+    async fn find_waitlist(&self, trip: TripId) -> Result<Vec<WaitlistPosition>, WaitlistError> {
+        let exists = self
+            .repo
+            .find_trip(trip.clone())
+            .await
+            .map_err(|e| WaitlistError::Unknown(e.into()))?
+            .is_some();
+        if !exists {
+            return Err(WaitlistError::TripNotFound(trip));
+        }
+
+        Ok(WaitlistPosition::rank(self.repo.find_waitlist(trip).await?))
+    }
+
+    async fn leave_waitlist(&self, id: WaitlistEntryId) -> Result<WaitlistEntry, WaitlistError> {
+        let entry = self
+            .repo
+            .find_waitlist_entry(id.clone())
+            .await?
+            .ok_or_else(|| WaitlistError::NotFound(id.clone()))?;
+        let was_offered = entry.has_open_offer(Utc::now());
+        if entry.status != WaitlistStatus::Waiting && !was_offered {
+            return Err(WaitlistError::NotWaiting(id));
+        }
+
+        let entry = WaitlistEntry {
+            status: WaitlistStatus::Withdrawn,
+            ..entry
+        };
+        self.repo.save_waitlist_entry(&entry).await?;
+        if was_offered {
+            self.offer_waitlist_seats(entry.trip.clone()).await?;
+        }
+
+        Ok(entry)
+    }
+
+    async fn promote_waitlists(&self) -> Result<Vec<WaitlistEntry>, WaitlistError> {
+        let mut offered = vec![];
+        for trip in self.repo.find_waitlisted_trips(Utc::now()).await? {
+            offered.extend(self.offer_waitlist_seats(trip).await?);
+        }
+
+        Ok(offered)
+    }
+
+    async fn reschedule_booking(
+        &self,
+        request: RescheduleBookingRequest,
+    ) -> Result<Booking, BookingError> {
+        let booking = self
+            .repo
+            .find_booking(request.id.clone())
+            .await?
+            .ok_or(BookingError::NotFound(request.id))?;
+        if !booking.status.is_open() {
+            return Err(BookingError::Closed(booking.id, booking.status));
+        }
+        if booking.hold_expired(Utc::now()) {
+            return Err(BookingError::HoldExpired(booking.id));
+        }
+        if booking.trip == request.trip {
+            return Ok(booking);
+        }
+
+        let from = self
+            .repo
+            .find_trip(booking.trip.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+        let to = self
+            .repo
+            .find_trip(request.trip.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .ok_or(BookingError::TripNotFound(request.trip))?;
+        if to.start_time <= Utc::now() {
+            return Err(BookingError::TripStarted(to.id));
+        }
+        if !from.is_compatible_with(&to) {
+            return Err(BookingError::IncompatibleTrip {
+                from: from.id,
+                to: to.id,
+            });
+        }
+
+        // Moving off the trip resolves any overbooking there; the new trip is checked afresh.
+        let mut booking = Booking {
+            trip: to.id.clone(),
+            overbooked: false,
+            ..booking
+        };
+        ensure_eligible(&to, &booking)?;
+        booking.overbooked = self
+            .repo
+            .reschedule_booking(&booking, self.overbooking)
+            .await?;
+
+        // The booking has moved either way; offers missed here are made by the next sweep.
+        if let Err(e) = self.offer_waitlist_seats(from.id).await {
+            tracing::warn!(booking_id = %booking.id.0, "failed to offer freed seats: {e}");
+        }
+
+        Ok(booking)
+    }
+
+    async fn confirm_booking(&self, id: BookingId) -> Result<Booking, BookingError> {
+        let booking = self
+            .repo
+            .find_booking(id.clone())
+            .await?
+            .ok_or(BookingError::NotFound(id))?;
+        if booking.hold_expired(Utc::now()) {
+            return Err(BookingError::HoldExpired(booking.id));
+        }
+
+        // The sweeper may cancel the hold meanwhile, so it's only confirmed if still held.
+        let booking = booking.transition(BookingStatus::Confirmed)?;
+        self.repo
+            .confirm_hold(booking.id.clone(), Utc::now())
+            .await?;
+
+        Ok(booking)
+    }
+
+    async fn release_expired_holds(&self) -> Result<Vec<BookingId>, BookingError> {
+        self.repo.cancel_expired_holds(Utc::now()).await
+    }
+
+    async fn complete_booking(&self, id: BookingId) -> Result<Booking, BookingError> {
+        self.transition_booking(id, BookingStatus::Completed).await
+    }
+
+    async fn mark_booking_no_show(&self, id: BookingId) -> Result<Booking, BookingError> {
+        self.transition_booking(id, BookingStatus::NoShow).await
+    }
+
+    async fn find_missing_waivers(
+        &self,
+        id: BookingId,
+    ) -> Result<Vec<MissingWaiver>, BookingError> {
+        let booking = self
+            .repo
+            .find_booking(id.clone())
+            .await?
+            .ok_or(BookingError::NotFound(id))?;
+
+        self.missing_waivers(&booking).await
+    }
+
+    async fn check_in_booking(&self, id: BookingId) -> Result<Booking, BookingError> {
+        let booking = self
+            .repo
+            .find_booking(id.clone())
+            .await?
+            .ok_or(BookingError::NotFound(id))?;
+        if booking.status == BookingStatus::CheckedIn {
+            return Ok(booking);
+        }
+        let from = booking.status;
+        let booking = booking.transition(BookingStatus::CheckedIn)?;
+
+        let missing = self.missing_waivers(&booking).await?;
+        if !missing.is_empty() {
+            return Err(BookingError::WaiversMissing(
+                missing.into_iter().map(|m| m.participant).collect(),
+            ));
+        }
+
+        let booking = Booking {
+            checked_in_at: Some(Utc::now()),
+            ..booking
+        };
+        self.repo.save_booking_status(&booking, from).await?;
+
+        Ok(booking)
+    }
+
+    async fn create_waiver(&self, request: CreateWaiverRequest) -> Result<Waiver, WaiverError> {
+        if request.content.trim().is_empty() {
+            return Err(WaiverError::EmptyContent);
+        }
+
+        let (id, version) = match request.id {
+            None => (WaiverId(Uuid::now_v7()), 1),
+            Some(id) => {
+                let latest = self
+                    .repo
+                    .find_latest_waiver(id.clone())
+                    .await?
+                    .ok_or(WaiverError::NotFound(id))?;
+                (latest.id, latest.version + 1)
+            }
+        };
+
+        let waiver = Waiver {
+            id,
+            version,
+            content: request.content,
+            effective_at: request.effective_at.unwrap_or_else(Utc::now),
+        };
+        self.repo.save_waiver(&waiver).await?;
+
+        Ok(waiver)
+    }
+
+    async fn find_current_waiver(&self, id: WaiverId) -> Result<Option<Waiver>, WaiverError> {
+        self.repo.find_current_waiver(id, Utc::now()).await
+    }
+
+    async fn sign_waiver(
+        &self,
+        request: SignWaiverRequest,
+    ) -> Result<WaiverSignature, WaiverError> {
+        if request.signer.name.trim().is_empty() {
+            return Err(WaiverError::InvalidSignerName);
+        }
+        if !request.signature_image.starts_with(PNG_SIGNATURE) {
+            return Err(WaiverError::InvalidSignatureImage);
+        }
+
+        let participant = self
+            .repo
+            .find_participant(request.participant.clone())
+            .await
+            .map_err(|e| WaiverError::Unknown(e.into()))?
+            .ok_or_else(|| WaiverError::ParticipantNotFound(request.participant.clone()))?;
+        let kind = self.participant_trip_kind(&participant).await?;
+        if kind.as_ref().and_then(|k| k.waiver.as_ref()) != Some(&request.waiver) {
+            return Err(WaiverError::NotRequired {
+                waiver: request.waiver,
+                participant: participant.id,
+            });
+        }
+
+        let now = Utc::now();
+        let waiver = self
+            .repo
+            .find_current_waiver(request.waiver.clone(), now)
+            .await?
+            .ok_or_else(|| WaiverError::NotFound(request.waiver.clone()))?;
+        if waiver.version != request.version {
+            return Err(WaiverError::OutdatedVersion(request.version));
+        }
+        let content_hash = waiver.content_hash();
+        if !content_hash.eq_ignore_ascii_case(&request.content_hash) {
+            return Err(WaiverError::ContentMismatch);
+        }
+
+        let is_minor = kind
+            .and_then(|k| k.eligibility.guardian_required_under)
+            .is_some_and(|under| participant.age_on(now.date_naive()) < under);
+        match (is_minor, &request.signer.guardian_relationship) {
+            (true, None) => return Err(WaiverError::GuardianRequired(participant.id)),
+            (false, Some(_)) => return Err(WaiverError::GuardianNotAllowed(participant.id)),
+            _ => {}
+        }
+
+        let signature_image = self
+            .blobs
+            .put_blob(&request.signature_image, "png")
+            .await
+            .map_err(|e| WaiverError::Unknown(e.into()))?;
+
+        let signature = WaiverSignature {
+            id: WaiverSignatureId(Uuid::now_v7()),
+            participant: participant.id,
+            waiver: waiver.id,
+            version: waiver.version,
+            content_hash: Some(content_hash),
+            signed_at: now,
+            signer: Some(request.signer),
+            signature_image: Some(signature_image.clone()),
+        };
+        if let Err(e) = self.repo.save_waiver_signature(&signature).await {
+            if let Err(e) = self.blobs.delete_blob(&signature_image).await {
+                tracing::warn!(key = %signature_image.0, "failed to delete orphaned blob: {e}");
+            }
+            return Err(e);
+        }
+
+        Ok(signature)
+    }
+
+    async fn create_customer(
+        &self,
+        request: CreateCustomerRequest,
+    ) -> Result<Customer, CustomerError> {
+        let customer = Customer::new(request, self.phone_region)?;
+        self.repo.save_customer(&customer).await?;
+
+        Ok(customer)
+    }
+
+    async fn find_customer(&self, id: CustomerId) -> Result<Option<Customer>, CustomerError> {
+        self.repo.find_customer(id).await
+    }
+
+    async fn edit_customer(&self, request: EditCustomerRequest) -> Result<Customer, CustomerError> {
+        let customer = self
+            .repo
+            .find_customer(request.id.clone())
+            .await?
+            .ok_or_else(|| CustomerError::NotFound(request.id.clone()))?
+            .edit(request, self.phone_region)?;
+
+        self.repo.save_customer(&customer).await?;
+
+        Ok(customer)
+    }
+
+    async fn search_customers(
+        &self,
+        query: &str,
+        page: Page,
+    ) -> Result<CustomerSearchResults, CustomerError> {
+        let search = CustomerSearch::new(query, self.phone_region, page)?;
+
+        self.repo.search_customers(&search).await
+    }
+
+    async fn find_duplicate_customers(
+        &self,
+        page: Page,
+    ) -> Result<Vec<DuplicateCandidate>, CustomerError> {
+        self.repo.find_duplicate_customers(page).await
+    }
+
+    async fn merge_customers(
+        &self,
+        request: MergeCustomersRequest,
+    ) -> Result<CustomerMerge, CustomerError> {
+        if request.survivor == request.merged {
+            return Err(CustomerError::MergeIntoSelf(request.survivor));
+        }
+
+        self.repo.merge_customers(&request).await
+    }
+
+    async fn find_customer_merges(
+        &self,
+        id: CustomerId,
+    ) -> Result<Vec<CustomerMerge>, CustomerError> {
+        self.repo.find_customer_merges(id).await
+    }
+
+    async fn normalize_customer_phones(&self) -> Result<PhoneNormalization, CustomerError> {
+        let mut result = PhoneNormalization::default();
+        let mut page = Page {
+            limit: Page::MAX_LIMIT,
+            offset: 0,
+        };
+        loop {
+            let customers = self.repo.find_customers(page).await?;
+            let last_page = (customers.len() as i64) < page.limit;
+
+            for customer in customers {
+                let Ok(phone) = PhoneNumber::parse(&customer.phone.0, self.phone_region) else {
+                    result.invalid.push(customer.id);
+                    continue;
+                };
+                if phone != customer.phone {
+                    let customer = Customer { phone, ..customer };
+                    self.repo.save_customer(&customer).await?;
+                    result.normalized.push(customer.id);
+                }
+            }
+
+            if last_page {
+                return Ok(result);
+            }
+            page.offset += page.limit;
+        }
+    }
+
+    async fn find_trip(&self, id: TripId) -> Result<Option<Trip>, TripError> {
+        self.repo.find_trip(id).await
+    }
+
+    async fn find_trips(&self, filters: &TripFilters) -> Result<Vec<Trip>, TripError> {
+        if filters.is_empty() {
+            return Err(TripError::MissingFilters);
+        }
+        if let Some((start, end)) = filters.date_range {
+            if end < start {
+                return Err(TripError::InvalidDateRange);
+            }
+        }
+
+        self.repo.find_trips(filters).await
+    }
+
+    async fn find_booking_rentals(
+        &self,
+        booking_id: BookingId,
+    ) -> Result<BookingRentals, EquipmentError> {
+        self.ensure_booking_exists(&booking_id).await?;
+
+        self.repo.find_booking_rentals(booking_id).await
+    }
+
+    async fn save_booking_rentals(
+        &self,
+        booking_rentals: &BookingRentals,
+    ) -> Result<(), EquipmentError> {
+        for (equipment, quantity) in &booking_rentals.rentals {
+            if *quantity <= 0 {
+                return Err(EquipmentError::InvalidQuantity {
+                    equipment: equipment.clone(),
+                    quantity: *quantity,
+                });
+            }
+        }
+
+        self.ensure_booking_exists(&booking_rentals.booking_id)
+            .await?;
+
+        self.repo.save_booking_rentals(booking_rentals).await
+    }
+
+    async fn find_equipment_availability(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<EquipmentAvailability, EquipmentError> {
+        if end_time < start_time {
+            return Err(EquipmentError::InvalidTimeWindow);
+        }
+
+        self.repo
+            .find_equipment_availability(start_time, end_time)
+            .await
+    }
+
+    async fn quote_booking(&self, request: &QuoteRequest) -> Result<Quote, PricingError> {
+        let trip = self
+            .repo
+            .find_trip(request.trip.clone())
+            .await
+            .map_err(|e| PricingError::Unknown(e.into()))?
+            .ok_or_else(|| PricingError::TripNotFound(request.trip.clone()))?;
+
+        self.repo
+            .find_price_list(trip.kind.id.clone())
+            .await?
+            .quote(&trip, request)
+    }
+
+    async fn authorize_payment(
+        &self,
+        request: AuthorizePaymentRequest,
+    ) -> Result<Payment, PaymentError> {
+        if request.amount <= Money::default() {
+            return Err(PaymentError::InvalidAmount(request.amount));
+        }
+        let booking = self
+            .repo
+            .find_booking(request.booking.clone())
+            .await
+            .map_err(|e| PaymentError::Unknown(e.into()))?
+            .ok_or(PaymentError::BookingNotFound(request.booking))?;
+
+        let result = self
+            .payments
+            .authorize(&request.method, request.amount)
+            .await;
+
+        self.record_payment(
+            booking.id,
+            PaymentKind::Authorization,
+            None,
+            request.amount,
+            result,
+        )
+        .await
+    }
+
+    async fn capture_payment(
+        &self,
+        request: CapturePaymentRequest,
+    ) -> Result<Payment, PaymentError> {
+        let (authorization, amount) = self
+            .settleable(
+                request.authorization,
+                PaymentKind::Authorization,
+                request.amount,
+            )
+            .await?;
+
+        let reference = authorization
+            .reference
+            .as_ref()
+            .ok_or_else(|| PaymentError::InvalidOperation(authorization.id.clone(), "captured"))?;
+        let result = self.payments.capture(reference, amount).await;
+
+        self.record_payment(
+            authorization.booking,
+            PaymentKind::Capture,
+            Some(authorization.id),
+            amount,
+            result,
+        )
+        .await
+    }
+
+    async fn refund_payment(&self, request: RefundPaymentRequest) -> Result<Payment, PaymentError> {
+        let (capture, amount) = self
+            .settleable(request.capture, PaymentKind::Capture, request.amount)
+            .await?;
+
+        let reference = capture
+            .reference
+            .as_ref()
+            .ok_or_else(|| PaymentError::InvalidOperation(capture.id.clone(), "refunded"))?;
+        let result = self.payments.refund(reference, amount).await;
+
+        self.record_payment(
+            capture.booking,
+            PaymentKind::Refund,
+            Some(capture.id),
+            amount,
+            result,
+        )
+        .await
+    }
+
+    async fn find_payment_ledger(&self, booking: BookingId) -> Result<PaymentLedger, PaymentError> {
+        let exists = self
+            .repo
+            .find_booking(booking.clone())
+            .await
+            .map_err(|e| PaymentError::Unknown(e.into()))?
+            .is_some();
+        if !exists {
+            return Err(PaymentError::BookingNotFound(booking));
+        }
+
+        self.repo.find_payment_ledger(booking).await
+    }
+}
+
+impl<R: UnitOfWork, B: BlobStore, P: PaymentGateway> Service<R, B, P> {
+    /// Offers a trip's free seats to its waitlist, for the configured offer duration.
+    async fn offer_waitlist_seats(
+        &self,
+        trip: TripId,
+    ) -> Result<Vec<WaitlistEntry>, WaitlistError> {
+        let now = Utc::now();
+        self.repo
+            .offer_waitlist_seats(trip, now, now + self.waitlist_offer_duration)
+            .await
+    }
+
+    /// Rejects a booking added to a reference that none of its customer's bookings have.
+    async fn ensure_reference_owned(
+        &self,
+        repo: &R,
+        booking: &Booking,
+    ) -> Result<(), BookingError> {
+        let filters = BookingFilters {
+            reference: Some(booking.reference.clone()),
+            customer: Some(booking.customer.clone()),
+            ..BookingFilters::default()
+        };
+        if repo.find_bookings(&filters).await?.is_empty() {
+            return Err(BookingError::UnknownReference(booking.reference.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects a booking made from a waitlist entry that hasn't been offered
+    /// enough seats on the booking's trip, or whose offer has expired.
+    async fn ensure_waitlist_offer(
+        &self,
+        repo: &R,
+        id: &WaitlistEntryId,
+        booking: &Booking,
+    ) -> Result<(), BookingError> {
+        let entry = repo
+            .find_waitlist_entry(id.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .filter(|e| {
+                e.customer == booking.customer
+                    && e.trip == booking.trip
+                    && e.has_open_offer(Utc::now())
+            })
+            .ok_or_else(|| BookingError::NoWaitlistOffer(id.clone()))?;
+        if booking.participants.len() > entry.party_size as usize {
+            return Err(BookingError::ExceedsWaitlistOffer {
+                entry: entry.id,
+                offered: entry.party_size,
+                requested: booking.participants.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Gets the participants of a booking without a signature for the current version
+    /// of the waiver required by the booking's trip.
+    /// Gets the kind of trip a participant is booked on, or [None] if their booking is gone.
+    async fn participant_trip_kind(
+        &self,
+        participant: &Participant,
+    ) -> Result<Option<TripKind>, WaiverError> {
+        let bookings = self
+            .repo
+            .find_bookings(&BookingFilters {
+                participant: Some(participant.id.clone()),
+                ..BookingFilters::default()
+            })
+            .await
+            .map_err(|e| WaiverError::Unknown(e.into()))?;
+        let Some(booking) = bookings.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let trip = self
+            .repo
+            .find_trip(booking.trip)
+            .await
+            .map_err(|e| WaiverError::Unknown(e.into()))?;
+
+        Ok(trip.map(|t| t.kind))
+    }
+
+    async fn missing_waivers(&self, booking: &Booking) -> Result<Vec<MissingWaiver>, BookingError> {
+        let trip = self
+            .repo
+            .find_trip(booking.trip.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+        let Some(waiver_id) = trip.kind.waiver else {
+            return Ok(vec![]);
+        };
+
+        let waiver = self
+            .repo
+            .find_current_waiver(waiver_id, Utc::now())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?;
+        let Some(waiver) = waiver else {
+            // No version of the waiver is in force yet, so there's nothing to sign.
+            return Ok(vec![]);
+        };
+
+        let participants = booking
+            .participants
+            .iter()
+            .map(|p| p.id.clone())
+            .collect::<Vec<_>>();
+        let signatures = self
+            .repo
+            .find_waiver_signatures(&participants)
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?;
+
+        Ok(participants
+            .into_iter()
+            .filter(|participant| {
+                !signatures.iter().any(|s| {
+                    &s.participant == participant
+                        && s.waiver == waiver.id
+                        && s.version == waiver.version
+                })
+            })
+            .map(|participant| MissingWaiver {
+                participant,
+                waiver: waiver.id.clone(),
+                version: waiver.version,
+            })
+            .collect())
+    }
+
+    /// Moves an existing booking to a status that needs no other changes. A booking can only be
+    /// a no-show once its trip has started, and only be completed once its trip has ended.
+    async fn transition_booking(
+        &self,
+        id: BookingId,
+        status: BookingStatus,
+    ) -> Result<Booking, BookingError> {
+        let booking = self
+            .repo
+            .find_booking(id.clone())
+            .await?
+            .ok_or(BookingError::NotFound(id))?;
+        let from = booking.status;
+        let booking = booking.transition(status)?;
+
+        let trip = self
+            .repo
+            .find_trip(booking.trip.clone())
+            .await
+            .map_err(|e| BookingError::Unknown(e.into()))?
+            .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+        let now = Utc::now();
+        match status {
+            BookingStatus::NoShow if trip.start_time > now => {
+                return Err(BookingError::TripNotStarted(trip.id));
+            }
+            BookingStatus::Completed if trip.end_time > now => {
+                return Err(BookingError::TripNotEnded(trip.id));
+            }
+            _ => {}
+        }
+
+        self.repo.save_booking_status(&booking, from).await?;
+
+        Ok(booking)
+    }
+
+    /// Gets a successful payment of the given kind along with the amount to settle
+    /// (capture or refund) from it, defaulting to everything that remains.
+    async fn settleable(
+        &self,
+        id: PaymentId,
+        kind: PaymentKind,
+        amount: Option<Money>,
+    ) -> Result<(Payment, Money), PaymentError> {
+        let payment = self
+            .repo
+            .find_payment(id.clone())
+            .await?
+            .ok_or_else(|| PaymentError::NotFound(id.clone()))?;
+        if payment.kind != kind || payment.status != PaymentStatus::Succeeded {
+            let operation = match kind {
+                PaymentKind::Authorization => "captured",
+                _ => "refunded",
+            };
+            return Err(PaymentError::InvalidOperation(id, operation));
+        }
+
+        let remaining = self
+            .repo
+            .find_payment_ledger(payment.booking.clone())
+            .await?
+            .remaining(&payment);
+        let amount = amount.unwrap_or(remaining);
+        if amount <= Money::default() {
+            return Err(PaymentError::InvalidAmount(amount));
+        }
+        if amount > remaining {
+            return Err(PaymentError::ExceedsRemaining {
+                payment: id,
+                requested: amount,
+                remaining,
+            });
+        }
+
+        Ok((payment, amount))
+    }
+
+    /// Appends the outcome of a payment provider operation to a booking's ledger.
+    /// Declines are recorded too, but still returned as errors.
+    async fn record_payment(
+        &self,
+        booking: BookingId,
+        kind: PaymentKind,
+        parent: Option<PaymentId>,
+        amount: Money,
+        result: Result<ProviderReference, PaymentError>,
+    ) -> Result<Payment, PaymentError> {
+        let (status, reference) = match result {
+            Ok(reference) => (PaymentStatus::Succeeded, Some(reference)),
+            Err(PaymentError::Declined(reason)) => (PaymentStatus::Declined(reason), None),
+            Err(e) => return Err(e),
+        };
+
+        let payment = Payment {
+            id: PaymentId(Uuid::now_v7()),
+            booking,
+            kind,
+            parent,
+            amount,
+            status,
+            reference,
+            created_at: Utc::now(),
+        };
+        self.repo.save_payment(&payment).await?;
+
+        match payment.status {
+            PaymentStatus::Succeeded => Ok(payment),
+            PaymentStatus::Declined(reason) => Err(PaymentError::Declined(reason)),
+        }
+    }
+
+    async fn ensure_booking_exists(&self, id: &BookingId) -> Result<(), EquipmentError> {
+        let booking = self
+            .repo
+            .find_booking(id.clone())
+            .await
+            .map_err(|e| EquipmentError::Unknown(e.into()))?;
+
+        match booking {
+            Some(_) => Ok(()),
+            None => Err(EquipmentError::BookingNotFound(id.clone())),
+        }
+    }
+}
+
+/// Rejects a booking if any of its participants don't meet the eligibility rules of its trip.
+fn ensure_eligible(trip: &Trip, booking: &Booking) -> Result<(), BookingError> {
+    let violations = trip
+        .kind
+        .eligibility
+        .violations(&booking.participants, trip.local_date());
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(BookingError::Ineligible(violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::booking::models::booking::ParticipantId;
+    use crate::domain::booking::models::booking::{
+        CreateParticipantRequest, EditParticipantRequest,
+    };
+    use crate::domain::booking::models::payment::PaymentMethod;
+    use crate::domain::booking::models::pricing::ParticipantCategory;
+    use crate::domain::booking::models::trip::{
+        CancellationPolicy, Eligibility, LocationId, RefundTier, TripKind, TripKindId,
+    };
+    use crate::domain::booking::models::waiver::Signer;
+    use crate::outbound::fake_payments::FakePaymentGateway;
+    use crate::outbound::filesystem::LocalBlobStore;
+    use crate::outbound::memory::Memory;
+    use chrono::{Months, NaiveDate};
+    use chrono_tz::Tz;
+    use std::collections::HashMap;
+
+    type TestService = Service<Memory, LocalBlobStore, FakePaymentGateway>;
+
+    async fn service(repo: &Memory) -> TestService {
+        let blobs = LocalBlobStore::new(std::env::temp_dir().join("tide-service-tests"))
+            .await
+            .unwrap();
+
+        Service::new(repo.clone(), blobs, FakePaymentGateway::new())
+    }
+
+    fn trip_kind(max_participants: Option<i32>) -> TripKind {
+        let id = Uuid::now_v7();
+
+        TripKind {
+            id: TripKindId(id),
+            name: format!("Sea Kayak Tour {id}"),
+            description: "A paddle around the bay".to_string(),
+            guided: true,
+            meal_provided: false,
+            max_participants,
+            waiver: None,
+            eligibility: Eligibility {
+                requirements: vec!["Able to swim".to_string()],
+                ..Eligibility::default()
+            },
+            cancellation_policy: CancellationPolicy {
+                tiers: vec![
+                    RefundTier {
+                        notice: Duration::days(7),
+                        percent: 100,
+                    },
+                    RefundTier {
+                        notice: Duration::days(1),
+                        percent: 50,
+                    },
+                ],
+            },
+        }
+    }
+
+    /// Adds a trip of the given kind starting `starts_in` from now.
+    fn seeded_trip(repo: &Memory, kind: &TripKind, starts_in: Duration) -> Trip {
+        let start_time = Utc::now() + starts_in;
+        let trip = Trip {
+            id: TripId(Uuid::now_v7()),
+            kind: kind.clone(),
+            location: LocationId(Uuid::now_v7()),
+            start_time,
+            end_time: start_time + Duration::hours(3),
+            max_participants: None,
+            timezone: Tz::UTC,
+        };
+        repo.insert_trip(trip.clone());
+
+        trip
+    }
+
+    /// Moves a trip so that it ended an hour ago.
+    fn finish_trip(repo: &Memory, trip: &Trip) {
+        repo.insert_trip(Trip {
+            start_time: Utc::now() - Duration::hours(4),
+            end_time: Utc::now() - Duration::hours(1),
+            ..trip.clone()
+        });
+    }
+
+    async fn seeded_customer(service: &TestService) -> Customer {
+        let id = Uuid::now_v7();
+        service
+            .create_customer(CreateCustomerRequest {
+                name: "Ana Reyes".to_string(),
+                email: format!("ana.{id}@example.com"),
+                phone: "+14155550100".to_string(),
+            })
+            .await
+            .unwrap()
+    }
+
+    fn participant(name: &str) -> CreateParticipantRequest {
+        CreateParticipantRequest {
+            name: name.to_string(),
+            dob: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+            notes: String::new(),
+        }
+    }
+
+    fn booking_request(customer: &Customer, trip: &Trip, names: &[&str]) -> CreateBookingRequest {
+        CreateBookingRequest {
+            reference: None,
+            customer: customer.id.clone(),
+            trip: trip.id.clone(),
+            participants: names.iter().map(|name| participant(name)).collect(),
+            waitlist_entry: None,
+            hold: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn bookings_move_through_their_lifecycle() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let customer = seeded_customer(&service).await;
+        let trip = seeded_trip(&repo, &trip_kind(None), Duration::days(2));
+
+        let booking = service
+            .create_booking(booking_request(&customer, &trip, &["Ana"]))
+            .await
+            .unwrap();
+        assert_eq!(booking.status, BookingStatus::Confirmed);
+        let absent = service
+            .create_booking(booking_request(&customer, &trip, &["Bob"]))
+            .await
+            .unwrap();
+
+        let checked_in = service.check_in_booking(booking.id.clone()).await.unwrap();
+        assert_eq!(checked_in.status, BookingStatus::CheckedIn);
+        assert!(checked_in.checked_in_at.is_some());
+
+        let result = service.complete_booking(booking.id.clone()).await;
+        assert!(matches!(result, Err(BookingError::TripNotEnded(id)) if id == trip.id));
+        let result = service.mark_booking_no_show(absent.id.clone()).await;
+        assert!(matches!(result, Err(BookingError::TripNotStarted(id)) if id == trip.id));
+
+        finish_trip(&repo, &trip);
+        let completed = service.complete_booking(booking.id.clone()).await.unwrap();
+        assert_eq!(completed.status, BookingStatus::Completed);
+        let no_show = service
+            .mark_booking_no_show(absent.id.clone())
+            .await
+            .unwrap();
+        assert_eq!(no_show.status, BookingStatus::NoShow);
+
+        let result = service
+            .cancel_booking(CancelBookingRequest {
+                id: booking.id.clone(),
+                reason: "changed plans".to_string(),
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(BookingError::InvalidTransition {
+                from: BookingStatus::Completed,
+                to: BookingStatus::Cancelled,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn bookings_are_only_held_when_asked_to_be() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let customer = seeded_customer(&service).await;
+        let trip = seeded_trip(&repo, &trip_kind(None), Duration::days(2));
+
+        let confirmed = service
+            .create_booking(booking_request(&customer, &trip, &["Ana"]))
+            .await
+            .unwrap();
+        assert_eq!(confirmed.status, BookingStatus::Confirmed);
+        assert_eq!(confirmed.hold_expires_at, None);
+
+        let held = service
+            .create_booking(CreateBookingRequest {
+                hold: true,
+                ..booking_request(&customer, &trip, &["Bob"])
+            })
+            .await
+            .unwrap();
+        assert_eq!(held.status, BookingStatus::Held);
+        assert!(held.hold_expires_at.is_some());
+
+        let result = service.confirm_booking(confirmed.id).await;
+        assert!(matches!(
+            result,
+            Err(BookingError::InvalidTransition { .. })
+        ));
+        let held = service.confirm_booking(held.id.clone()).await.unwrap();
+        assert_eq!(held.status, BookingStatus::Confirmed);
+        assert_eq!(
+            service.find_booking(held.id.clone()).await.unwrap(),
+            Some(held)
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_holds_are_released_and_cannot_be_changed() {
+        let repo = Memory::new();
+        let service = service(&repo).await.with_hold_duration(Duration::zero());
+        let customer = seeded_customer(&service).await;
+        let trip = seeded_trip(&repo, &trip_kind(None), Duration::days(2));
+
+        let held = service
+            .create_booking(CreateBookingRequest {
+                hold: true,
+                ..booking_request(&customer, &trip, &["Ana"])
+            })
+            .await
+            .unwrap();
+
+        let result = service.confirm_booking(held.id.clone()).await;
+        assert!(matches!(result, Err(BookingError::HoldExpired(id)) if id == held.id));
+        let result = service
+            .edit_booking(EditBookingRequest {
+                id: held.id.clone(),
+                participants: vec![],
+            })
+            .await;
+        assert!(matches!(result, Err(BookingError::HoldExpired(id)) if id == held.id));
+        let other = seeded_trip(&repo, &trip.kind, Duration::days(3));
+        let result = service
+            .reschedule_booking(RescheduleBookingRequest {
+                id: held.id.clone(),
+                trip: other.id,
+            })
+            .await;
+        assert!(matches!(result, Err(BookingError::HoldExpired(id)) if id == held.id));
+
+        let released = service.release_expired_holds().await.unwrap();
+        assert_eq!(released, vec![held.id.clone()]);
+        let result = service.confirm_booking(held.id.clone()).await;
+        assert!(matches!(
+            result,
+            Err(BookingError::InvalidTransition {
+                from: BookingStatus::Cancelled,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancel_booking_refunds_by_notice_given() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let customer = seeded_customer(&service).await;
+        let kind = trip_kind(None);
+
+        for (starts_in, refund) in [
+            (Duration::days(10), Money(10_000)),
+            (Duration::days(3), Money(5_000)),
+            (Duration::hours(12), Money(0)),
+        ] {
+            let trip = seeded_trip(&repo, &kind, starts_in);
+            let booking = service
+                .create_booking(booking_request(&customer, &trip, &["Ana"]))
+                .await
+                .unwrap();
+            let authorization = service
+                .authorize_payment(AuthorizePaymentRequest {
+                    booking: booking.id.clone(),
+                    amount: Money(10_000),
+                    method: PaymentMethod("tok_visa".to_string()),
+                })
+                .await
+                .unwrap();
+            service
+                .capture_payment(CapturePaymentRequest {
+                    authorization: authorization.id,
+                    amount: None,
+                })
+                .await
+                .unwrap();
+
+            let cancelled = service
+                .cancel_booking(CancelBookingRequest {
+                    id: booking.id,
+                    reason: "changed plans".to_string(),
+                })
+                .await
+                .unwrap();
+            assert_eq!(cancelled.status, BookingStatus::Cancelled);
+            assert_eq!(cancelled.cancellation.unwrap().refund, refund);
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_seats_are_offered_to_the_waitlist() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let customer = seeded_customer(&service).await;
+        let trip = seeded_trip(&repo, &trip_kind(Some(2)), Duration::days(2));
+
+        let booking = service
+            .create_booking(booking_request(&customer, &trip, &["Ana", "Bob"]))
+            .await
+            .unwrap();
+        let position = service
+            .join_waitlist(JoinWaitlistRequest {
+                customer: customer.id.clone(),
+                trip: trip.id.clone(),
+                party_size: 2,
+            })
+            .await
+            .unwrap();
+        assert_eq!(position.entry.status, WaitlistStatus::Waiting);
+
+        let result = service
+            .create_booking(booking_request(&customer, &trip, &["Cleo"]))
+            .await;
+        assert!(matches!(result, Err(BookingError::TripFull { .. })));
+
+        service
+            .cancel_booking(CancelBookingRequest {
+                id: booking.id,
+                reason: "changed plans".to_string(),
+            })
+            .await
+            .unwrap();
+        let entry = service
+            .find_waitlist_entry(position.entry.id.clone())
+            .await
+            .unwrap()
+            .unwrap()
+            .entry;
+        assert!(entry.has_open_offer(Utc::now()));
+
+        // The offered seats are kept for the waitlisted party.
+        let result = service
+            .create_booking(booking_request(&customer, &trip, &["Cleo"]))
+            .await;
+        assert!(matches!(result, Err(BookingError::TripFull { .. })));
+
+        let booking = service
+            .create_booking(CreateBookingRequest {
+                waitlist_entry: Some(entry.id.clone()),
+                ..booking_request(&customer, &trip, &["Dev", "Eli"])
+            })
+            .await
+            .unwrap();
+        assert_eq!(booking.status, BookingStatus::Confirmed);
+        let entry = service
+            .find_waitlist_entry(entry.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .entry;
+        assert_eq!(entry.status, WaitlistStatus::Accepted);
+    }
+
+    #[tokio::test]
+    async fn reschedule_booking_checks_the_new_trip() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let customer = seeded_customer(&service).await;
+        let kind = trip_kind(Some(2));
+        let from = seeded_trip(&repo, &kind, Duration::days(2));
+        let full = seeded_trip(&repo, &kind, Duration::days(3));
+        let to = seeded_trip(&repo, &kind, Duration::days(4));
+        let other_kind = seeded_trip(&repo, &trip_kind(Some(2)), Duration::days(4));
+
+        let booking = service
+            .create_booking(booking_request(&customer, &from, &["Ana", "Bob"]))
+            .await
+            .unwrap();
+        service
+            .create_booking(booking_request(&customer, &full, &["Cleo", "Dev"]))
+            .await
+            .unwrap();
+        let reschedule = |trip: &Trip| RescheduleBookingRequest {
+            id: booking.id.clone(),
+            trip: trip.id.clone(),
+        };
+
+        let result = service.reschedule_booking(reschedule(&other_kind)).await;
+        assert!(matches!(result, Err(BookingError::IncompatibleTrip { .. })));
+        let result = service.reschedule_booking(reschedule(&full)).await;
+        assert!(matches!(result, Err(BookingError::TripFull { .. })));
+
+        finish_trip(&repo, &to);
+        let result = service.reschedule_booking(reschedule(&to)).await;
+        assert!(matches!(result, Err(BookingError::TripStarted(id)) if id == to.id));
+
+        let to = seeded_trip(&repo, &kind, Duration::days(5));
+        let moved = service.reschedule_booking(reschedule(&to)).await.unwrap();
+        assert_eq!(moved.trip, to.id);
+        assert_eq!(
+            service.find_booking(booking.id.clone()).await.unwrap(),
+            Some(moved)
+        );
+    }
+
+    #[tokio::test]
+    async fn participants_cannot_be_booked_twice_on_a_trip() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let customer = seeded_customer(&service).await;
+        let other = seeded_customer(&service).await;
+        let trip = seeded_trip(&repo, &trip_kind(None), Duration::days(2));
+
+        let checked_in = service
+            .create_booking(booking_request(&customer, &trip, &["Ana Reyes"]))
+            .await
+            .unwrap();
+        service
+            .check_in_booking(checked_in.id.clone())
+            .await
+            .unwrap();
+        let cancelled = service
+            .create_booking(booking_request(&customer, &trip, &["Bob Smith"]))
+            .await
+            .unwrap();
+        service
+            .cancel_booking(CancelBookingRequest {
+                id: cancelled.id.clone(),
+                reason: "changed plans".to_string(),
+            })
+            .await
+            .unwrap();
+
+        // Checked-in participants are still on the trip, whoever books them again.
+        let result = service
+            .create_booking(booking_request(&other, &trip, &["ana reyes"]))
+            .await;
+        assert!(
+            matches!(result, Err(BookingError::AlreadyBooked { booking, .. }) if booking == checked_in.id)
+        );
+
+        let rebooked = service
+            .create_booking(booking_request(&other, &trip, &["Bob Smith"]))
+            .await
+            .unwrap();
+        let result = service
+            .edit_booking(EditBookingRequest {
+                id: rebooked.id,
+                participants: vec![
+                    EditParticipantRequest {
+                        id: Some(rebooked.participants[0].id.clone()),
+                        name: "Bob Smith".to_string(),
+                        dob: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+                        notes: String::new(),
+                    },
+                    EditParticipantRequest {
+                        id: None,
+                        name: "Ana Reyes".to_string(),
+                        dob: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+                        notes: String::new(),
+                    },
+                ],
+            })
+            .await;
+        assert!(matches!(result, Err(BookingError::AlreadyBooked { .. })));
+    }
+
+    #[tokio::test]
+    async fn quotes_list_the_trip_requirements() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let kind = trip_kind(None);
+        let trip = seeded_trip(&repo, &kind, Duration::days(2));
+        repo.insert_base_price(kind.id.clone(), ParticipantCategory::Adult, Money(5_000));
+
+        let quote = service
+            .quote_booking(&QuoteRequest {
+                trip: trip.id.clone(),
+                participants: vec![participant("Ana"), participant("Bob")],
+                rentals: HashMap::new(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(quote.total, Money(10_000));
+        assert_eq!(quote.requirements, vec!["Able to swim".to_string()]);
+    }
+
+    fn signer(guardian_relationship: Option<&str>) -> Signer {
+        Signer {
+            name: "Ana Reyes".to_string(),
+            ip: None,
+            user_agent: None,
+            guardian_relationship: guardian_relationship.map(str::to_string),
+        }
+    }
+
+    fn sign_request(
+        waiver: &Waiver,
+        participant: &ParticipantId,
+        guardian_relationship: Option<&str>,
+    ) -> SignWaiverRequest {
+        SignWaiverRequest {
+            waiver: waiver.id.clone(),
+            participant: participant.clone(),
+            version: waiver.version,
+            content_hash: waiver.content_hash(),
+            signer: signer(guardian_relationship),
+            signature_image: [PNG_SIGNATURE, b"signature"].concat(),
+        }
+    }
+
+    /// Adds a trip requiring a new waiver, and books an adult and someone `minor_age` years
+    /// old on it.
+    async fn booked_waiver_trip(
+        repo: &Memory,
+        service: &TestService,
+        minor_age: u32,
+    ) -> (Waiver, Participant, Participant) {
+        let waiver = service
+            .create_waiver(CreateWaiverRequest {
+                id: None,
+                content: "I accept the risks of sea kayaking.".to_string(),
+                effective_at: None,
+            })
+            .await
+            .unwrap();
+        let mut kind = trip_kind(None);
+        kind.waiver = Some(waiver.id.clone());
+        kind.eligibility.guardian_required_under = Some(16);
+        let trip = seeded_trip(repo, &kind, Duration::days(2));
+        let customer = seeded_customer(service).await;
+
+        let today = Utc::now().date_naive();
+        let booking = service
+            .create_booking(CreateBookingRequest {
+                participants: vec![
+                    participant("Ana"),
+                    CreateParticipantRequest {
+                        dob: today
+                            .checked_sub_months(Months::new(12 * minor_age))
+                            .unwrap(),
+                        ..participant("Bea")
+                    },
+                ],
+                ..booking_request(&customer, &trip, &[])
+            })
+            .await
+            .unwrap();
+        let find = |name: &str| {
+            booking
+                .participants
+                .iter()
+                .find(|p| p.name == name)
+                .unwrap()
+                .clone()
+        };
+
+        (waiver, find("Ana"), find("Bea"))
+    }
+
+    #[tokio::test]
+    async fn sign_waiver_follows_the_trip_kinds_guardian_rule() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let (waiver, adult, minor) = booked_waiver_trip(&repo, &service, 10).await;
+
+        let result = service
+            .sign_waiver(sign_request(&waiver, &minor.id, None))
+            .await;
+        assert!(matches!(result, Err(WaiverError::GuardianRequired(id)) if id == minor.id));
+        let result = service
+            .sign_waiver(sign_request(&waiver, &adult.id, Some("parent")))
+            .await;
+        assert!(matches!(result, Err(WaiverError::GuardianNotAllowed(id)) if id == adult.id));
+
+        let signature = service
+            .sign_waiver(sign_request(&waiver, &minor.id, Some("parent")))
+            .await
+            .unwrap();
+        assert_eq!(signature.participant, minor.id);
+        assert_eq!(
+            signature.signer.unwrap().guardian_relationship.as_deref(),
+            Some("parent")
+        );
+        service
+            .sign_waiver(sign_request(&waiver, &adult.id, None))
+            .await
+            .unwrap();
+
+        // Minors at or over the trip kind's guardian age sign for themselves.
+        let (waiver, _, teen) = booked_waiver_trip(&repo, &service, 16).await;
+        service
+            .sign_waiver(sign_request(&waiver, &teen.id, None))
+            .await
+            .unwrap();
+        let result = service
+            .sign_waiver(sign_request(&waiver, &teen.id, Some("parent")))
+            .await;
+        assert!(matches!(result, Err(WaiverError::GuardianNotAllowed(id)) if id == teen.id));
+    }
+
+    #[tokio::test]
+    async fn sign_waiver_only_accepts_the_trips_waiver() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let (_, adult, _) = booked_waiver_trip(&repo, &service, 10).await;
+        let (other, _, _) = booked_waiver_trip(&repo, &service, 10).await;
+
+        let result = service
+            .sign_waiver(sign_request(&other, &adult.id, None))
+            .await;
+        assert!(matches!(
+            result,
+            Err(WaiverError::NotRequired { waiver, participant })
+                if waiver == other.id && participant == adult.id
+        ));
+    }
+
+    #[tokio::test]
+    async fn sign_waiver_rejects_stale_versions_and_content() {
+        let repo = Memory::new();
+        let service = service(&repo).await;
+        let (first, adult, _) = booked_waiver_trip(&repo, &service, 10).await;
+        let second = service
+            .create_waiver(CreateWaiverRequest {
+                id: Some(first.id.clone()),
+                content: "I accept the risks of sea kayaking and rolling.".to_string(),
+                effective_at: None,
+            })
+            .await
+            .unwrap();
+
+        let result = service
+            .sign_waiver(sign_request(&first, &adult.id, None))
+            .await;
+        assert!(matches!(result, Err(WaiverError::OutdatedVersion(version)) if version == 1));
+
+        let result = service
+            .sign_waiver(SignWaiverRequest {
+                content_hash: first.content_hash(),
+                ..sign_request(&second, &adult.id, None)
+            })
+            .await;
+        assert!(matches!(result, Err(WaiverError::ContentMismatch)));
+    }
+
+    #[tokio::test]
+    async fn sign_waiver_deletes_the_image_if_the_signature_isnt_saved() {
+        let repo = Memory::new();
+        let root = std::env::temp_dir().join(format!("tide-service-tests-{}", Uuid::now_v7()));
+        let blobs = LocalBlobStore::new(&root).await.unwrap();
+        let service = Service::new(repo.clone(), blobs, FakePaymentGateway::new());
+        let (waiver, adult, _) = booked_waiver_trip(&repo, &service, 10).await;
+
+        repo.fail_waiver_signatures();
+        let result = service
+            .sign_waiver(sign_request(&waiver, &adult.id, None))
+            .await;
+        assert!(matches!(result, Err(WaiverError::Unknown(_))));
+
+        let mut entries = tokio::fs::read_dir(&root).await.unwrap();
+        assert!(entries.next_entry().await.unwrap().is_none());
+        tokio::fs::remove_dir(&root).await.unwrap();
+    }
 }
@@ -0,0 +1,6 @@
+//! The [scheduling] domain concerns itself with staff-facing trip scheduling.
+//! It is primarily responsible for turning recurring schedules into bookable trips.
+
+pub mod models;
+pub mod ports;
+pub mod service;
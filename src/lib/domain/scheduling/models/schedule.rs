@@ -0,0 +1,333 @@
+use crate::domain::booking::models::trip::{LocationId, TripId, TripKindId};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A [Schedule] is a recurring series of [Trip]s of one [TripKind] departing from one [Location].
+///
+/// Trips are materialized from a schedule's [RecurrenceRule] over a rolling horizon,
+/// so customers can book them like any other trip.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub id: ScheduleId,
+    pub trip_kind: TripKindId,
+    pub location: LocationId,
+    pub recurrence: RecurrenceRule,
+    /// Overrides the [TripKind]'s maximum participant count for every trip in the series.
+    pub max_participants: Option<i32>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScheduleId(pub Uuid);
+
+/// A [RecurrenceRule] describes when the trips of a [Schedule] depart, in the spirit of
+/// an iCalendar RRULE with a daily frequency.
+///
+/// e.g. "daily at 9:00 and 14:00, May–September, except Tuesdays" is `times = [09:00, 14:00]`,
+/// `months = [5, 6, 7, 8, 9]` and `weekdays = [Mon, Wed, Thu, Fri, Sat, Sun]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    /// The time zone that `times` are expressed in.
+    pub timezone: Tz,
+    pub starts_on: NaiveDate,
+    pub ends_on: Option<NaiveDate>,
+    /// The days of the week trips run on. Empty means every day.
+    pub weekdays: Vec<Weekday>,
+    /// The months (1-12) trips run in. Empty means every month.
+    pub months: Vec<u32>,
+    /// The local departure times on each day the rule applies.
+    pub times: Vec<NaiveTime>,
+    /// How long each trip lasts, in whole minutes.
+    pub duration: Duration,
+}
+
+impl RecurrenceRule {
+    /// Checks that the rule can produce a sensible series of trips.
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        if self.times.is_empty() {
+            return Err(ScheduleError::InvalidRule(
+                "at least one departure time is required".to_string(),
+            ));
+        }
+        if self.duration < Duration::minutes(1) {
+            return Err(ScheduleError::InvalidRule(
+                "duration must be at least one minute".to_string(),
+            ));
+        }
+        if self.duration != Duration::minutes(self.duration.num_minutes()) {
+            return Err(ScheduleError::InvalidRule(
+                "duration must be a whole number of minutes".to_string(),
+            ));
+        }
+        if i32::try_from(self.duration.num_minutes()).is_err() {
+            return Err(ScheduleError::InvalidRule(
+                "duration is too long".to_string(),
+            ));
+        }
+        if self.ends_on.is_some_and(|ends_on| ends_on < self.starts_on) {
+            return Err(ScheduleError::InvalidRule(
+                "schedule must not end before it starts".to_string(),
+            ));
+        }
+        if let Some(month) = self.months.iter().find(|m| !(1..=12).contains(*m)) {
+            return Err(ScheduleError::InvalidRule(format!(
+                "{month} is not a valid month"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Whether trips run on the given local date.
+    pub fn applies_on(&self, date: NaiveDate) -> bool {
+        date >= self.starts_on
+            && self.ends_on.is_none_or(|ends_on| date <= ends_on)
+            && (self.weekdays.is_empty() || self.weekdays.contains(&date.weekday()))
+            && (self.months.is_empty() || self.months.contains(&date.month()))
+    }
+
+    /// Gets every [Occurrence] departing on a local date between `from` and `until` (inclusive).
+    ///
+    /// Departure times that don't exist on a given day (e.g. during a DST transition) are skipped.
+    pub fn occurrences(&self, from: NaiveDate, until: NaiveDate) -> Vec<Occurrence> {
+        from.iter_days()
+            .take_while(|date| *date <= until)
+            .filter(|date| self.applies_on(*date))
+            .flat_map(|date| {
+                self.times.iter().filter_map(move |time| {
+                    let start = self
+                        .timezone
+                        .from_local_datetime(&date.and_time(*time))
+                        .earliest()?
+                        .with_timezone(&Utc);
+
+                    Some(Occurrence {
+                        scheduled_start: start,
+                        start_time: start,
+                        end_time: start + self.duration,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the rule has a departure at exactly `scheduled_start`.
+    pub fn occurs_at(&self, scheduled_start: DateTime<Utc>) -> bool {
+        let date = scheduled_start.with_timezone(&self.timezone).date_naive();
+
+        self.occurrences(date, date)
+            .iter()
+            .any(|o| o.scheduled_start == scheduled_start)
+    }
+}
+
+/// An [Occurrence] is a single departure of a [Schedule].
+///
+/// `scheduled_start` identifies the occurrence within its series, even if the trip
+/// itself is later moved to a different `start_time`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Occurrence {
+    pub scheduled_start: DateTime<Utc>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateScheduleRequest {
+    pub trip_kind: TripKindId,
+    pub location: LocationId,
+    pub recurrence: RecurrenceRule,
+    pub max_participants: Option<i32>,
+}
+
+impl TryFrom<CreateScheduleRequest> for Schedule {
+    type Error = ScheduleError;
+
+    fn try_from(request: CreateScheduleRequest) -> Result<Self, Self::Error> {
+        request.recurrence.validate()?;
+
+        Ok(Self {
+            id: ScheduleId(Uuid::now_v7()),
+            trip_kind: request.trip_kind,
+            location: request.location,
+            recurrence: request.recurrence,
+            max_participants: request.max_participants,
+            cancelled_at: None,
+        })
+    }
+}
+
+/// [EditScheduleRequest] replaces the rule of a whole series.
+///
+/// Only occurrences that haven't departed yet are affected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditScheduleRequest {
+    pub id: ScheduleId,
+    pub location: LocationId,
+    pub recurrence: RecurrenceRule,
+    pub max_participants: Option<i32>,
+}
+
+/// A [ScheduleEdit] is the outcome of editing a whole series.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScheduleEdit {
+    pub schedule: Schedule,
+    /// Upcoming trips that no longer match the series but were kept because they have
+    /// bookings, so staff can follow up with the customers.
+    pub booked_trips: Vec<TripId>,
+}
+
+/// [EditOccurrenceRequest] moves a single occurrence of a series,
+/// detaching it from any later edits to the series.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditOccurrenceRequest {
+    pub schedule: ScheduleId,
+    pub scheduled_start: DateTime<Utc>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error("schedule {} does not exist", .0.0)]
+    NotFound(ScheduleId),
+    #[error("schedule {} has been cancelled", .0.0)]
+    Cancelled(ScheduleId),
+    #[error("invalid recurrence rule: {0}")]
+    InvalidRule(String),
+    #[error("schedule has no occurrence at {0}")]
+    OccurrenceNotFound(DateTime<Utc>),
+    #[error("trip {} already has bookings", .0.0)]
+    OccurrenceHasBookings(TripId),
+    #[error("trip must not end before it starts")]
+    InvalidTimeRange,
+    #[error(transparent)]
+    Unknown(anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(timezone: Tz, times: &[(u32, u32)]) -> RecurrenceRule {
+        RecurrenceRule {
+            timezone,
+            starts_on: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            ends_on: None,
+            weekdays: vec![],
+            months: vec![],
+            times: times
+                .iter()
+                .map(|(h, m)| NaiveTime::from_hms_opt(*h, *m, 0).unwrap())
+                .collect(),
+            duration: Duration::minutes(90),
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn starts(occurrences: &[Occurrence]) -> Vec<DateTime<Utc>> {
+        occurrences.iter().map(|o| o.scheduled_start).collect()
+    }
+
+    #[test]
+    fn occurrences_depart_at_local_times_in_the_rules_timezone() {
+        let rule = rule(Tz::Europe__London, &[(9, 0), (14, 30)]);
+
+        let summer = rule.occurrences(date(2026, 7, 1), date(2026, 7, 1));
+        assert_eq!(
+            summer,
+            vec![
+                Occurrence {
+                    scheduled_start: utc(2026, 7, 1, 8, 0),
+                    start_time: utc(2026, 7, 1, 8, 0),
+                    end_time: utc(2026, 7, 1, 9, 30),
+                },
+                Occurrence {
+                    scheduled_start: utc(2026, 7, 1, 13, 30),
+                    start_time: utc(2026, 7, 1, 13, 30),
+                    end_time: utc(2026, 7, 1, 15, 0),
+                },
+            ]
+        );
+
+        let winter = rule.occurrences(date(2026, 12, 1), date(2026, 12, 1));
+        assert_eq!(
+            starts(&winter),
+            vec![utc(2026, 12, 1, 9, 0), utc(2026, 12, 1, 14, 30)]
+        );
+    }
+
+    #[test]
+    fn occurrences_skip_times_missing_in_a_dst_gap() {
+        // Clocks in London go from 01:00 straight to 02:00 on 29 March 2026.
+        let rule = rule(Tz::Europe__London, &[(1, 30), (9, 0)]);
+
+        let occurrences = rule.occurrences(date(2026, 3, 28), date(2026, 3, 29));
+        assert_eq!(
+            starts(&occurrences),
+            vec![
+                utc(2026, 3, 28, 1, 30),
+                utc(2026, 3, 28, 9, 0),
+                utc(2026, 3, 29, 8, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn occurrences_depart_once_at_the_earlier_time_in_a_dst_overlap() {
+        // Clocks in London go from 02:00 back to 01:00 on 25 October 2026.
+        let rule = rule(Tz::Europe__London, &[(1, 30)]);
+
+        let occurrences = rule.occurrences(date(2026, 10, 25), date(2026, 10, 25));
+        assert_eq!(starts(&occurrences), vec![utc(2026, 10, 25, 0, 30)]);
+        assert!(rule.occurs_at(utc(2026, 10, 25, 0, 30)));
+        assert!(!rule.occurs_at(utc(2026, 10, 25, 1, 30)));
+    }
+
+    #[test]
+    fn occurrences_apply_the_weekday_and_month_filters() {
+        let rule = RecurrenceRule {
+            weekdays: vec![Weekday::Sat, Weekday::Sun],
+            months: vec![6],
+            ..rule(Tz::UTC, &[(10, 0)])
+        };
+
+        let occurrences = rule.occurrences(date(2026, 5, 25), date(2026, 7, 5));
+        assert_eq!(
+            starts(&occurrences),
+            [6, 7, 13, 14, 20, 21, 27, 28]
+                .into_iter()
+                .map(|day| utc(2026, 6, day, 10, 0))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn occurrences_stay_within_the_rules_dates() {
+        let rule = RecurrenceRule {
+            starts_on: date(2026, 6, 10),
+            ends_on: Some(date(2026, 6, 12)),
+            ..rule(Tz::UTC, &[(10, 0)])
+        };
+
+        let occurrences = rule.occurrences(date(2026, 6, 1), date(2026, 6, 30));
+        assert_eq!(
+            starts(&occurrences),
+            vec![
+                utc(2026, 6, 10, 10, 0),
+                utc(2026, 6, 11, 10, 0),
+                utc(2026, 6, 12, 10, 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::domain::booking::models::trip::TripId;
+use crate::domain::scheduling::models::schedule::{
+    CreateScheduleRequest, EditOccurrenceRequest, EditScheduleRequest, Occurrence, Schedule,
+    ScheduleEdit, ScheduleError, ScheduleId,
+};
+use chrono::{DateTime, Utc};
+use std::future::Future;
+
+/// [ScheduleService] is able to handle use-case interactions with the scheduling domain.
+pub trait ScheduleService: Clone + Send + Sync + 'static {
+    /// create_schedule creates a new [Schedule] and materializes its upcoming trips.
+    fn create_schedule(
+        &self,
+        request: CreateScheduleRequest,
+    ) -> impl Future<Output = Result<Schedule, ScheduleError>> + Send;
+
+    /// find_schedule gets a [Schedule] by ID if it exists.
+    fn find_schedule(
+        &self,
+        id: ScheduleId,
+    ) -> impl Future<Output = Result<Option<Schedule>, ScheduleError>> + Send;
+
+    /// edit_schedule replaces the rule of a whole series, removing upcoming trips that
+    /// no longer match it and materializing any new ones.
+    ///
+    /// Upcoming trips that already have bookings are kept where they are, and those that
+    /// no longer match are returned so staff can follow up with the customers.
+    fn edit_schedule(
+        &self,
+        request: EditScheduleRequest,
+    ) -> impl Future<Output = Result<ScheduleEdit, ScheduleError>> + Send;
+
+    /// cancel_schedule cancels a whole series, removing its upcoming trips.
+    ///
+    /// Upcoming trips that already have bookings are kept, and their IDs are returned
+    /// so staff can follow up with the customers.
+    fn cancel_schedule(
+        &self,
+        id: ScheduleId,
+    ) -> impl Future<Output = Result<Vec<TripId>, ScheduleError>> + Send;
+
+    /// edit_occurrence moves a single occurrence of a series, returning its trip's ID.
+    fn edit_occurrence(
+        &self,
+        request: EditOccurrenceRequest,
+    ) -> impl Future<Output = Result<TripId, ScheduleError>> + Send;
+
+    /// cancel_occurrence cancels a single occurrence of a series that has no bookings.
+    fn cancel_occurrence(
+        &self,
+        id: ScheduleId,
+        scheduled_start: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), ScheduleError>> + Send;
+
+    /// materialize_schedules creates or updates the trips of every active [Schedule]
+    /// over the rolling horizon, returning how many trips were written.
+    ///
+    /// It is idempotent, so it's safe to run repeatedly (e.g. on a timer).
+    fn materialize_schedules(&self) -> impl Future<Output = Result<u64, ScheduleError>> + Send;
+}
+
+/// [ScheduleRepository] is able to access and persist scheduling domain models.
+pub trait ScheduleRepository: Clone + Send + Sync + 'static {
+    /// find_schedule gets a [Schedule] by ID if it exists.
+    fn find_schedule(
+        &self,
+        id: ScheduleId,
+    ) -> impl Future<Output = Result<Option<Schedule>, ScheduleError>> + Send;
+
+    /// find_active_schedules gets every [Schedule] that isn't cancelled or ended as of `now`.
+    fn find_active_schedules(
+        &self,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<Schedule>, ScheduleError>> + Send;
+
+    /// save_schedule creates or updates a schedule.
+    fn save_schedule(
+        &self,
+        schedule: &Schedule,
+    ) -> impl Future<Output = Result<(), ScheduleError>> + Send;
+
+    /// save_occurrences creates or updates a trip for each [Occurrence] of a schedule,
+    /// returning how many trips were written.
+    ///
+    /// Cancelled and detached occurrences are left untouched, as are trips with bookings,
+    /// so booked customers aren't moved without notice.
+    fn save_occurrences(
+        &self,
+        schedule: &Schedule,
+        occurrences: &[Occurrence],
+    ) -> impl Future<Output = Result<u64, ScheduleError>> + Send;
+
+    /// prune_occurrences deletes the trips of a schedule that start after `after`,
+    /// aren't detached, and aren't in `keep`.
+    ///
+    /// Trips with bookings are never deleted; their IDs are returned instead.
+    fn prune_occurrences(
+        &self,
+        id: &ScheduleId,
+        after: DateTime<Utc>,
+        keep: &[Occurrence],
+    ) -> impl Future<Output = Result<Vec<TripId>, ScheduleError>> + Send;
+
+    /// delete_upcoming_occurrences deletes every trip of a schedule that starts after `after`,
+    /// including detached ones.
+    ///
+    /// Trips with bookings are never deleted; their IDs are returned instead.
+    fn delete_upcoming_occurrences(
+        &self,
+        id: &ScheduleId,
+        after: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<TripId>, ScheduleError>> + Send;
+
+    /// save_detached_occurrence creates or updates the trip of a single occurrence,
+    /// detaching it from later edits to the series.
+    fn save_detached_occurrence(
+        &self,
+        schedule: &Schedule,
+        occurrence: &Occurrence,
+    ) -> impl Future<Output = Result<TripId, ScheduleError>> + Send;
+
+    /// cancel_occurrence atomically records a cancelled occurrence and deletes its trip.
+    ///
+    /// Fails with [ScheduleError::OccurrenceHasBookings] if the trip has any bookings.
+    fn cancel_occurrence(
+        &self,
+        id: &ScheduleId,
+        scheduled_start: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), ScheduleError>> + Send;
+}
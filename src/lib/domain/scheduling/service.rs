@@ -0,0 +1,167 @@
+use crate::domain::booking::models::trip::TripId;
+use crate::domain::scheduling::models::schedule::{
+    CreateScheduleRequest, EditOccurrenceRequest, EditScheduleRequest, Occurrence, Schedule,
+    ScheduleEdit, ScheduleError, ScheduleId,
+};
+use crate::domain::scheduling::ports::{ScheduleRepository, ScheduleService};
+use chrono::{DateTime, Duration, Utc};
+
+const DEFAULT_HORIZON_DAYS: i64 = 90;
+
+#[derive(Debug, Clone)]
+pub struct Service<R: ScheduleRepository> {
+    repo: R,
+    horizon: Duration,
+}
+
+impl<R: ScheduleRepository> Service<R> {
+    pub fn new(repo: R) -> Self {
+        Self {
+            repo,
+            horizon: Duration::days(DEFAULT_HORIZON_DAYS),
+        }
+    }
+
+    /// Sets how far ahead of today trips are materialized.
+    pub fn with_horizon(self, horizon: Duration) -> Self {
+        Self { horizon, ..self }
+    }
+
+    /// Gets the occurrences of a schedule that haven't departed yet, up to the horizon.
+    fn upcoming_occurrences(&self, schedule: &Schedule, now: DateTime<Utc>) -> Vec<Occurrence> {
+        let rule = &schedule.recurrence;
+        let today = now.with_timezone(&rule.timezone).date_naive();
+        let until = (now + self.horizon)
+            .with_timezone(&rule.timezone)
+            .date_naive();
+
+        rule.occurrences(today, until)
+            .into_iter()
+            .filter(|o| o.scheduled_start > now)
+            .collect()
+    }
+
+    async fn find_active_schedule(&self, id: ScheduleId) -> Result<Schedule, ScheduleError> {
+        let schedule = self
+            .repo
+            .find_schedule(id.clone())
+            .await?
+            .ok_or(ScheduleError::NotFound(id))?;
+
+        match schedule.cancelled_at {
+            Some(_) => Err(ScheduleError::Cancelled(schedule.id)),
+            None => Ok(schedule),
+        }
+    }
+}
+
+impl<R: ScheduleRepository> ScheduleService for Service<R> {
+    async fn create_schedule(
+        &self,
+        request: CreateScheduleRequest,
+    ) -> Result<Schedule, ScheduleError> {
+        let schedule = Schedule::try_from(request)?;
+        self.repo.save_schedule(&schedule).await?;
+
+        let occurrences = self.upcoming_occurrences(&schedule, Utc::now());
+        self.repo.save_occurrences(&schedule, &occurrences).await?;
+
+        Ok(schedule)
+    }
+
+    async fn find_schedule(&self, id: ScheduleId) -> Result<Option<Schedule>, ScheduleError> {
+        self.repo.find_schedule(id).await
+    }
+
+    async fn edit_schedule(
+        &self,
+        request: EditScheduleRequest,
+    ) -> Result<ScheduleEdit, ScheduleError> {
+        request.recurrence.validate()?;
+
+        let schedule = Schedule {
+            location: request.location,
+            recurrence: request.recurrence,
+            max_participants: request.max_participants,
+            ..self.find_active_schedule(request.id).await?
+        };
+        self.repo.save_schedule(&schedule).await?;
+
+        let now = Utc::now();
+        let occurrences = self.upcoming_occurrences(&schedule, now);
+        let booked_trips = self
+            .repo
+            .prune_occurrences(&schedule.id, now, &occurrences)
+            .await?;
+        self.repo.save_occurrences(&schedule, &occurrences).await?;
+
+        Ok(ScheduleEdit {
+            schedule,
+            booked_trips,
+        })
+    }
+
+    async fn cancel_schedule(&self, id: ScheduleId) -> Result<Vec<TripId>, ScheduleError> {
+        let now = Utc::now();
+        let schedule = Schedule {
+            cancelled_at: Some(now),
+            ..self.find_active_schedule(id).await?
+        };
+        self.repo.save_schedule(&schedule).await?;
+
+        self.repo
+            .delete_upcoming_occurrences(&schedule.id, now)
+            .await
+    }
+
+    async fn edit_occurrence(
+        &self,
+        request: EditOccurrenceRequest,
+    ) -> Result<TripId, ScheduleError> {
+        if request.end_time < request.start_time {
+            return Err(ScheduleError::InvalidTimeRange);
+        }
+
+        let schedule = self.find_active_schedule(request.schedule).await?;
+        if !schedule.recurrence.occurs_at(request.scheduled_start) {
+            return Err(ScheduleError::OccurrenceNotFound(request.scheduled_start));
+        }
+
+        let occurrence = Occurrence {
+            scheduled_start: request.scheduled_start,
+            start_time: request.start_time,
+            end_time: request.end_time,
+        };
+
+        self.repo
+            .save_detached_occurrence(&schedule, &occurrence)
+            .await
+    }
+
+    async fn cancel_occurrence(
+        &self,
+        id: ScheduleId,
+        scheduled_start: DateTime<Utc>,
+    ) -> Result<(), ScheduleError> {
+        let schedule = self.find_active_schedule(id).await?;
+        if !schedule.recurrence.occurs_at(scheduled_start) {
+            return Err(ScheduleError::OccurrenceNotFound(scheduled_start));
+        }
+
+        self.repo
+            .cancel_occurrence(&schedule.id, scheduled_start)
+            .await
+    }
+
+    async fn materialize_schedules(&self) -> Result<u64, ScheduleError> {
+        let now = Utc::now();
+        let mut written = 0;
+
+        for schedule in self.repo.find_active_schedules(now).await? {
+            let occurrences = self.upcoming_occurrences(&schedule, now);
+            written += self.repo.save_occurrences(&schedule, &occurrences).await?;
+        }
+
+        Ok(written)
+    }
+}
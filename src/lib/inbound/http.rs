@@ -1,10 +1,23 @@
 //! Module [http] is an inbound/driving adapter that exposes
 //! a REST-ful API to interact with the core domain over HTTP.
 
+mod bookings;
+mod customers;
+mod equipment;
+mod payments;
+mod quotes;
+mod responses;
+mod schedules;
+mod waitlist;
+mod waivers;
+
 use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::ports::ScheduleService;
 use anyhow::Context;
 use axum::extract::Request;
+use axum::routing::{get, post, put};
 use axum::Router;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tower_http::trace::TraceLayer;
@@ -12,11 +25,15 @@ use tower_http::trace::TraceLayer;
 #[derive(Debug, Clone)]
 pub struct HttpConfig<'cfg> {
     pub port: &'cfg str,
+    /// The reverse proxies whose `X-Forwarded-For` header is trusted.
+    pub trusted_proxies: &'cfg [IpAddr],
 }
 
 #[derive(Debug, Clone)]
-pub struct AppState<BS: BookingService> {
+pub struct AppState<BS: BookingService, SS: ScheduleService> {
     pub bookings: Arc<BS>,
+    pub schedules: Arc<SS>,
+    pub trusted_proxies: Arc<[IpAddr]>,
 }
 
 pub struct HttpServer {
@@ -25,9 +42,10 @@ pub struct HttpServer {
 }
 
 impl HttpServer {
-    pub async fn new<BS: BookingService>(
+    pub async fn new<BS: BookingService, SS: ScheduleService>(
         config: HttpConfig<'_>,
         bookings: BS,
+        schedules: SS,
     ) -> anyhow::Result<Self> {
         let trace_layer = TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
             let uri = request.uri().to_string();
@@ -36,10 +54,12 @@ impl HttpServer {
 
         let app_state = AppState {
             bookings: Arc::new(bookings),
+            schedules: Arc::new(schedules),
+            trusted_proxies: config.trusted_proxies.into(),
         };
 
         let router = Router::new()
-            .nest("/api", Router::new())
+            .nest("/api", api_routes())
             .layer(trace_layer)
             .with_state(app_state);
 
@@ -53,10 +73,127 @@ impl HttpServer {
     pub async fn serve(self) -> anyhow::Result<()> {
         tracing::debug!("listening on {}", self.listener.local_addr()?);
 
-        axum::serve(self.listener, self.router)
+        let service = self
+            .router
+            .into_make_service_with_connect_info::<SocketAddr>();
+
+        axum::serve(self.listener, service)
             .await
             .context("Error starting server")?;
 
         Ok(())
     }
 }
+
+fn api_routes<BS: BookingService, SS: ScheduleService>() -> Router<AppState<BS, SS>> {
+    Router::new()
+        .route(
+            "/bookings",
+            get(bookings::find_bookings::<BS, SS>).post(bookings::create_booking::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id",
+            get(bookings::find_booking::<BS, SS>)
+                .put(bookings::edit_booking::<BS, SS>)
+                .delete(bookings::delete_booking::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id/confirm",
+            post(bookings::confirm_booking::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id/complete",
+            post(bookings::complete_booking::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id/no-show",
+            post(bookings::mark_booking_no_show::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id/cancel",
+            post(bookings::cancel_booking::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id/reschedule",
+            post(bookings::reschedule_booking::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id/missing-waivers",
+            get(bookings::find_missing_waivers::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id/check-in",
+            post(bookings::check_in_booking::<BS, SS>),
+        )
+        .route(
+            "/bookings/:booking_id/payments",
+            get(payments::find_payment_ledger::<BS, SS>)
+                .post(payments::authorize_payment::<BS, SS>),
+        )
+        .route(
+            "/payments/:payment_id/capture",
+            post(payments::capture_payment::<BS, SS>),
+        )
+        .route(
+            "/payments/:payment_id/refund",
+            post(payments::refund_payment::<BS, SS>),
+        )
+        .route(
+            "/customers",
+            get(customers::search_customers::<BS, SS>).post(customers::create_customer::<BS, SS>),
+        )
+        .route(
+            "/customers/duplicates",
+            get(customers::find_duplicate_customers::<BS, SS>),
+        )
+        .route(
+            "/customers/:customer_id",
+            get(customers::find_customer::<BS, SS>).patch(customers::edit_customer::<BS, SS>),
+        )
+        .route(
+            "/customers/:customer_id/merge",
+            post(customers::merge_customers::<BS, SS>),
+        )
+        .route(
+            "/customers/:customer_id/merges",
+            get(customers::find_customer_merges::<BS, SS>),
+        )
+        .route(
+            "/equipment/availability",
+            get(equipment::find_equipment_availability::<BS, SS>),
+        )
+        .route("/quotes", post(quotes::quote_booking::<BS, SS>))
+        .route(
+            "/trips/:trip_id/waitlist",
+            get(waitlist::find_waitlist::<BS, SS>).post(waitlist::join_waitlist::<BS, SS>),
+        )
+        .route(
+            "/waitlist/:entry_id",
+            get(waitlist::find_waitlist_entry::<BS, SS>).delete(waitlist::leave_waitlist::<BS, SS>),
+        )
+        .route("/waivers", post(waivers::create_waiver::<BS, SS>))
+        .route(
+            "/waivers/:waiver_id",
+            get(waivers::find_current_waiver::<BS, SS>),
+        )
+        .route(
+            "/waivers/:waiver_id/versions",
+            post(waivers::create_waiver_version::<BS, SS>),
+        )
+        .route(
+            "/waivers/:waiver_id/signatures",
+            post(waivers::sign_waiver::<BS, SS>),
+        )
+        .route("/schedules", post(schedules::create_schedule::<BS, SS>))
+        .route(
+            "/schedules/:schedule_id",
+            get(schedules::find_schedule::<BS, SS>)
+                .put(schedules::edit_schedule::<BS, SS>)
+                .delete(schedules::cancel_schedule::<BS, SS>),
+        )
+        .route(
+            "/schedules/:schedule_id/occurrences/:scheduled_start",
+            put(schedules::edit_occurrence::<BS, SS>)
+                .delete(schedules::cancel_occurrence::<BS, SS>),
+        )
+}
@@ -0,0 +1,378 @@
+//! HTTP handlers & DTOs for the `/api/bookings` resource.
+
+use crate::domain::booking::models::booking::{
+    Booking, BookingError, BookingFilters, BookingId, BookingReference, CancelBookingRequest,
+    Cancellation, CreateBookingRequest, CreateParticipantRequest, EditBookingRequest,
+    EditParticipantRequest, Participant, ParticipantId, RescheduleBookingRequest,
+};
+use crate::domain::booking::models::customer::CustomerId;
+use crate::domain::booking::models::trip::TripId;
+use crate::domain::booking::models::waitlist::WaitlistEntryId;
+use crate::domain::booking::models::waiver::MissingWaiver;
+use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::ports::ScheduleService;
+use crate::inbound::http::responses::{ApiError, ApiSuccess};
+use crate::inbound::http::AppState;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// POST `/api/bookings`
+pub async fn create_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Json(body): Json<CreateBookingRequestBody>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let booking = state.bookings.create_booking(body.into()).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&booking).into()))
+}
+
+/// GET `/api/bookings/:booking_id`
+pub async fn find_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let booking = state
+        .bookings
+        .find_booking(BookingId(booking_id))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("booking {booking_id} does not exist")))?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&booking).into()))
+}
+
+/// GET `/api/bookings?reference=&customer_id=&trip_id=&participant_id=&status=`
+pub async fn find_bookings<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Query(params): Query<BookingFiltersParams>,
+) -> Result<ApiSuccess<Vec<BookingResponseData>>, ApiError> {
+    let filters = BookingFilters::try_from(params)?;
+    let bookings = state.bookings.find_bookings(&filters).await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        bookings.iter().map(BookingResponseData::from).collect(),
+    ))
+}
+
+/// PUT `/api/bookings/:booking_id`
+pub async fn edit_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+    Json(body): Json<EditBookingRequestBody>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let request = body.into_domain(BookingId(booking_id));
+    let booking = state.bookings.edit_booking(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&booking).into()))
+}
+
+/// DELETE `/api/bookings/:booking_id`
+pub async fn delete_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.bookings.delete_booking(BookingId(booking_id)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST `/api/bookings/:booking_id/confirm`
+pub async fn confirm_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let booking = state
+        .bookings
+        .confirm_booking(BookingId(booking_id))
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&booking).into()))
+}
+
+/// POST `/api/bookings/:booking_id/complete`
+pub async fn complete_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let booking = state
+        .bookings
+        .complete_booking(BookingId(booking_id))
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&booking).into()))
+}
+
+/// POST `/api/bookings/:booking_id/no-show`
+pub async fn mark_booking_no_show<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let booking = state
+        .bookings
+        .mark_booking_no_show(BookingId(booking_id))
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&booking).into()))
+}
+
+/// POST `/api/bookings/:booking_id/cancel`
+pub async fn cancel_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+    Json(body): Json<CancelBookingRequestBody>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let request = CancelBookingRequest {
+        id: BookingId(booking_id),
+        reason: body.reason,
+    };
+    let booking = state.bookings.cancel_booking(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&booking).into()))
+}
+
+/// POST `/api/bookings/:booking_id/reschedule`
+pub async fn reschedule_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+    Json(body): Json<RescheduleBookingRequestBody>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let request = RescheduleBookingRequest {
+        id: BookingId(booking_id),
+        trip: TripId(body.trip_id),
+    };
+    let booking = state.bookings.reschedule_booking(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&booking).into()))
+}
+
+/// GET `/api/bookings/:booking_id/missing-waivers`
+pub async fn find_missing_waivers<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+) -> Result<ApiSuccess<Vec<MissingWaiverResponseData>>, ApiError> {
+    let missing = state
+        .bookings
+        .find_missing_waivers(BookingId(booking_id))
+        .await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        missing
+            .iter()
+            .map(MissingWaiverResponseData::from)
+            .collect(),
+    ))
+}
+
+/// POST `/api/bookings/:booking_id/check-in`
+pub async fn check_in_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+) -> Result<ApiSuccess<BookingResponseData>, ApiError> {
+    let booking = state
+        .bookings
+        .check_in_booking(BookingId(booking_id))
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&booking).into()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateBookingRequestBody {
+    /// The reference of the customer's bookings to add this one to, if any.
+    #[serde(default)]
+    reference: Option<String>,
+    customer_id: Uuid,
+    trip_id: Uuid,
+    participants: Vec<ParticipantRequestBody>,
+    /// The waitlist entry whose offered seats the booking takes, if any.
+    #[serde(default)]
+    waitlist_entry_id: Option<Uuid>,
+    /// Whether to hold the booking until it's confirmed, instead of confirming it now.
+    #[serde(default)]
+    hold: bool,
+}
+
+impl From<CreateBookingRequestBody> for CreateBookingRequest {
+    fn from(body: CreateBookingRequestBody) -> Self {
+        Self {
+            reference: body.reference.as_deref().map(BookingReference::parse),
+            customer: CustomerId(body.customer_id),
+            trip: TripId(body.trip_id),
+            participants: body
+                .participants
+                .into_iter()
+                .map(|p| CreateParticipantRequest {
+                    name: p.name,
+                    dob: p.dob,
+                    notes: p.notes,
+                })
+                .collect(),
+            waitlist_entry: body.waitlist_entry_id.map(WaitlistEntryId),
+            hold: body.hold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EditBookingRequestBody {
+    participants: Vec<ParticipantRequestBody>,
+}
+
+impl EditBookingRequestBody {
+    fn into_domain(self, id: BookingId) -> EditBookingRequest {
+        EditBookingRequest {
+            id,
+            participants: self
+                .participants
+                .into_iter()
+                .map(|p| EditParticipantRequest {
+                    id: p.id.map(ParticipantId),
+                    name: p.name,
+                    dob: p.dob,
+                    notes: p.notes,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CancelBookingRequestBody {
+    reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RescheduleBookingRequestBody {
+    trip_id: Uuid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ParticipantRequestBody {
+    #[serde(default)]
+    id: Option<Uuid>,
+    name: String,
+    dob: NaiveDate,
+    #[serde(default)]
+    notes: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BookingFiltersParams {
+    reference: Option<String>,
+    customer_id: Option<Uuid>,
+    trip_id: Option<Uuid>,
+    participant_id: Option<Uuid>,
+    status: Option<String>,
+}
+
+impl TryFrom<BookingFiltersParams> for BookingFilters {
+    type Error = BookingError;
+
+    fn try_from(params: BookingFiltersParams) -> Result<Self, Self::Error> {
+        Ok(Self {
+            reference: params.reference.as_deref().map(BookingReference::parse),
+            customer: params.customer_id.map(CustomerId),
+            trip: params.trip_id.map(TripId),
+            participant: params.participant_id.map(ParticipantId),
+            status: params.status.as_deref().map(str::parse).transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BookingResponseData {
+    id: Uuid,
+    reference: String,
+    customer_id: Uuid,
+    trip_id: Uuid,
+    participants: Vec<ParticipantResponseData>,
+    status: &'static str,
+    hold_expires_at: Option<DateTime<Utc>>,
+    checked_in_at: Option<DateTime<Utc>>,
+    cancellation: Option<CancellationResponseData>,
+    waitlist_entry_id: Option<Uuid>,
+    overbooked: bool,
+}
+
+impl From<&Booking> for BookingResponseData {
+    fn from(booking: &Booking) -> Self {
+        Self {
+            id: booking.id.0,
+            reference: booking.reference.0.clone(),
+            customer_id: booking.customer.0,
+            trip_id: booking.trip.0,
+            participants: booking
+                .participants
+                .iter()
+                .map(ParticipantResponseData::from)
+                .collect(),
+            status: booking.status.as_str(),
+            hold_expires_at: booking.hold_expires_at,
+            checked_in_at: booking.checked_in_at,
+            cancellation: booking.cancellation.as_ref().map(Into::into),
+            waitlist_entry_id: booking.waitlist_entry.as_ref().map(|e| e.0),
+            overbooked: booking.overbooked,
+        }
+    }
+}
+
+/// [CancellationResponseData] reports the refund in the smallest unit of the currency (e.g. cents).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CancellationResponseData {
+    reason: String,
+    cancelled_at: DateTime<Utc>,
+    refund: i64,
+}
+
+impl From<&Cancellation> for CancellationResponseData {
+    fn from(cancellation: &Cancellation) -> Self {
+        Self {
+            reason: cancellation.reason.clone(),
+            cancelled_at: cancellation.cancelled_at,
+            refund: cancellation.refund.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParticipantResponseData {
+    id: Uuid,
+    name: String,
+    dob: NaiveDate,
+    notes: String,
+    waiver_id: Option<Uuid>,
+}
+
+impl From<&Participant> for ParticipantResponseData {
+    fn from(participant: &Participant) -> Self {
+        Self {
+            id: participant.id.0,
+            name: participant.name.clone(),
+            dob: participant.dob,
+            notes: participant.notes.clone(),
+            waiver_id: participant.waiver.as_ref().map(|w| w.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingWaiverResponseData {
+    participant_id: Uuid,
+    waiver_id: Uuid,
+    version: i32,
+}
+
+impl From<&MissingWaiver> for MissingWaiverResponseData {
+    fn from(missing: &MissingWaiver) -> Self {
+        Self {
+            participant_id: missing.participant.0,
+            waiver_id: missing.waiver.0,
+            version: missing.version,
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! HTTP handlers & DTOs for the `/api/customers` resource.
+
+use crate::domain::booking::models::customer::{
+    CreateCustomerRequest, Customer, CustomerId, CustomerMerge, CustomerSearchResults,
+    DuplicateCandidate, EditCustomerRequest, MergeCustomersRequest, Page,
+};
+use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::ports::ScheduleService;
+use crate::inbound::http::responses::{ApiError, ApiSuccess};
+use crate::inbound::http::AppState;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// POST `/api/customers`
+pub async fn create_customer<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Json(body): Json<CreateCustomerRequestBody>,
+) -> Result<ApiSuccess<CustomerResponseData>, ApiError> {
+    let customer = state.bookings.create_customer(body.into()).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&customer).into()))
+}
+
+/// GET `/api/customers?q=...`
+///
+/// Fuzzy-matches customers by name, email address or phone number, best match first.
+pub async fn search_customers<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Query(params): Query<CustomerSearchParams>,
+) -> Result<ApiSuccess<CustomerSearchResponseData>, ApiError> {
+    let page = Page::new(params.limit, params.offset)?;
+    let results = state.bookings.search_customers(&params.q, page).await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        CustomerSearchResponseData::new(&results, page),
+    ))
+}
+
+/// GET `/api/customers/duplicates`
+///
+/// Reports pairs of customers who likely represent the same person.
+pub async fn find_duplicate_customers<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Query(params): Query<PageParams>,
+) -> Result<ApiSuccess<Vec<DuplicateCandidateResponseData>>, ApiError> {
+    let page = Page::new(params.limit, params.offset)?;
+    let candidates = state.bookings.find_duplicate_customers(page).await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        candidates.iter().map(Into::into).collect(),
+    ))
+}
+
+/// GET `/api/customers/:customer_id`
+pub async fn find_customer<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(customer_id): Path<Uuid>,
+) -> Result<ApiSuccess<CustomerResponseData>, ApiError> {
+    let customer = state
+        .bookings
+        .find_customer(CustomerId(customer_id))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("customer {customer_id} does not exist")))?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&customer).into()))
+}
+
+/// PATCH `/api/customers/:customer_id`
+///
+/// Only the fields present in the body are changed.
+pub async fn edit_customer<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(customer_id): Path<Uuid>,
+    Json(body): Json<EditCustomerRequestBody>,
+) -> Result<ApiSuccess<CustomerResponseData>, ApiError> {
+    let customer = state
+        .bookings
+        .edit_customer(body.into_domain(CustomerId(customer_id)))
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&customer).into()))
+}
+
+/// POST `/api/customers/:customer_id/merge`
+///
+/// Merges another customer into this one, moving their bookings and waitlist entries.
+pub async fn merge_customers<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(customer_id): Path<Uuid>,
+    Json(body): Json<MergeCustomersRequestBody>,
+) -> Result<ApiSuccess<CustomerMergeResponseData>, ApiError> {
+    let merge = state
+        .bookings
+        .merge_customers(body.into_domain(CustomerId(customer_id)))
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&merge).into()))
+}
+
+/// GET `/api/customers/:customer_id/merges`
+///
+/// Lists the merges this customer took part in, as either the survivor or the merged customer.
+pub async fn find_customer_merges<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(customer_id): Path<Uuid>,
+) -> Result<ApiSuccess<Vec<CustomerMergeResponseData>>, ApiError> {
+    let merges = state
+        .bookings
+        .find_customer_merges(CustomerId(customer_id))
+        .await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        merges.iter().map(Into::into).collect(),
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateCustomerRequestBody {
+    name: String,
+    email: String,
+    phone: String,
+}
+
+impl From<CreateCustomerRequestBody> for CreateCustomerRequest {
+    fn from(body: CreateCustomerRequestBody) -> Self {
+        Self {
+            name: body.name,
+            email: body.email,
+            phone: body.phone,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EditCustomerRequestBody {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    phone: Option<String>,
+}
+
+impl EditCustomerRequestBody {
+    fn into_domain(self, id: CustomerId) -> EditCustomerRequest {
+        EditCustomerRequest {
+            id,
+            name: self.name,
+            email: self.email,
+            phone: self.phone,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CustomerResponseData {
+    id: Uuid,
+    name: String,
+    email: String,
+    phone: String,
+}
+
+impl From<&Customer> for CustomerResponseData {
+    fn from(customer: &Customer) -> Self {
+        Self {
+            id: customer.id.0,
+            name: customer.name.0.clone(),
+            email: customer.email.0.clone(),
+            phone: customer.phone.0.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PageParams {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CustomerSearchParams {
+    q: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CustomerSearchResponseData {
+    customers: Vec<CustomerResponseData>,
+    total: i64,
+    limit: i64,
+    offset: i64,
+}
+
+impl CustomerSearchResponseData {
+    fn new(results: &CustomerSearchResults, page: Page) -> Self {
+        Self {
+            customers: results.customers.iter().map(Into::into).collect(),
+            total: results.total,
+            limit: page.limit,
+            offset: page.offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateCandidateResponseData {
+    customers: [CustomerResponseData; 2],
+    reasons: Vec<&'static str>,
+}
+
+impl From<&DuplicateCandidate> for DuplicateCandidateResponseData {
+    fn from(candidate: &DuplicateCandidate) -> Self {
+        Self {
+            customers: [(&candidate.first).into(), (&candidate.second).into()],
+            reasons: candidate.reasons.iter().map(|r| r.as_str()).collect(),
+        }
+    }
+}
+
+/// [MergeCustomersRequestBody] keeps the surviving customer's details unless told otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MergeCustomersRequestBody {
+    merged_customer_id: Uuid,
+    #[serde(default)]
+    use_merged_name: bool,
+    #[serde(default)]
+    use_merged_email: bool,
+    #[serde(default)]
+    use_merged_phone: bool,
+}
+
+impl MergeCustomersRequestBody {
+    fn into_domain(self, survivor: CustomerId) -> MergeCustomersRequest {
+        MergeCustomersRequest {
+            survivor,
+            merged: CustomerId(self.merged_customer_id),
+            use_merged_name: self.use_merged_name,
+            use_merged_email: self.use_merged_email,
+            use_merged_phone: self.use_merged_phone,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CustomerMergeResponseData {
+    id: Uuid,
+    survivor_id: Uuid,
+    survivor_before: Option<CustomerResponseData>,
+    merged: CustomerResponseData,
+    booking_ids: Vec<Uuid>,
+    waitlist_entry_ids: Vec<Uuid>,
+    merged_at: DateTime<Utc>,
+}
+
+impl From<&CustomerMerge> for CustomerMergeResponseData {
+    fn from(merge: &CustomerMerge) -> Self {
+        Self {
+            id: merge.id.0,
+            survivor_id: merge.survivor.0,
+            survivor_before: merge
+                .survivor_before
+                .as_ref()
+                .map(CustomerResponseData::from),
+            merged: (&merge.merged).into(),
+            booking_ids: merge.bookings.iter().map(|b| b.0).collect(),
+            waitlist_entry_ids: merge.waitlist_entries.iter().map(|e| e.0).collect(),
+            merged_at: merge.merged_at,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! HTTP handlers & DTOs for the `/api/equipment` resource.
+
+use crate::domain::booking::models::equipment::EquipmentAvailability;
+use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::ports::ScheduleService;
+use crate::inbound::http::responses::{ApiError, ApiSuccess};
+use crate::inbound::http::AppState;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// GET `/api/equipment/availability?start_time=&end_time=`
+pub async fn find_equipment_availability<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Query(params): Query<TimeWindowParams>,
+) -> Result<ApiSuccess<EquipmentAvailabilityResponseData>, ApiError> {
+    let availability = state
+        .bookings
+        .find_equipment_availability(params.start_time, params.end_time)
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, availability.into()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TimeWindowParams {
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EquipmentAvailabilityResponseData {
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    available: HashMap<Uuid, i32>,
+}
+
+impl From<EquipmentAvailability> for EquipmentAvailabilityResponseData {
+    fn from(availability: EquipmentAvailability) -> Self {
+        Self {
+            start_time: availability.start_time,
+            end_time: availability.end_time,
+            available: availability
+                .available
+                .into_iter()
+                .map(|(id, count)| (id.0, count))
+                .collect(),
+        }
+    }
+}
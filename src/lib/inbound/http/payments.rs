@@ -0,0 +1,146 @@
+//! HTTP handlers & DTOs for the `/api/payments` resource and a booking's payment ledger.
+
+use crate::domain::booking::models::booking::BookingId;
+use crate::domain::booking::models::payment::{
+    AuthorizePaymentRequest, CapturePaymentRequest, Payment, PaymentId, PaymentKind, PaymentLedger,
+    PaymentMethod, PaymentStatus, RefundPaymentRequest,
+};
+use crate::domain::booking::models::pricing::Money;
+use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::ports::ScheduleService;
+use crate::inbound::http::responses::{ApiError, ApiSuccess};
+use crate::inbound::http::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// GET `/api/bookings/:booking_id/payments`
+pub async fn find_payment_ledger<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+) -> Result<ApiSuccess<PaymentLedgerResponseData>, ApiError> {
+    let ledger = state
+        .bookings
+        .find_payment_ledger(BookingId(booking_id))
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&ledger).into()))
+}
+
+/// POST `/api/bookings/:booking_id/payments`
+pub async fn authorize_payment<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(booking_id): Path<Uuid>,
+    Json(body): Json<AuthorizePaymentRequestBody>,
+) -> Result<ApiSuccess<PaymentResponseData>, ApiError> {
+    let request = AuthorizePaymentRequest {
+        booking: BookingId(booking_id),
+        amount: Money(body.amount),
+        method: PaymentMethod(body.payment_method),
+    };
+    let payment = state.bookings.authorize_payment(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&payment).into()))
+}
+
+/// POST `/api/payments/:payment_id/capture`
+pub async fn capture_payment<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(payment_id): Path<Uuid>,
+    Json(body): Json<SettlePaymentRequestBody>,
+) -> Result<ApiSuccess<PaymentResponseData>, ApiError> {
+    let request = CapturePaymentRequest {
+        authorization: PaymentId(payment_id),
+        amount: body.amount.map(Money),
+    };
+    let payment = state.bookings.capture_payment(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&payment).into()))
+}
+
+/// POST `/api/payments/:payment_id/refund`
+pub async fn refund_payment<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(payment_id): Path<Uuid>,
+    Json(body): Json<SettlePaymentRequestBody>,
+) -> Result<ApiSuccess<PaymentResponseData>, ApiError> {
+    let request = RefundPaymentRequest {
+        capture: PaymentId(payment_id),
+        amount: body.amount.map(Money),
+    };
+    let payment = state.bookings.refund_payment(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&payment).into()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AuthorizePaymentRequestBody {
+    amount: i64,
+    payment_method: String,
+}
+
+/// [SettlePaymentRequestBody] captures or refunds everything that remains if no amount is given.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SettlePaymentRequestBody {
+    #[serde(default)]
+    amount: Option<i64>,
+}
+
+/// [PaymentResponseData] reports amounts in the smallest unit of the currency (e.g. cents).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaymentResponseData {
+    id: Uuid,
+    booking_id: Uuid,
+    kind: &'static str,
+    parent_id: Option<Uuid>,
+    amount: i64,
+    status: &'static str,
+    decline_reason: Option<String>,
+    reference: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<&Payment> for PaymentResponseData {
+    fn from(payment: &Payment) -> Self {
+        let (status, decline_reason) = match &payment.status {
+            PaymentStatus::Succeeded => ("succeeded", None),
+            PaymentStatus::Declined(reason) => ("declined", Some(reason.clone())),
+        };
+
+        Self {
+            id: payment.id.0,
+            booking_id: payment.booking.0,
+            kind: match payment.kind {
+                PaymentKind::Authorization => "authorization",
+                PaymentKind::Capture => "capture",
+                PaymentKind::Refund => "refund",
+            },
+            parent_id: payment.parent.as_ref().map(|p| p.0),
+            amount: payment.amount.0,
+            status,
+            decline_reason,
+            reference: payment.reference.as_ref().map(|r| r.0.clone()),
+            created_at: payment.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaymentLedgerResponseData {
+    booking_id: Uuid,
+    paid: i64,
+    payments: Vec<PaymentResponseData>,
+}
+
+impl From<&PaymentLedger> for PaymentLedgerResponseData {
+    fn from(ledger: &PaymentLedger) -> Self {
+        Self {
+            booking_id: ledger.booking.0,
+            paid: ledger.paid().0,
+            payments: ledger.payments.iter().map(Into::into).collect(),
+        }
+    }
+}
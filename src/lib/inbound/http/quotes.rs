@@ -0,0 +1,156 @@
+//! HTTP handlers & DTOs for the `/api/quotes` resource.
+
+use crate::domain::booking::models::booking::CreateParticipantRequest;
+use crate::domain::booking::models::equipment::EquipmentId;
+use crate::domain::booking::models::pricing::{
+    ParticipantCategory, Quote, QuoteAdjustment, QuoteItem, QuoteLine, QuoteRequest,
+};
+use crate::domain::booking::models::trip::TripId;
+use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::ports::ScheduleService;
+use crate::inbound::http::responses::{ApiError, ApiSuccess};
+use crate::inbound::http::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// POST `/api/quotes`
+///
+/// Prices a prospective booking without saving it.
+pub async fn quote_booking<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Json(body): Json<QuoteRequestBody>,
+) -> Result<ApiSuccess<QuoteResponseData>, ApiError> {
+    let quote = state.bookings.quote_booking(&body.into()).await?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&quote).into()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct QuoteRequestBody {
+    trip_id: Uuid,
+    participants: Vec<QuoteParticipantRequestBody>,
+    /// Quantities of each rented equipment, by equipment ID.
+    #[serde(default)]
+    rentals: HashMap<Uuid, i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct QuoteParticipantRequestBody {
+    name: String,
+    dob: NaiveDate,
+}
+
+impl From<QuoteRequestBody> for QuoteRequest {
+    fn from(body: QuoteRequestBody) -> Self {
+        Self {
+            trip: TripId(body.trip_id),
+            participants: body
+                .participants
+                .into_iter()
+                .map(|p| CreateParticipantRequest {
+                    name: p.name,
+                    dob: p.dob,
+                    notes: String::new(),
+                })
+                .collect(),
+            rentals: body
+                .rentals
+                .into_iter()
+                .map(|(id, quantity)| (EquipmentId(id), quantity))
+                .collect(),
+        }
+    }
+}
+
+/// [QuoteResponseData] reports all amounts in the smallest unit of the currency (e.g. cents).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuoteResponseData {
+    trip_id: Uuid,
+    lines: Vec<QuoteLineResponseData>,
+    subtotal: i64,
+    adjustments: Vec<QuoteAdjustmentResponseData>,
+    total: i64,
+    requirements: Vec<String>,
+}
+
+impl From<&Quote> for QuoteResponseData {
+    fn from(quote: &Quote) -> Self {
+        Self {
+            trip_id: quote.trip.0,
+            lines: quote.lines.iter().map(Into::into).collect(),
+            subtotal: quote.subtotal.0,
+            adjustments: quote.adjustments.iter().map(Into::into).collect(),
+            total: quote.total.0,
+            requirements: quote.requirements.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuoteItemResponseData {
+    Participant {
+        name: String,
+        category: &'static str,
+    },
+    Rental {
+        equipment_id: Uuid,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuoteLineResponseData {
+    item: QuoteItemResponseData,
+    unit_price: i64,
+    quantity: i32,
+    amount: i64,
+}
+
+impl From<&QuoteLine> for QuoteLineResponseData {
+    fn from(line: &QuoteLine) -> Self {
+        let item = match &line.item {
+            QuoteItem::Participant { name, category } => QuoteItemResponseData::Participant {
+                name: name.clone(),
+                category: match category {
+                    ParticipantCategory::Child => "child",
+                    ParticipantCategory::Adult => "adult",
+                    ParticipantCategory::Senior => "senior",
+                },
+            },
+            QuoteItem::Rental(equipment) => QuoteItemResponseData::Rental {
+                equipment_id: equipment.0,
+            },
+        };
+
+        Self {
+            item,
+            unit_price: line.unit_price.0,
+            quantity: line.quantity,
+            amount: line.amount.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuoteAdjustmentResponseData {
+    modifier_id: Uuid,
+    name: String,
+    percent: i32,
+    amount: i64,
+}
+
+impl From<&QuoteAdjustment> for QuoteAdjustmentResponseData {
+    fn from(adjustment: &QuoteAdjustment) -> Self {
+        Self {
+            modifier_id: adjustment.modifier.0,
+            name: adjustment.name.clone(),
+            percent: adjustment.percent,
+            amount: adjustment.amount.0,
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Response types shared by all HTTP handlers.
+
+use crate::domain::booking::models::booking::BookingError;
+use crate::domain::booking::models::customer::CustomerError;
+use crate::domain::booking::models::equipment::EquipmentError;
+use crate::domain::booking::models::payment::PaymentError;
+use crate::domain::booking::models::pricing::PricingError;
+use crate::domain::booking::models::waitlist::WaitlistError;
+use crate::domain::booking::models::waiver::WaiverError;
+use crate::domain::scheduling::models::schedule::ScheduleError;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// [ApiSuccess] is a successful JSON response with a status code.
+#[derive(Debug, Clone)]
+pub struct ApiSuccess<T: Serialize>(StatusCode, Json<T>);
+
+impl<T: Serialize> ApiSuccess<T> {
+    pub fn new(status: StatusCode, data: T) -> Self {
+        Self(status, Json(data))
+    }
+}
+
+impl<T: Serialize> IntoResponse for ApiSuccess<T> {
+    fn into_response(self) -> Response {
+        (self.0, self.1).into_response()
+    }
+}
+
+/// [ApiError] is an unsuccessful JSON response.
+///
+/// Internal errors are logged and reported to the client without any details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+    PaymentRequired(String),
+    Conflict(String),
+    UnprocessableEntity(String),
+    /// An unprocessable request with a detailed explanation for each offending item.
+    Invalid {
+        message: String,
+        details: Vec<ApiErrorDetail>,
+    },
+    InternalServerError(String),
+}
+
+/// [ApiErrorDetail] explains why a single item (e.g. a participant) was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiErrorDetail {
+    pub id: Uuid,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct ApiErrorBody {
+    error: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    details: Vec<ApiErrorDetail>,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message, details) = match self {
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message, vec![]),
+            ApiError::NotFound(message) => (StatusCode::NOT_FOUND, message, vec![]),
+            ApiError::PaymentRequired(message) => (StatusCode::PAYMENT_REQUIRED, message, vec![]),
+            ApiError::Conflict(message) => (StatusCode::CONFLICT, message, vec![]),
+            ApiError::UnprocessableEntity(message) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, message, vec![])
+            }
+            ApiError::Invalid { message, details } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, message, details)
+            }
+            ApiError::InternalServerError(message) => {
+                tracing::error!("{message}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal server error".to_string(),
+                    vec![],
+                )
+            }
+        };
+
+        let body = ApiErrorBody {
+            error: message,
+            details,
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+impl From<BookingError> for ApiError {
+    fn from(error: BookingError) -> Self {
+        match error {
+            BookingError::NotFound(_) => Self::NotFound(error.to_string()),
+            BookingError::MissingFilters | BookingError::InvalidStatus(_) => {
+                Self::BadRequest(error.to_string())
+            }
+            BookingError::TripFull { .. }
+            | BookingError::WaiversMissing(_)
+            | BookingError::HasPayments(_)
+            | BookingError::Closed(..)
+            | BookingError::HoldExpired(_)
+            | BookingError::StatusChanged { .. }
+            | BookingError::NoWaitlistOffer(_)
+            | BookingError::AlreadyBooked { .. }
+            | BookingError::InsufficientInventory { .. }
+            | BookingError::TripNotStarted(_)
+            | BookingError::TripNotEnded(_)
+            | BookingError::InvalidTransition { .. } => Self::Conflict(error.to_string()),
+            BookingError::CustomerNotFound(_)
+            | BookingError::TripNotFound(_)
+            | BookingError::TripStarted(_)
+            | BookingError::UnknownParticipant(_)
+            | BookingError::InvalidParticipantName(_)
+            | BookingError::ExceedsWaitlistOffer { .. }
+            | BookingError::IncompatibleTrip { .. }
+            | BookingError::UnknownReference(_)
+            | BookingError::MissingCancellationReason => {
+                Self::UnprocessableEntity(error.to_string())
+            }
+            BookingError::Ineligible(ref violations) => Self::Invalid {
+                message: error.to_string(),
+                details: violations
+                    .iter()
+                    .map(|v| ApiErrorDetail {
+                        id: v.participant.0,
+                        message: format!("{} is {} on the trip date: {}", v.name, v.age, v.reason),
+                    })
+                    .collect(),
+            },
+            BookingError::Unknown(cause) => Self::InternalServerError(format!("{cause:#}")),
+        }
+    }
+}
+
+impl From<CustomerError> for ApiError {
+    fn from(error: CustomerError) -> Self {
+        match error {
+            CustomerError::NotFound(_) => Self::NotFound(error.to_string()),
+            CustomerError::EmailTaken(_) => Self::Conflict(error.to_string()),
+            CustomerError::InvalidName(_)
+            | CustomerError::InvalidEmail(_)
+            | CustomerError::InvalidPhone(_)
+            | CustomerError::InvalidPhoneRegion(_)
+            | CustomerError::MergeIntoSelf(_) => Self::UnprocessableEntity(error.to_string()),
+            CustomerError::SearchTooShort(_)
+            | CustomerError::InvalidPageLimit(_)
+            | CustomerError::InvalidPageOffset(_) => Self::BadRequest(error.to_string()),
+            CustomerError::Unknown(cause) => Self::InternalServerError(format!("{cause:#}")),
+        }
+    }
+}
+
+impl From<EquipmentError> for ApiError {
+    fn from(error: EquipmentError) -> Self {
+        match error {
+            EquipmentError::BookingNotFound(_) | EquipmentError::NotFound(_) => {
+                Self::NotFound(error.to_string())
+            }
+            EquipmentError::InvalidTimeWindow => Self::BadRequest(error.to_string()),
+            EquipmentError::InvalidQuantity { .. } => Self::UnprocessableEntity(error.to_string()),
+            EquipmentError::InsufficientInventory { .. } => Self::Conflict(error.to_string()),
+            EquipmentError::Unknown(cause) => Self::InternalServerError(format!("{cause:#}")),
+        }
+    }
+}
+
+impl From<WaiverError> for ApiError {
+    fn from(error: WaiverError) -> Self {
+        match error {
+            WaiverError::NotFound(_) | WaiverError::ParticipantNotFound(_) => {
+                Self::NotFound(error.to_string())
+            }
+            WaiverError::OutdatedVersion(_) | WaiverError::ContentMismatch => {
+                Self::Conflict(error.to_string())
+            }
+            WaiverError::EmptyContent
+            | WaiverError::InvalidSignerName
+            | WaiverError::InvalidSignatureImage
+            | WaiverError::NotRequired { .. }
+            | WaiverError::GuardianRequired(_)
+            | WaiverError::GuardianNotAllowed(_) => Self::UnprocessableEntity(error.to_string()),
+            WaiverError::Unknown(cause) => Self::InternalServerError(format!("{cause:#}")),
+        }
+    }
+}
+
+impl From<PaymentError> for ApiError {
+    fn from(error: PaymentError) -> Self {
+        match error {
+            PaymentError::BookingNotFound(_) | PaymentError::NotFound(_) => {
+                Self::NotFound(error.to_string())
+            }
+            PaymentError::InvalidAmount(_) => Self::UnprocessableEntity(error.to_string()),
+            PaymentError::InvalidOperation(..) | PaymentError::ExceedsRemaining { .. } => {
+                Self::Conflict(error.to_string())
+            }
+            PaymentError::Declined(_) => Self::PaymentRequired(error.to_string()),
+            PaymentError::Unknown(cause) => Self::InternalServerError(format!("{cause:#}")),
+        }
+    }
+}
+
+impl From<PricingError> for ApiError {
+    fn from(error: PricingError) -> Self {
+        match error {
+            PricingError::TripNotFound(_)
+            | PricingError::MissingBasePrice(..)
+            | PricingError::NotRentable(_)
+            | PricingError::InvalidQuantity { .. } => Self::UnprocessableEntity(error.to_string()),
+            PricingError::Unknown(cause) => Self::InternalServerError(format!("{cause:#}")),
+        }
+    }
+}
+
+impl From<WaitlistError> for ApiError {
+    fn from(error: WaitlistError) -> Self {
+        match error {
+            WaitlistError::NotFound(_) => Self::NotFound(error.to_string()),
+            WaitlistError::NotWaiting(_) => Self::Conflict(error.to_string()),
+            WaitlistError::CustomerNotFound(_)
+            | WaitlistError::TripNotFound(_)
+            | WaitlistError::TripStarted(_)
+            | WaitlistError::InvalidPartySize(_) => Self::UnprocessableEntity(error.to_string()),
+            WaitlistError::Unknown(cause) => Self::InternalServerError(format!("{cause:#}")),
+        }
+    }
+}
+
+impl From<ScheduleError> for ApiError {
+    fn from(error: ScheduleError) -> Self {
+        match error {
+            ScheduleError::NotFound(_) | ScheduleError::OccurrenceNotFound(_) => {
+                Self::NotFound(error.to_string())
+            }
+            ScheduleError::Cancelled(_) | ScheduleError::OccurrenceHasBookings(_) => {
+                Self::Conflict(error.to_string())
+            }
+            ScheduleError::InvalidRule(_) | ScheduleError::InvalidTimeRange => {
+                Self::UnprocessableEntity(error.to_string())
+            }
+            ScheduleError::Unknown(cause) => Self::InternalServerError(format!("{cause:#}")),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! HTTP handlers & DTOs for the `/api/schedules` resource.
+
+use crate::domain::booking::models::trip::{LocationId, TripKindId};
+use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::models::schedule::{
+    CreateScheduleRequest, EditOccurrenceRequest, EditScheduleRequest, RecurrenceRule, Schedule,
+    ScheduleError, ScheduleId,
+};
+use crate::domain::scheduling::ports::ScheduleService;
+use crate::inbound::http::responses::{ApiError, ApiSuccess};
+use crate::inbound::http::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// POST `/api/schedules`
+pub async fn create_schedule<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Json(body): Json<CreateScheduleRequestBody>,
+) -> Result<ApiSuccess<ScheduleResponseData>, ApiError> {
+    let request = body.try_into()?;
+    let schedule = state.schedules.create_schedule(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&schedule).into()))
+}
+
+/// GET `/api/schedules/:schedule_id`
+pub async fn find_schedule<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<ApiSuccess<ScheduleResponseData>, ApiError> {
+    let schedule = state
+        .schedules
+        .find_schedule(ScheduleId(schedule_id))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("schedule {schedule_id} does not exist")))?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&schedule).into()))
+}
+
+/// PUT `/api/schedules/:schedule_id`
+pub async fn edit_schedule<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(schedule_id): Path<Uuid>,
+    Json(body): Json<EditScheduleRequestBody>,
+) -> Result<ApiSuccess<EditScheduleResponseData>, ApiError> {
+    let request = body.try_into_domain(ScheduleId(schedule_id))?;
+    let edit = state.schedules.edit_schedule(request).await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        EditScheduleResponseData {
+            schedule: (&edit.schedule).into(),
+            booked_trip_ids: edit.booked_trips.into_iter().map(|id| id.0).collect(),
+        },
+    ))
+}
+
+/// DELETE `/api/schedules/:schedule_id`
+pub async fn cancel_schedule<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<ApiSuccess<CancelScheduleResponseData>, ApiError> {
+    let booked = state
+        .schedules
+        .cancel_schedule(ScheduleId(schedule_id))
+        .await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        CancelScheduleResponseData {
+            booked_trip_ids: booked.into_iter().map(|id| id.0).collect(),
+        },
+    ))
+}
+
+/// PUT `/api/schedules/:schedule_id/occurrences/:scheduled_start`
+pub async fn edit_occurrence<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path((schedule_id, scheduled_start)): Path<(Uuid, DateTime<Utc>)>,
+    Json(body): Json<EditOccurrenceRequestBody>,
+) -> Result<ApiSuccess<OccurrenceResponseData>, ApiError> {
+    let request = EditOccurrenceRequest {
+        schedule: ScheduleId(schedule_id),
+        scheduled_start,
+        start_time: body.start_time,
+        end_time: body.end_time,
+    };
+    let trip_id = state.schedules.edit_occurrence(request).await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        OccurrenceResponseData {
+            trip_id: trip_id.0,
+            scheduled_start,
+            start_time: body.start_time,
+            end_time: body.end_time,
+        },
+    ))
+}
+
+/// DELETE `/api/schedules/:schedule_id/occurrences/:scheduled_start`
+pub async fn cancel_occurrence<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path((schedule_id, scheduled_start)): Path<(Uuid, DateTime<Utc>)>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .schedules
+        .cancel_occurrence(ScheduleId(schedule_id), scheduled_start)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateScheduleRequestBody {
+    trip_kind_id: Uuid,
+    location_id: Uuid,
+    recurrence: RecurrenceRuleBody,
+    #[serde(default)]
+    max_participants: Option<i32>,
+}
+
+impl TryFrom<CreateScheduleRequestBody> for CreateScheduleRequest {
+    type Error = ScheduleError;
+
+    fn try_from(body: CreateScheduleRequestBody) -> Result<Self, Self::Error> {
+        Ok(Self {
+            trip_kind: TripKindId(body.trip_kind_id),
+            location: LocationId(body.location_id),
+            recurrence: body.recurrence.try_into()?,
+            max_participants: body.max_participants,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EditScheduleRequestBody {
+    location_id: Uuid,
+    recurrence: RecurrenceRuleBody,
+    #[serde(default)]
+    max_participants: Option<i32>,
+}
+
+impl EditScheduleRequestBody {
+    fn try_into_domain(self, id: ScheduleId) -> Result<EditScheduleRequest, ScheduleError> {
+        Ok(EditScheduleRequest {
+            id,
+            location: LocationId(self.location_id),
+            recurrence: self.recurrence.try_into()?,
+            max_participants: self.max_participants,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EditOccurrenceRequestBody {
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurrenceRuleBody {
+    timezone: String,
+    starts_on: NaiveDate,
+    #[serde(default)]
+    ends_on: Option<NaiveDate>,
+    #[serde(default)]
+    weekdays: Vec<Weekday>,
+    #[serde(default)]
+    months: Vec<u32>,
+    times: Vec<NaiveTime>,
+    duration_minutes: i64,
+}
+
+impl TryFrom<RecurrenceRuleBody> for RecurrenceRule {
+    type Error = ScheduleError;
+
+    fn try_from(body: RecurrenceRuleBody) -> Result<Self, Self::Error> {
+        let timezone = body.timezone.parse::<Tz>().map_err(|_| {
+            ScheduleError::InvalidRule(format!("\"{}\" is not a valid timezone", body.timezone))
+        })?;
+        let duration = Duration::try_minutes(body.duration_minutes)
+            .ok_or_else(|| ScheduleError::InvalidRule("duration is too long".to_string()))?;
+
+        Ok(Self {
+            timezone,
+            starts_on: body.starts_on,
+            ends_on: body.ends_on,
+            weekdays: body.weekdays,
+            months: body.months,
+            times: body.times,
+            duration,
+        })
+    }
+}
+
+impl From<&RecurrenceRule> for RecurrenceRuleBody {
+    fn from(rule: &RecurrenceRule) -> Self {
+        Self {
+            timezone: rule.timezone.name().to_string(),
+            starts_on: rule.starts_on,
+            ends_on: rule.ends_on,
+            weekdays: rule.weekdays.clone(),
+            months: rule.months.clone(),
+            times: rule.times.clone(),
+            duration_minutes: rule.duration.num_minutes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScheduleResponseData {
+    id: Uuid,
+    trip_kind_id: Uuid,
+    location_id: Uuid,
+    recurrence: RecurrenceRuleBody,
+    max_participants: Option<i32>,
+    cancelled_at: Option<DateTime<Utc>>,
+}
+
+impl From<&Schedule> for ScheduleResponseData {
+    fn from(schedule: &Schedule) -> Self {
+        Self {
+            id: schedule.id.0,
+            trip_kind_id: schedule.trip_kind.0,
+            location_id: schedule.location.0,
+            recurrence: (&schedule.recurrence).into(),
+            max_participants: schedule.max_participants,
+            cancelled_at: schedule.cancelled_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EditScheduleResponseData {
+    #[serde(flatten)]
+    schedule: ScheduleResponseData,
+    booked_trip_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CancelScheduleResponseData {
+    booked_trip_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OccurrenceResponseData {
+    trip_id: Uuid,
+    scheduled_start: DateTime<Utc>,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+}
@@ -0,0 +1,119 @@
+//! HTTP handlers & DTOs for the `/api/waitlist` resource and a trip's waitlist.
+
+use crate::domain::booking::models::customer::CustomerId;
+use crate::domain::booking::models::trip::TripId;
+use crate::domain::booking::models::waitlist::{
+    JoinWaitlistRequest, WaitlistEntryId, WaitlistPosition, WaitlistStatus,
+};
+use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::ports::ScheduleService;
+use crate::inbound::http::responses::{ApiError, ApiSuccess};
+use crate::inbound::http::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// POST `/api/trips/:trip_id/waitlist`
+pub async fn join_waitlist<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(trip_id): Path<Uuid>,
+    Json(body): Json<JoinWaitlistRequestBody>,
+) -> Result<ApiSuccess<WaitlistEntryResponseData>, ApiError> {
+    let request = JoinWaitlistRequest {
+        customer: CustomerId(body.customer_id),
+        trip: TripId(trip_id),
+        party_size: body.party_size,
+    };
+    let position = state.bookings.join_waitlist(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&position).into()))
+}
+
+/// GET `/api/trips/:trip_id/waitlist`
+pub async fn find_waitlist<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(trip_id): Path<Uuid>,
+) -> Result<ApiSuccess<Vec<WaitlistEntryResponseData>>, ApiError> {
+    let waitlist = state.bookings.find_waitlist(TripId(trip_id)).await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        waitlist.iter().map(Into::into).collect(),
+    ))
+}
+
+/// GET `/api/waitlist/:entry_id`
+pub async fn find_waitlist_entry<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(entry_id): Path<Uuid>,
+) -> Result<ApiSuccess<WaitlistEntryResponseData>, ApiError> {
+    let position = state
+        .bookings
+        .find_waitlist_entry(WaitlistEntryId(entry_id))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("waitlist entry {entry_id} does not exist")))?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&position).into()))
+}
+
+/// DELETE `/api/waitlist/:entry_id`
+///
+/// Withdraws the party from the waitlist, keeping the entry for reporting.
+pub async fn leave_waitlist<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(entry_id): Path<Uuid>,
+) -> Result<ApiSuccess<WaitlistEntryResponseData>, ApiError> {
+    let entry = state
+        .bookings
+        .leave_waitlist(WaitlistEntryId(entry_id))
+        .await?;
+    let position = WaitlistPosition {
+        entry,
+        position: None,
+    };
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&position).into()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct JoinWaitlistRequestBody {
+    customer_id: Uuid,
+    party_size: i32,
+}
+
+/// [WaitlistEntryResponseData] has a `position` while the party is waiting, and an
+/// `offer_expires_at` while they have been offered seats.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WaitlistEntryResponseData {
+    id: Uuid,
+    customer_id: Uuid,
+    trip_id: Uuid,
+    party_size: i32,
+    status: &'static str,
+    position: Option<usize>,
+    offer_expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<&WaitlistPosition> for WaitlistEntryResponseData {
+    fn from(position: &WaitlistPosition) -> Self {
+        let entry = &position.entry;
+
+        Self {
+            id: entry.id.0,
+            customer_id: entry.customer.0,
+            trip_id: entry.trip.0,
+            party_size: entry.party_size,
+            status: entry.status.as_str(),
+            position: position.position,
+            offer_expires_at: match entry.status {
+                WaitlistStatus::Offered { expires_at } => Some(expires_at),
+                _ => None,
+            },
+            created_at: entry.created_at,
+        }
+    }
+}
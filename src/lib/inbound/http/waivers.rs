@@ -0,0 +1,221 @@
+//! HTTP handlers & DTOs for the `/api/waivers` resource.
+
+use crate::domain::booking::models::booking::ParticipantId;
+use crate::domain::booking::models::waiver::{
+    CreateWaiverRequest, SignWaiverRequest, Signer, Waiver, WaiverId, WaiverSignature,
+};
+use crate::domain::booking::ports::BookingService;
+use crate::domain::scheduling::ports::ScheduleService;
+use crate::inbound::http::responses::{ApiError, ApiSuccess};
+use crate::inbound::http::AppState;
+use axum::extract::{ConnectInfo, Path, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::Json;
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use uuid::Uuid;
+
+/// POST `/api/waivers`
+pub async fn create_waiver<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Json(body): Json<CreateWaiverRequestBody>,
+) -> Result<ApiSuccess<WaiverResponseData>, ApiError> {
+    let waiver = state.bookings.create_waiver(body.into_domain(None)).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&waiver).into()))
+}
+
+/// POST `/api/waivers/:waiver_id/versions`
+pub async fn create_waiver_version<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(waiver_id): Path<Uuid>,
+    Json(body): Json<CreateWaiverRequestBody>,
+) -> Result<ApiSuccess<WaiverResponseData>, ApiError> {
+    let waiver = state
+        .bookings
+        .create_waiver(body.into_domain(Some(WaiverId(waiver_id))))
+        .await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&waiver).into()))
+}
+
+/// GET `/api/waivers/:waiver_id`
+pub async fn find_current_waiver<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    Path(waiver_id): Path<Uuid>,
+) -> Result<ApiSuccess<WaiverResponseData>, ApiError> {
+    let waiver = state
+        .bookings
+        .find_current_waiver(WaiverId(waiver_id))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("waiver {waiver_id} is not in force")))?;
+
+    Ok(ApiSuccess::new(StatusCode::OK, (&waiver).into()))
+}
+
+/// POST `/api/waivers/:waiver_id/signatures`
+///
+/// The signer's IP address is the address of the connecting client, unless it's a trusted
+/// proxy, in which case it's the last address in the `X-Forwarded-For` header that isn't one.
+pub async fn sign_waiver<BS: BookingService, SS: ScheduleService>(
+    State(state): State<AppState<BS, SS>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(waiver_id): Path<Uuid>,
+    Json(body): Json<SignWaiverRequestBody>,
+) -> Result<ApiSuccess<WaiverSignatureResponseData>, ApiError> {
+    let signature_image = BASE64_STANDARD
+        .decode(&body.signature_image)
+        .map_err(|_| ApiError::BadRequest("signature_image must be base64".to_string()))?;
+
+    let ip = client_ip(client.ip(), &headers, &state.trusted_proxies);
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let request = SignWaiverRequest {
+        waiver: WaiverId(waiver_id),
+        participant: ParticipantId(body.participant_id),
+        version: body.version,
+        content_hash: body.content_hash,
+        signer: Signer {
+            name: body.signer_name,
+            ip: Some(ip),
+            user_agent,
+            guardian_relationship: body.guardian_relationship,
+        },
+        signature_image,
+    };
+    let signature = state.bookings.sign_waiver(request).await?;
+
+    Ok(ApiSuccess::new(StatusCode::CREATED, (&signature).into()))
+}
+
+/// Gets the address of the client a request came from, following the `X-Forwarded-For`
+/// header back through trusted proxies only, since any client can set it.
+fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let forwarded = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect::<Vec<_>>();
+
+    let mut ip = peer;
+    for hop in forwarded.into_iter().rev() {
+        if !trusted_proxies.contains(&ip) {
+            break;
+        }
+        match hop.trim().parse() {
+            Ok(hop) => ip = hop,
+            Err(_) => break,
+        }
+    }
+
+    ip
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateWaiverRequestBody {
+    content: String,
+    #[serde(default)]
+    effective_at: Option<DateTime<Utc>>,
+}
+
+impl CreateWaiverRequestBody {
+    fn into_domain(self, id: Option<WaiverId>) -> CreateWaiverRequest {
+        CreateWaiverRequest {
+            id,
+            content: self.content,
+            effective_at: self.effective_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SignWaiverRequestBody {
+    participant_id: Uuid,
+    version: i32,
+    content_hash: String,
+    signer_name: String,
+    #[serde(default)]
+    guardian_relationship: Option<String>,
+    /// A base64-encoded PNG of the captured signature.
+    signature_image: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WaiverResponseData {
+    id: Uuid,
+    version: i32,
+    content: String,
+    content_hash: String,
+    effective_at: DateTime<Utc>,
+}
+
+impl From<&Waiver> for WaiverResponseData {
+    fn from(waiver: &Waiver) -> Self {
+        Self {
+            id: waiver.id.0,
+            version: waiver.version,
+            content: waiver.content.clone(),
+            content_hash: waiver.content_hash(),
+            effective_at: waiver.effective_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WaiverSignatureResponseData {
+    id: Uuid,
+    participant_id: Uuid,
+    waiver_id: Uuid,
+    version: i32,
+    content_hash: Option<String>,
+    signed_at: DateTime<Utc>,
+    signer_name: Option<String>,
+    guardian_relationship: Option<String>,
+}
+
+impl From<&WaiverSignature> for WaiverSignatureResponseData {
+    fn from(signature: &WaiverSignature) -> Self {
+        Self {
+            id: signature.id.0,
+            participant_id: signature.participant.0,
+            waiver_id: signature.waiver.0,
+            version: signature.version,
+            content_hash: signature.content_hash.clone(),
+            signed_at: signature.signed_at,
+            signer_name: signature.signer.as_ref().map(|s| s.name.clone()),
+            guardian_relationship: signature
+                .signer
+                .as_ref()
+                .and_then(|s| s.guardian_relationship.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn client_ip_only_follows_forwarded_for_through_trusted_proxies() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.1, 203.0.113.7"),
+        );
+
+        assert_eq!(client_ip(proxy, &headers, &[proxy]), client);
+        assert_eq!(client_ip(proxy, &headers, &[]), proxy);
+        assert_eq!(client_ip(client, &headers, &[proxy]), client);
+        assert_eq!(client_ip(proxy, &HeaderMap::new(), &[proxy]), proxy);
+    }
+}
@@ -1 +1,9 @@
-pub mod postgres;
\ No newline at end of file
+#[cfg(test)]
+mod conformance;
+pub mod fake_payments;
+pub mod filesystem;
+mod fuzzy;
+pub mod memory;
+pub mod postgres;
+pub mod sqlite;
+mod transaction;
@@ -0,0 +1,2334 @@
+//! Module [conformance] is a test suite that every [UnitOfWork] and [ScheduleRepository] adapter
+//! must pass, so the domain behaves the same whichever storage it runs on.
+//!
+//! Each test is generic over the repository, which also implements [Catalog] to add the
+//! reference data the application never writes itself. An adapter runs the whole suite
+//! with [booking_repository_conformance], giving the test attribute, the test function's
+//! parameters and an expression creating an empty repository, e.g.
+//!
+//! ```ignore
+//! booking_repository_conformance!(#[tokio::test] () => Memory::new());
+//! ```
+
+use crate::domain::booking::models::blob::BlobKey;
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::customer::*;
+use crate::domain::booking::models::equipment::*;
+use crate::domain::booking::models::payment::*;
+use crate::domain::booking::models::pricing::*;
+use crate::domain::booking::models::trip::*;
+use crate::domain::booking::models::waitlist::*;
+use crate::domain::booking::models::waiver::*;
+use crate::domain::booking::ports::{
+    BookingRepository, CustomerRepository, TripRepository, UnitOfWork,
+};
+use crate::domain::scheduling::models::schedule::*;
+use crate::domain::scheduling::ports::ScheduleRepository;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, SubsecRound, Utc, Weekday};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// [Catalog] adds the reference data the repositories read but never write.
+pub(crate) trait Catalog {
+    /// Adds a one-off [Trip], along with its kind and location if they are new.
+    async fn insert_trip(&self, trip: &Trip);
+
+    async fn insert_equipment(&self, equipment: &Equipment);
+
+    async fn insert_base_price(
+        &self,
+        kind: &TripKindId,
+        category: ParticipantCategory,
+        price: Money,
+    );
+
+    async fn insert_price_modifier(&self, modifier: &PriceModifier);
+}
+
+/// Defines a test running each conformance test against the repository made by `$repository`.
+macro_rules! booking_repository_conformance {
+    (#[$test:meta] $params:tt => $repository:expr) => {
+        $crate::outbound::conformance::booking_repository_conformance!(
+            @tests #[$test] $params => $repository;
+            save_booking_saves_and_replaces_bookings,
+            find_bookings_returns_nothing_without_filters,
+            find_bookings_applies_every_filter,
+            find_booking_keeps_the_order_of_participants,
+            find_booking_finds_bookings_without_participants,
+            find_booking_lists_participants_once_with_their_latest_waiver,
+            find_bookings_by_participant_returns_the_whole_booking,
+            save_booking_applies_the_overbooking_policy,
+            save_booking_marks_flagged_overbookings,
+            save_booking_keeps_seats_the_booking_already_has,
+            save_booking_ignores_cancelled_bookings_and_expired_holds,
+            save_booking_reserves_seats_offered_to_the_waitlist,
+            save_booking_rejects_participants_already_on_the_trip,
+            concurrent_bookings_cannot_overbook_a_trip,
+            reschedule_booking_checks_seats_and_rentals,
+            cancel_expired_holds_cancels_only_lapsed_holds,
+            confirm_hold_confirms_only_live_holds,
+            save_booking_status_requires_the_status_it_was_read_with,
+            delete_booking_removes_rentals_but_keeps_participants,
+            save_waitlist_entry_saves_and_replaces_entries,
+            find_waitlisted_trips_lists_upcoming_trips_with_seats_to_offer,
+            offer_waitlist_seats_offers_in_order_until_a_party_does_not_fit,
+            offer_waitlist_seats_rejects_missing_trips,
+            save_customer_saves_and_replaces_customers,
+            save_customer_rejects_taken_emails_ignoring_case,
+            find_customers_pages_through_customers_in_id_order,
+            search_customers_matches_names_emails_and_phones,
+            find_duplicate_customers_pairs_similar_customers,
+            merge_customers_moves_bookings_and_waitlist_entries,
+            merge_customers_rejects_missing_customers,
+            find_trips_returns_nothing_without_filters,
+            find_trips_applies_every_filter,
+            save_occurrences_materializes_each_occurrence_once,
+            save_occurrences_leaves_detached_and_booked_trips_in_place,
+            save_booking_rentals_replaces_and_sums_rentals,
+            save_booking_rentals_rejects_missing_bookings_and_equipment,
+            save_booking_rentals_checks_inventory_of_overlapping_trips,
+            concurrent_rentals_cannot_exceed_inventory,
+            find_equipment_availability_subtracts_active_rentals,
+            find_current_waiver_picks_the_version_in_force,
+            save_waiver_signature_sets_the_participant_waiver,
+            find_price_list_picks_matching_prices_and_modifiers,
+            save_payment_builds_the_ledger,
+            unit_of_work_commits_changes_across_repositories,
+            unit_of_work_discards_uncommitted_changes,
+        );
+    };
+    (@tests #[$test:meta] $params:tt => $repository:expr; $($name:ident,)*) => {
+        $(
+            #[$test]
+            async fn $name $params {
+                $crate::outbound::conformance::$name($repository).await;
+            }
+        )*
+    };
+}
+
+pub(crate) use booking_repository_conformance;
+
+/// The current time, truncated to the microsecond precision every adapter keeps.
+fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(6)
+}
+
+fn customer(name: &str, email: &str, phone: &str) -> Customer {
+    Customer {
+        id: CustomerId(Uuid::now_v7()),
+        name: CustomerName(name.to_string()),
+        email: EmailAddress(email.to_string()),
+        phone: PhoneNumber(phone.to_string()),
+    }
+}
+
+fn trip_kind(max_participants: Option<i32>) -> TripKind {
+    let id = Uuid::now_v7();
+
+    TripKind {
+        id: TripKindId(id),
+        name: format!("Sea Kayak Tour {id}"),
+        description: "Paddle the harbour".to_string(),
+        guided: true,
+        meal_provided: false,
+        max_participants,
+        waiver: None,
+        eligibility: Eligibility {
+            min_age: Some(8),
+            max_age: Some(80),
+            guardian_required_under: Some(16),
+            requirements: vec!["Able to swim".to_string()],
+        },
+        cancellation_policy: CancellationPolicy {
+            tiers: vec![
+                RefundTier {
+                    notice: Duration::days(2),
+                    percent: 50,
+                },
+                RefundTier {
+                    notice: Duration::days(7),
+                    percent: 100,
+                },
+            ],
+        },
+    }
+}
+
+/// A three hour trip of the given kind from the given location.
+fn trip(kind: &TripKind, location: &LocationId, start_time: DateTime<Utc>) -> Trip {
+    Trip {
+        id: TripId(Uuid::now_v7()),
+        kind: kind.clone(),
+        location: location.clone(),
+        start_time,
+        end_time: start_time + Duration::hours(3),
+        max_participants: None,
+        timezone: Tz::UTC,
+    }
+}
+
+/// Adds a trip starting tomorrow, with room for `max_participants` if given.
+async fn seeded_trip<R: Catalog>(repo: &R, max_participants: Option<i32>) -> Trip {
+    let trip = trip(
+        &trip_kind(max_participants),
+        &LocationId(Uuid::now_v7()),
+        now() + Duration::days(1),
+    );
+    repo.insert_trip(&trip).await;
+
+    trip
+}
+
+/// A daily schedule of the trip's kind departing from its location at 10:00 UTC.
+fn schedule(trip: &Trip) -> Schedule {
+    Schedule {
+        id: ScheduleId(Uuid::now_v7()),
+        trip_kind: trip.kind.id.clone(),
+        location: trip.location.clone(),
+        recurrence: RecurrenceRule {
+            timezone: Tz::UTC,
+            starts_on: now().date_naive(),
+            ends_on: None,
+            weekdays: vec![],
+            months: vec![],
+            times: vec![NaiveTime::from_hms_opt(10, 0, 0).unwrap()],
+            duration: Duration::hours(2),
+        },
+        max_participants: None,
+        cancelled_at: None,
+    }
+}
+
+/// The schedule's occurrences three to five days from now, after the trip it was made from.
+fn upcoming_occurrences(schedule: &Schedule) -> Vec<Occurrence> {
+    let today = now().date_naive();
+
+    schedule
+        .recurrence
+        .occurrences(today + Duration::days(3), today + Duration::days(5))
+}
+
+/// Gets the trips materialized from a schedule made by [schedule] from `trip`, by start time.
+async fn scheduled_trips<R: TripRepository>(repo: &R, trip: &Trip) -> Vec<Trip> {
+    let filters = TripFilters {
+        kind: Some(trip.kind.id.clone()),
+        date_range: Some((trip.end_time, trip.end_time + Duration::days(30))),
+        ..TripFilters::default()
+    };
+    let mut trips = repo.find_trips(&filters).await.unwrap();
+    trips.sort_by_key(|t| t.start_time);
+
+    trips
+}
+
+async fn seeded_customer<R: CustomerRepository>(repo: &R) -> Customer {
+    let id = Uuid::now_v7();
+    let customer = customer(
+        "Ana Reyes",
+        &format!("ana.{id}@example.com"),
+        "+14155550100",
+    );
+    repo.save_customer(&customer).await.unwrap();
+
+    customer
+}
+
+async fn seeded_equipment<R: Catalog>(repo: &R, total_inventory: i32) -> Equipment {
+    let id = Uuid::now_v7();
+    let equipment = Equipment {
+        id: EquipmentId(id),
+        name: EquipmentName(format!("Wetsuit {id}")),
+        description: EquipmentDescription(String::new()),
+        total_inventory,
+        rental_price: Some(Money(1500)),
+    };
+    repo.insert_equipment(&equipment).await;
+
+    equipment
+}
+
+fn booking(customer: &Customer, trip: &Trip, participants: usize) -> Booking {
+    Booking {
+        id: BookingId(Uuid::now_v7()),
+        reference: BookingReference::generate(),
+        customer: customer.id.clone(),
+        trip: trip.id.clone(),
+        participants: (0..participants)
+            .map(|_| Participant {
+                id: ParticipantId(Uuid::now_v7()),
+                // Participants of different bookings are different people.
+                name: format!("Participant {}", Uuid::now_v7()),
+                dob: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+                notes: String::new(),
+                waiver: None,
+            })
+            .collect(),
+        status: BookingStatus::Confirmed,
+        hold_expires_at: None,
+        checked_in_at: None,
+        cancellation: None,
+        waitlist_entry: None,
+        overbooked: false,
+    }
+}
+
+fn waitlist_entry(
+    customer: &Customer,
+    trip: &Trip,
+    party_size: i32,
+    created_at: DateTime<Utc>,
+) -> WaitlistEntry {
+    WaitlistEntry {
+        id: WaitlistEntryId(Uuid::now_v7()),
+        customer: customer.id.clone(),
+        trip: trip.id.clone(),
+        party_size,
+        status: WaitlistStatus::Waiting,
+        created_at,
+    }
+}
+
+/// Orders bookings, and the participants of each, by ID, as the ports leave order unspecified.
+fn sorted(mut bookings: Vec<Booking>) -> Vec<Booking> {
+    bookings.sort_by(|a, b| a.id.cmp(&b.id));
+
+    bookings
+}
+
+async fn found<R: BookingRepository>(repo: &R, id: &BookingId) -> Booking {
+    repo.find_booking(id.clone()).await.unwrap().unwrap()
+}
+
+pub(crate) async fn save_booking_saves_and_replaces_bookings<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let mut booking = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(now() + Duration::minutes(15)),
+        ..booking(&customer, &trip, 3)
+    };
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    assert_eq!(found(&repo, &booking.id).await, booking);
+
+    booking.participants.remove(1);
+    booking.participants[0].name = "Renamed".to_string();
+    booking.participants[0].notes = "Vegetarian".to_string();
+    booking.status = BookingStatus::Cancelled;
+    booking.hold_expires_at = None;
+    booking.cancellation = Some(Cancellation {
+        reason: "weather".to_string(),
+        cancelled_at: now(),
+        refund: Money(4500),
+    });
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    assert_eq!(found(&repo, &booking.id).await, booking);
+
+    let missing = BookingId(Uuid::now_v7());
+    assert_eq!(repo.find_booking(missing).await.unwrap(), None);
+}
+
+pub(crate) async fn find_bookings_returns_nothing_without_filters<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    repo.save_booking(&booking(&customer, &trip, 1), OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    let unfiltered = repo.find_bookings(&Default::default()).await.unwrap();
+    assert!(unfiltered.is_empty());
+}
+
+pub(crate) async fn find_bookings_applies_every_filter<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let other_customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let other_trip = seeded_trip(&repo, Some(8)).await;
+
+    let confirmed = booking(&customer, &trip, 2);
+    let held = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(now() + Duration::minutes(15)),
+        ..booking(&customer, &trip, 1)
+    };
+    let other = booking(&other_customer, &other_trip, 1);
+    for booking in [&confirmed, &held, &other] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+
+    let cases = [
+        (
+            BookingFilters {
+                reference: Some(held.reference.clone()),
+                ..Default::default()
+            },
+            vec![&held],
+        ),
+        (
+            BookingFilters {
+                customer: Some(customer.id.clone()),
+                ..Default::default()
+            },
+            vec![&confirmed, &held],
+        ),
+        (
+            BookingFilters {
+                trip: Some(other_trip.id.clone()),
+                ..Default::default()
+            },
+            vec![&other],
+        ),
+        (
+            BookingFilters {
+                participant: Some(other.participants[0].id.clone()),
+                ..Default::default()
+            },
+            vec![&other],
+        ),
+        (
+            BookingFilters {
+                status: Some(BookingStatus::Held),
+                ..Default::default()
+            },
+            vec![&held],
+        ),
+        (
+            BookingFilters {
+                customer: Some(customer.id.clone()),
+                status: Some(BookingStatus::Confirmed),
+                ..Default::default()
+            },
+            vec![&confirmed],
+        ),
+        (
+            BookingFilters {
+                customer: Some(other_customer.id.clone()),
+                trip: Some(trip.id.clone()),
+                ..Default::default()
+            },
+            vec![],
+        ),
+    ];
+    for (filters, expected) in cases {
+        let found = repo.find_bookings(&filters).await.unwrap();
+        let expected = expected.into_iter().cloned().collect();
+        assert_eq!(sorted(found), sorted(expected), "{filters:?}");
+    }
+}
+
+pub(crate) async fn find_booking_keeps_the_order_of_participants<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let mut booking = booking(&customer, &trip, 3);
+    booking.participants.reverse();
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    assert_eq!(found(&repo, &booking.id).await, booking);
+
+    booking.participants.swap(0, 2);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    assert_eq!(found(&repo, &booking.id).await, booking);
+}
+
+pub(crate) async fn find_booking_finds_bookings_without_participants<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let booking = booking(&customer, &trip, 0);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    assert_eq!(found(&repo, &booking.id).await, booking);
+    let filters = BookingFilters {
+        customer: Some(customer.id.clone()),
+        ..BookingFilters::default()
+    };
+    assert_eq!(repo.find_bookings(&filters).await.unwrap(), vec![booking]);
+}
+
+pub(crate) async fn find_booking_lists_participants_once_with_their_latest_waiver<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let booking = booking(&customer, &trip, 2);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    let waivers = [1, 2].map(|version| Waiver {
+        id: WaiverId(Uuid::now_v7()),
+        version,
+        content: format!("I accept the risks of sea kayaking (v{version})"),
+        effective_at: now() - Duration::days(1),
+    });
+    for waiver in &waivers {
+        repo.save_waiver(waiver).await.unwrap();
+    }
+
+    let signer = &booking.participants[0];
+    let signed_at = now() - Duration::hours(1);
+    for (waiver, signed_at) in [
+        (&waivers[1], signed_at),
+        (&waivers[0], signed_at + Duration::minutes(10)),
+        (&waivers[1], signed_at - Duration::minutes(10)),
+    ] {
+        let signature = WaiverSignature {
+            id: WaiverSignatureId(Uuid::now_v7()),
+            participant: signer.id.clone(),
+            waiver: waiver.id.clone(),
+            version: waiver.version,
+            content_hash: None,
+            signed_at,
+            signer: None,
+            signature_image: None,
+        };
+        repo.save_waiver_signature(&signature).await.unwrap();
+    }
+
+    let expected = Booking {
+        participants: vec![
+            Participant {
+                waiver: Some(waivers[0].id.clone()),
+                ..signer.clone()
+            },
+            booking.participants[1].clone(),
+        ],
+        ..booking.clone()
+    };
+    assert_eq!(found(&repo, &booking.id).await, expected);
+    let filters = BookingFilters {
+        trip: Some(trip.id),
+        ..BookingFilters::default()
+    };
+    assert_eq!(repo.find_bookings(&filters).await.unwrap(), vec![expected]);
+}
+
+pub(crate) async fn find_bookings_by_participant_returns_the_whole_booking<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let booking = booking(&customer, &trip, 3);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    for participant in &booking.participants {
+        let filters = BookingFilters {
+            participant: Some(participant.id.clone()),
+            ..BookingFilters::default()
+        };
+        assert_eq!(
+            repo.find_bookings(&filters).await.unwrap(),
+            vec![booking.clone()]
+        );
+    }
+}
+
+pub(crate) async fn save_booking_applies_the_overbooking_policy<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(2)).await;
+    repo.save_booking(&booking(&customer, &trip, 2), OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    let extra = booking(&customer, &trip, 1);
+    let rejected = repo.save_booking(&extra, OverbookingPolicy::Reject).await;
+    assert!(matches!(
+        rejected,
+        Err(BookingError::TripFull {
+            capacity: 2,
+            available: 0,
+            ..
+        })
+    ));
+    assert_eq!(repo.find_booking(extra.id.clone()).await.unwrap(), None);
+
+    repo.save_booking(&extra, OverbookingPolicy::Flag)
+        .await
+        .unwrap();
+    assert_eq!(
+        found(&repo, &extra.id).await,
+        Booking {
+            overbooked: true,
+            ..extra
+        }
+    );
+
+    let unlimited = seeded_trip(&repo, None).await;
+    repo.save_booking(
+        &booking(&customer, &unlimited, 50),
+        OverbookingPolicy::Reject,
+    )
+    .await
+    .unwrap();
+}
+
+pub(crate) async fn save_booking_marks_flagged_overbookings<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(2)).await;
+    let fits = booking(&customer, &trip, 2);
+    let overbooked = repo
+        .save_booking(&fits, OverbookingPolicy::Flag)
+        .await
+        .unwrap();
+    assert!(!overbooked);
+    assert!(!found(&repo, &fits.id).await.overbooked);
+
+    let extra = booking(&customer, &trip, 1);
+    let overbooked = repo
+        .save_booking(&extra, OverbookingPolicy::Flag)
+        .await
+        .unwrap();
+    assert!(overbooked);
+    let mut extra = found(&repo, &extra.id).await;
+    assert!(extra.overbooked);
+
+    // Edits keep the mark until staff resolve the overbooking.
+    extra.participants[0].notes = "Vegetarian".to_string();
+    let overbooked = repo
+        .save_booking(&extra, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    assert!(overbooked);
+    assert!(found(&repo, &extra.id).await.overbooked);
+
+    let roomy = seeded_trip(&repo, Some(8)).await;
+    let moved = Booking {
+        trip: roomy.id.clone(),
+        overbooked: false,
+        ..extra
+    };
+    let overbooked = repo
+        .reschedule_booking(&moved, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    assert!(!overbooked);
+    assert_eq!(found(&repo, &moved.id).await, moved);
+}
+
+pub(crate) async fn save_booking_keeps_seats_the_booking_already_has<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = Trip {
+        max_participants: Some(3),
+        ..seeded_trip(&repo, Some(8)).await
+    };
+    repo.insert_trip(&trip).await;
+
+    let mut booking = booking(&customer, &trip, 3);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    booking.status = BookingStatus::CheckedIn;
+    booking.checked_in_at = Some(now());
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    assert_eq!(found(&repo, &booking.id).await, booking);
+
+    let mut grown = booking.clone();
+    grown
+        .participants
+        .extend(self::booking(&customer, &trip, 1).participants);
+    let rejected = repo.save_booking(&grown, OverbookingPolicy::Reject).await;
+    assert!(matches!(
+        rejected,
+        Err(BookingError::TripFull {
+            capacity: 3,
+            available: 3,
+            ..
+        })
+    ));
+}
+
+pub(crate) async fn save_booking_ignores_cancelled_bookings_and_expired_holds<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(4)).await;
+
+    let cancelled = Booking {
+        status: BookingStatus::Cancelled,
+        cancellation: Some(Cancellation {
+            reason: "weather".to_string(),
+            cancelled_at: now(),
+            refund: Money(0),
+        }),
+        ..booking(&customer, &trip, 2)
+    };
+    let expired = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(now() - Duration::minutes(1)),
+        ..booking(&customer, &trip, 2)
+    };
+    let held = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(now() + Duration::minutes(15)),
+        ..booking(&customer, &trip, 2)
+    };
+    for booking in [&cancelled, &expired, &held] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+
+    repo.save_booking(&booking(&customer, &trip, 2), OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    let rejected = repo
+        .save_booking(&booking(&customer, &trip, 1), OverbookingPolicy::Reject)
+        .await;
+    assert!(matches!(
+        rejected,
+        Err(BookingError::TripFull {
+            capacity: 4,
+            available: 0,
+            ..
+        })
+    ));
+}
+
+pub(crate) async fn save_booking_rejects_participants_already_on_the_trip<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let other_trip = seeded_trip(&repo, None).await;
+
+    let checked_in = Booking {
+        status: BookingStatus::CheckedIn,
+        checked_in_at: Some(now()),
+        ..booking(&customer, &trip, 1)
+    };
+    let cancelled = Booking {
+        status: BookingStatus::Cancelled,
+        cancellation: Some(Cancellation {
+            reason: "weather".to_string(),
+            cancelled_at: now(),
+            refund: Money(0),
+        }),
+        ..booking(&customer, &trip, 1)
+    };
+    let expired = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(now() - Duration::minutes(1)),
+        ..booking(&customer, &trip, 1)
+    };
+    for booking in [&checked_in, &cancelled, &expired] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+
+    // The same person, entered again with different casing.
+    let again = |booking: &Booking| Participant {
+        id: ParticipantId(Uuid::now_v7()),
+        name: booking.participants[0].name.to_uppercase(),
+        ..booking.participants[0].clone()
+    };
+
+    let duplicate = Booking {
+        participants: vec![again(&checked_in)],
+        ..booking(&customer, &trip, 0)
+    };
+    let rejected = repo
+        .save_booking(&duplicate, OverbookingPolicy::Reject)
+        .await;
+    assert!(
+        matches!(rejected, Err(BookingError::AlreadyBooked { booking, .. }) if booking == checked_in.id)
+    );
+
+    let elsewhere = Booking {
+        trip: other_trip.id.clone(),
+        ..duplicate.clone()
+    };
+    repo.save_booking(&elsewhere, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    let rebooked = Booking {
+        participants: vec![again(&cancelled), again(&expired)],
+        ..booking(&customer, &trip, 0)
+    };
+    repo.save_booking(&rebooked, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    // A closed booking is saved even though its participant is now booked again.
+    repo.save_booking(&cancelled, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+}
+
+pub(crate) async fn save_booking_reserves_seats_offered_to_the_waitlist<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(2)).await;
+    let entry = waitlist_entry(&customer, &trip, 2, now());
+    repo.save_waitlist_entry(&entry).await.unwrap();
+    let offers = repo
+        .offer_waitlist_seats(trip.id.clone(), now(), now() + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(offers.len(), 1);
+
+    let rejected = repo
+        .save_booking(&booking(&customer, &trip, 1), OverbookingPolicy::Reject)
+        .await;
+    assert!(matches!(
+        rejected,
+        Err(BookingError::TripFull {
+            capacity: 2,
+            available: 0,
+            ..
+        })
+    ));
+
+    let accepted = Booking {
+        waitlist_entry: Some(entry.id.clone()),
+        ..booking(&customer, &trip, 2)
+    };
+    repo.save_booking(&accepted, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    assert_eq!(found(&repo, &accepted.id).await, accepted);
+
+    let entry = repo.find_waitlist_entry(entry.id).await.unwrap().unwrap();
+    assert_eq!(entry.status, WaitlistStatus::Accepted);
+}
+
+pub(crate) async fn concurrent_bookings_cannot_overbook_a_trip<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(4)).await;
+
+    let attempts = (0..4)
+        .map(|_| {
+            let repo = repo.clone();
+            let booking = booking(&customer, &trip, 3);
+            tokio::spawn(
+                async move { repo.save_booking(&booking, OverbookingPolicy::Reject).await },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut saved = 0;
+    for attempt in attempts {
+        match attempt.await.unwrap() {
+            Ok(_) => saved += 1,
+            Err(BookingError::TripFull { .. }) => {}
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+    assert_eq!(saved, 1);
+
+    let filters = BookingFilters {
+        trip: Some(trip.id.clone()),
+        ..Default::default()
+    };
+    assert_eq!(repo.find_bookings(&filters).await.unwrap().len(), 1);
+}
+
+pub(crate) async fn reschedule_booking_checks_seats_and_rentals<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let wetsuit = seeded_equipment(&repo, 4).await;
+    let kind = trip_kind(Some(4));
+    let location = LocationId(Uuid::now_v7());
+    let start_time = now() + Duration::days(1);
+    let original = trip(&kind, &location, start_time);
+    let overlapping = trip(&kind, &location, start_time + Duration::days(1));
+    let busy = trip(
+        &kind,
+        &location,
+        start_time + Duration::days(1) + Duration::hours(1),
+    );
+    let full = trip(&kind, &location, start_time + Duration::days(2));
+    let free = trip(&kind, &location, start_time + Duration::days(3));
+    for trip in [&original, &overlapping, &busy, &full, &free] {
+        repo.insert_trip(trip).await;
+    }
+
+    let booking = booking(&customer, &original, 2);
+    let rental = self::booking(&customer, &busy, 1);
+    for booking in [&booking, &rental, &self::booking(&customer, &full, 3)] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+    for (booking, quantity) in [(&booking, 3), (&rental, 2)] {
+        repo.save_booking_rentals(&BookingRentals {
+            booking_id: booking.id.clone(),
+            rentals: HashMap::from([(wetsuit.id.clone(), quantity)]),
+        })
+        .await
+        .unwrap();
+    }
+
+    let to_overlapping = Booking {
+        trip: overlapping.id.clone(),
+        ..booking.clone()
+    };
+    let rejected = repo
+        .reschedule_booking(&to_overlapping, OverbookingPolicy::Reject)
+        .await;
+    assert!(matches!(
+        rejected,
+        Err(BookingError::InsufficientInventory {
+            requested: 3,
+            available: 2,
+            ..
+        })
+    ));
+
+    let to_full = Booking {
+        trip: full.id.clone(),
+        ..booking.clone()
+    };
+    let rejected = repo
+        .reschedule_booking(&to_full, OverbookingPolicy::Reject)
+        .await;
+    assert!(matches!(
+        rejected,
+        Err(BookingError::TripFull {
+            capacity: 4,
+            available: 1,
+            ..
+        })
+    ));
+    assert_eq!(found(&repo, &booking.id).await, booking);
+
+    let to_free = Booking {
+        trip: free.id.clone(),
+        ..booking.clone()
+    };
+    repo.reschedule_booking(&to_free, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    assert_eq!(found(&repo, &booking.id).await, to_free);
+    let rentals = repo.find_booking_rentals(booking.id).await.unwrap();
+    assert_eq!(rentals.rentals, HashMap::from([(wetsuit.id, 3)]));
+}
+
+pub(crate) async fn cancel_expired_holds_cancels_only_lapsed_holds<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let at = now();
+
+    let lapsed = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(at - Duration::minutes(1)),
+        ..booking(&customer, &trip, 1)
+    };
+    let lapsing = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(at),
+        ..booking(&customer, &trip, 1)
+    };
+    let held = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(at + Duration::minutes(1)),
+        ..booking(&customer, &trip, 1)
+    };
+    let confirmed = booking(&customer, &trip, 1);
+    for booking in [&lapsed, &lapsing, &held, &confirmed] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+
+    let mut cancelled = repo.cancel_expired_holds(at).await.unwrap();
+    cancelled.sort();
+    assert_eq!(cancelled, vec![lapsed.id.clone(), lapsing.id.clone()]);
+
+    for booking in [&lapsed, &lapsing] {
+        let expected = Booking {
+            status: BookingStatus::Cancelled,
+            hold_expires_at: None,
+            cancellation: Some(Cancellation {
+                reason: HOLD_EXPIRED_REASON.to_string(),
+                cancelled_at: at,
+                refund: Money(0),
+            }),
+            ..booking.clone()
+        };
+        assert_eq!(found(&repo, &booking.id).await, expected);
+    }
+    for booking in [&held, &confirmed] {
+        assert_eq!(&found(&repo, &booking.id).await, booking);
+    }
+
+    assert!(repo.cancel_expired_holds(at).await.unwrap().is_empty());
+}
+
+pub(crate) async fn save_booking_status_requires_the_status_it_was_read_with<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let other_trip = seeded_trip(&repo, None).await;
+    let confirmed = booking(&customer, &trip, 1);
+    repo.save_booking(&confirmed, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    let checked_in = Booking {
+        status: BookingStatus::CheckedIn,
+        checked_in_at: Some(now()),
+        ..confirmed.clone()
+    };
+    repo.save_booking_status(&checked_in, BookingStatus::Confirmed)
+        .await
+        .unwrap();
+    assert_eq!(found(&repo, &confirmed.id).await, checked_in);
+
+    // A cancellation that read the booking before it was checked in.
+    let cancelled = Booking {
+        status: BookingStatus::Cancelled,
+        cancellation: Some(Cancellation {
+            reason: "weather".to_string(),
+            cancelled_at: now(),
+            refund: Money(0),
+        }),
+        ..confirmed.clone()
+    };
+    let result = repo
+        .save_booking_status(&cancelled, BookingStatus::Confirmed)
+        .await;
+    assert!(matches!(
+        result,
+        Err(BookingError::StatusChanged {
+            from: BookingStatus::Confirmed,
+            ..
+        })
+    ));
+    assert_eq!(found(&repo, &confirmed.id).await, checked_in);
+
+    // A reschedule that read the booking before it was checked in.
+    let moved = Booking {
+        trip: other_trip.id.clone(),
+        ..confirmed.clone()
+    };
+    let result = repo
+        .reschedule_booking(&moved, OverbookingPolicy::Reject)
+        .await;
+    assert!(matches!(result, Err(BookingError::StatusChanged { .. })));
+    assert_eq!(found(&repo, &confirmed.id).await, checked_in);
+}
+
+pub(crate) async fn confirm_hold_confirms_only_live_holds<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let at = now();
+
+    let held = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(at + Duration::minutes(1)),
+        ..booking(&customer, &trip, 1)
+    };
+    let lapsed = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(at - Duration::minutes(1)),
+        ..booking(&customer, &trip, 1)
+    };
+    let swept = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(at - Duration::minutes(1)),
+        ..booking(&customer, &trip, 1)
+    };
+    let confirmed = booking(&customer, &trip, 1);
+    for booking in [&held, &lapsed, &swept, &confirmed] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+
+    repo.confirm_hold(held.id.clone(), at).await.unwrap();
+    let expected = Booking {
+        status: BookingStatus::Confirmed,
+        hold_expires_at: None,
+        ..held.clone()
+    };
+    assert_eq!(found(&repo, &held.id).await, expected);
+
+    assert!(matches!(
+        repo.confirm_hold(lapsed.id.clone(), at).await,
+        Err(BookingError::HoldExpired(id)) if id == lapsed.id
+    ));
+    assert_eq!(found(&repo, &lapsed.id).await, lapsed);
+
+    // The sweeper got to the hold between it being read and confirmed.
+    let swept = Booking {
+        status: BookingStatus::Cancelled,
+        hold_expires_at: None,
+        cancellation: Some(Cancellation {
+            reason: HOLD_EXPIRED_REASON.to_string(),
+            cancelled_at: at,
+            refund: Money(0),
+        }),
+        ..swept
+    };
+    repo.cancel_expired_holds(at).await.unwrap();
+    for booking in [&swept, &confirmed] {
+        assert!(matches!(
+            repo.confirm_hold(booking.id.clone(), at).await,
+            Err(BookingError::HoldExpired(id)) if id == booking.id
+        ));
+        assert_eq!(&found(&repo, &booking.id).await, booking);
+    }
+}
+
+pub(crate) async fn delete_booking_removes_rentals_but_keeps_participants<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let equipment = seeded_equipment(&repo, 4).await;
+    let booking = booking(&customer, &trip, 2);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    repo.save_booking_rentals(&BookingRentals {
+        booking_id: booking.id.clone(),
+        rentals: HashMap::from([(equipment.id.clone(), 2)]),
+    })
+    .await
+    .unwrap();
+
+    repo.delete_booking(booking.id.clone()).await.unwrap();
+
+    assert_eq!(repo.find_booking(booking.id.clone()).await.unwrap(), None);
+    let rentals = repo.find_booking_rentals(booking.id).await.unwrap();
+    assert!(rentals.rentals.is_empty());
+    let participant = booking.participants[0].id.clone();
+    assert_eq!(
+        repo.find_participant(participant).await.unwrap(),
+        Some(booking.participants[0].clone())
+    );
+}
+
+pub(crate) async fn save_waitlist_entry_saves_and_replaces_entries<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(2)).await;
+    let other_trip = seeded_trip(&repo, Some(2)).await;
+    let created_at = now();
+
+    let second = waitlist_entry(&customer, &trip, 2, created_at + Duration::seconds(1));
+    let mut first = waitlist_entry(&customer, &trip, 1, created_at);
+    let other = waitlist_entry(&customer, &other_trip, 1, created_at);
+    for entry in [&second, &first, &other] {
+        repo.save_waitlist_entry(entry).await.unwrap();
+    }
+    assert_eq!(
+        repo.find_waitlist(trip.id.clone()).await.unwrap(),
+        vec![first.clone(), second.clone()]
+    );
+
+    first.party_size = 3;
+    first.status = WaitlistStatus::Offered {
+        expires_at: now() + Duration::hours(1),
+    };
+    repo.save_waitlist_entry(&first).await.unwrap();
+    assert_eq!(
+        repo.find_waitlist_entry(first.id.clone()).await.unwrap(),
+        Some(first.clone())
+    );
+
+    first.status = WaitlistStatus::Withdrawn;
+    repo.save_waitlist_entry(&first).await.unwrap();
+    assert_eq!(
+        repo.find_waitlist_entry(first.id.clone()).await.unwrap(),
+        Some(first)
+    );
+
+    let missing = WaitlistEntryId(Uuid::now_v7());
+    assert_eq!(repo.find_waitlist_entry(missing).await.unwrap(), None);
+}
+
+pub(crate) async fn find_waitlisted_trips_lists_upcoming_trips_with_seats_to_offer<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let kind = trip_kind(Some(2));
+    let location = LocationId(Uuid::now_v7());
+    let at = now();
+    let waiting = trip(&kind, &location, at + Duration::days(1));
+    let lapsed = trip(&kind, &location, at + Duration::days(2));
+    let offered = trip(&kind, &location, at + Duration::days(3));
+    let started = trip(&kind, &location, at - Duration::hours(1));
+    let unlisted = trip(&kind, &location, at + Duration::days(4));
+    for trip in [&waiting, &lapsed, &offered, &started, &unlisted] {
+        repo.insert_trip(trip).await;
+    }
+
+    let offer = |trip: &Trip, expires_at| WaitlistEntry {
+        status: WaitlistStatus::Offered { expires_at },
+        ..waitlist_entry(&customer, trip, 1, at)
+    };
+    for entry in [
+        waitlist_entry(&customer, &waiting, 1, at),
+        offer(&lapsed, at - Duration::minutes(1)),
+        offer(&offered, at + Duration::minutes(1)),
+        waitlist_entry(&customer, &started, 1, at),
+        WaitlistEntry {
+            status: WaitlistStatus::Withdrawn,
+            ..waitlist_entry(&customer, &unlisted, 1, at)
+        },
+    ] {
+        repo.save_waitlist_entry(&entry).await.unwrap();
+    }
+
+    let mut trips = repo.find_waitlisted_trips(at).await.unwrap();
+    trips.sort();
+    assert_eq!(trips, vec![waiting.id, lapsed.id]);
+}
+
+pub(crate) async fn offer_waitlist_seats_offers_in_order_until_a_party_does_not_fit<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(4)).await;
+    repo.save_booking(&booking(&customer, &trip, 1), OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    let created_at = now();
+    let first = waitlist_entry(&customer, &trip, 2, created_at);
+    let second = waitlist_entry(&customer, &trip, 2, created_at + Duration::seconds(1));
+    let third = waitlist_entry(&customer, &trip, 1, created_at + Duration::seconds(2));
+    for entry in [&third, &first, &second] {
+        repo.save_waitlist_entry(entry).await.unwrap();
+    }
+
+    let at = now();
+    let expires_at = at + Duration::hours(1);
+    let offers = repo
+        .offer_waitlist_seats(trip.id.clone(), at, expires_at)
+        .await
+        .unwrap();
+    let first = WaitlistEntry {
+        status: WaitlistStatus::Offered { expires_at },
+        ..first
+    };
+    assert_eq!(offers, vec![first.clone()]);
+    assert_eq!(
+        repo.find_waitlist(trip.id.clone()).await.unwrap(),
+        vec![first.clone(), second.clone(), third.clone()]
+    );
+
+    let offers = repo
+        .offer_waitlist_seats(trip.id.clone(), at, expires_at)
+        .await
+        .unwrap();
+    assert!(offers.is_empty());
+
+    let at = expires_at;
+    let expires_at = at + Duration::hours(1);
+    let offers = repo
+        .offer_waitlist_seats(trip.id.clone(), at, expires_at)
+        .await
+        .unwrap();
+    let offered = |entry: WaitlistEntry| WaitlistEntry {
+        status: WaitlistStatus::Offered { expires_at },
+        ..entry
+    };
+    assert_eq!(
+        offers,
+        vec![offered(second.clone()), offered(third.clone())]
+    );
+    assert_eq!(
+        repo.find_waitlist(trip.id.clone()).await.unwrap(),
+        vec![
+            WaitlistEntry {
+                status: WaitlistStatus::Expired,
+                ..first
+            },
+            offered(second),
+            offered(third),
+        ]
+    );
+}
+
+pub(crate) async fn offer_waitlist_seats_rejects_missing_trips<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let missing = TripId(Uuid::now_v7());
+    let result = repo
+        .offer_waitlist_seats(missing.clone(), now(), now() + Duration::hours(1))
+        .await;
+
+    assert!(matches!(result, Err(WaitlistError::TripNotFound(trip)) if trip == missing));
+}
+
+pub(crate) async fn save_customer_saves_and_replaces_customers<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let mut customer = customer("Ana Reyes", "ana@example.com", "+14155550100");
+    repo.save_customer(&customer).await.unwrap();
+    assert_eq!(
+        repo.find_customer(customer.id.clone()).await.unwrap(),
+        Some(customer.clone())
+    );
+
+    customer.name = CustomerName("Ana Reyes-Ortiz".to_string());
+    customer.email = EmailAddress("Ana@example.com".to_string());
+    customer.phone = PhoneNumber("+14155550199".to_string());
+    repo.save_customer(&customer).await.unwrap();
+    assert_eq!(
+        repo.find_customer(customer.id.clone()).await.unwrap(),
+        Some(customer.clone())
+    );
+
+    repo.delete_customer(customer.id.clone()).await.unwrap();
+    assert_eq!(repo.find_customer(customer.id).await.unwrap(), None);
+}
+
+pub(crate) async fn find_customers_pages_through_customers_in_id_order<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let mut customers = vec![];
+    for i in 0..3 {
+        let customer = customer(
+            &format!("Customer {i}"),
+            &format!("customer{i}@example.com"),
+            &format!("+1415555010{i}"),
+        );
+        repo.save_customer(&customer).await.unwrap();
+        customers.push(customer);
+    }
+
+    let first = Page {
+        limit: 2,
+        offset: 0,
+    };
+    let second = Page {
+        limit: 2,
+        offset: 2,
+    };
+    assert_eq!(repo.find_customers(first).await.unwrap(), customers[..2]);
+    assert_eq!(repo.find_customers(second).await.unwrap(), customers[2..]);
+}
+
+pub(crate) async fn save_customer_rejects_taken_emails_ignoring_case<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = customer("Ana Reyes", "ana@example.com", "+14155550100");
+    repo.save_customer(&customer).await.unwrap();
+
+    let other = self::customer("Bob Smith", "ANA@example.com", "+14155550101");
+    let result = repo.save_customer(&other).await;
+    assert!(matches!(result, Err(CustomerError::EmailTaken(email)) if email == "ANA@example.com"));
+    assert_eq!(repo.find_customer(other.id).await.unwrap(), None);
+}
+
+pub(crate) async fn search_customers_matches_names_emails_and_phones<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let ana = customer("Ana Reyes", "ana@example.com", "+14155550100");
+    let maria = customer("Maria Reyes", "maria@example.com", "+14155550101");
+    let bob = customer("Bob Smith", "bob.smith@example.org", "+442071838750");
+    for customer in [&ana, &maria, &bob] {
+        repo.save_customer(customer).await.unwrap();
+    }
+    let search = |query: &str, phone_digits: Option<&str>, limit| CustomerSearch {
+        query: query.to_string(),
+        phone_digits: phone_digits.map(String::from),
+        page: Page { limit, offset: 0 },
+    };
+
+    let results = repo
+        .search_customers(&search("reyes", None, 20))
+        .await
+        .unwrap();
+    assert_eq!(results.total, 2);
+    let mut ids = results
+        .customers
+        .into_iter()
+        .map(|c| c.id)
+        .collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, vec![ana.id.clone(), maria.id.clone()]);
+
+    let results = repo
+        .search_customers(&search("reyes", None, 1))
+        .await
+        .unwrap();
+    assert_eq!(results.total, 2);
+    assert_eq!(results.customers.len(), 1);
+
+    let results = repo
+        .search_customers(&search("bob.smith", None, 20))
+        .await
+        .unwrap();
+    assert_eq!(results.customers, vec![bob.clone()]);
+
+    let results = repo
+        .search_customers(&search("2071838750", Some("2071838750"), 20))
+        .await
+        .unwrap();
+    assert_eq!(results.customers, vec![bob]);
+
+    let results = repo
+        .search_customers(&search("zhang", None, 20))
+        .await
+        .unwrap();
+    assert_eq!(results.total, 0);
+    assert!(results.customers.is_empty());
+}
+
+pub(crate) async fn find_duplicate_customers_pairs_similar_customers<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let ana = customer("Ana Reyes", "ana@example.com", "+14155550100");
+    let anna = customer("Anna Reyes", "anna.r@example.org", "+14155550100");
+    let bob = customer("Bob Smith", "bob@example.com", "+442071838750");
+    let robert = customer("Robert Jones", "bob@example.org", "+442071838751");
+    let bobby = customer("Bob Smyth", "Bob@example.net", "+442071838752");
+    for customer in [&ana, &anna, &bob, &robert, &bobby] {
+        repo.save_customer(customer).await.unwrap();
+    }
+
+    let candidates = repo
+        .find_duplicate_customers(Page {
+            limit: 20,
+            offset: 0,
+        })
+        .await
+        .unwrap();
+
+    // Robert only shares the user part of Bob's email address, which isn't enough by itself.
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].first, ana);
+    assert_eq!(candidates[0].second, anna);
+    assert!(candidates[0].reasons.contains(&DuplicateReason::SamePhone));
+    assert!(candidates[0]
+        .reasons
+        .contains(&DuplicateReason::SimilarName));
+    assert_eq!(candidates[1].first, bob);
+    assert_eq!(candidates[1].second, bobby);
+    assert_eq!(
+        candidates[1].reasons,
+        vec![DuplicateReason::SimilarName, DuplicateReason::SameEmailUser]
+    );
+}
+
+pub(crate) async fn merge_customers_moves_bookings_and_waitlist_entries<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let survivor = customer("Ana Reyes", "ana@example.com", "+14155550100");
+    let merged = customer("Ana R.", "ana.reyes@example.org", "+14155550101");
+    let bystander = customer("Bob Smith", "bob@example.com", "+14155550102");
+    for customer in [&survivor, &merged, &bystander] {
+        repo.save_customer(customer).await.unwrap();
+    }
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let bookings = [
+        booking(&merged, &trip, 1),
+        booking(&merged, &trip, 1),
+        booking(&bystander, &trip, 1),
+    ];
+    for booking in &bookings {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+    let entry = waitlist_entry(&merged, &trip, 1, now());
+    repo.save_waitlist_entry(&entry).await.unwrap();
+
+    // An edit made after the merge was requested is kept.
+    let survivor = Customer {
+        phone: PhoneNumber("+14155550109".to_string()),
+        ..survivor
+    };
+    repo.save_customer(&survivor).await.unwrap();
+
+    let request = MergeCustomersRequest {
+        survivor: survivor.id.clone(),
+        merged: merged.id.clone(),
+        use_merged_name: false,
+        use_merged_email: true,
+        use_merged_phone: false,
+    };
+    let merge = repo.merge_customers(&request).await.unwrap();
+    let edited = Customer {
+        email: merged.email.clone(),
+        ..survivor.clone()
+    };
+
+    assert_eq!(merge.survivor, survivor.id);
+    assert_eq!(merge.survivor_before, Some(survivor.clone()));
+    assert_eq!(merge.merged, merged);
+    assert_eq!(
+        merge.bookings,
+        vec![bookings[0].id.clone(), bookings[1].id.clone()]
+    );
+    assert_eq!(merge.waitlist_entries, vec![entry.id.clone()]);
+
+    assert_eq!(repo.find_customer(merged.id.clone()).await.unwrap(), None);
+    assert_eq!(
+        repo.find_customer(survivor.id.clone()).await.unwrap(),
+        Some(edited)
+    );
+    let filters = BookingFilters {
+        customer: Some(survivor.id.clone()),
+        ..Default::default()
+    };
+    let moved = repo.find_bookings(&filters).await.unwrap();
+    let expected = bookings[..2]
+        .iter()
+        .map(|b| Booking {
+            customer: survivor.id.clone(),
+            ..b.clone()
+        })
+        .collect();
+    assert_eq!(sorted(moved), sorted(expected));
+    let entry = repo.find_waitlist_entry(entry.id).await.unwrap().unwrap();
+    assert_eq!(entry.customer, survivor.id);
+    assert_eq!(found(&repo, &bookings[2].id).await, bookings[2]);
+
+    for id in [survivor.id, merged.id] {
+        assert_eq!(
+            repo.find_customer_merges(id).await.unwrap(),
+            vec![merge.clone()]
+        );
+    }
+    assert!(repo
+        .find_customer_merges(bystander.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+pub(crate) async fn merge_customers_rejects_missing_customers<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let saved = customer("Ana Reyes", "ana@example.com", "+14155550100");
+    let missing = customer("Bob Smith", "bob@example.com", "+14155550101");
+    repo.save_customer(&saved).await.unwrap();
+
+    let request = |survivor: &Customer, merged: &Customer| MergeCustomersRequest {
+        survivor: survivor.id.clone(),
+        merged: merged.id.clone(),
+        use_merged_name: true,
+        use_merged_email: true,
+        use_merged_phone: true,
+    };
+
+    let result = repo.merge_customers(&request(&missing, &saved)).await;
+    assert!(matches!(result, Err(CustomerError::NotFound(id)) if id == missing.id));
+
+    let result = repo.merge_customers(&request(&saved, &missing)).await;
+    assert!(matches!(result, Err(CustomerError::NotFound(id)) if id == missing.id));
+
+    assert_eq!(
+        repo.find_customer(saved.id.clone()).await.unwrap(),
+        Some(saved.clone())
+    );
+    assert!(repo
+        .find_customer_merges(saved.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+pub(crate) async fn find_trips_returns_nothing_without_filters<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    seeded_trip(&repo, None).await;
+
+    let unfiltered = repo.find_trips(&Default::default()).await.unwrap();
+    assert!(unfiltered.is_empty());
+}
+
+pub(crate) async fn find_trips_applies_every_filter<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let kind = trip_kind(Some(8));
+    let other_kind = TripKind {
+        max_participants: None,
+        eligibility: Default::default(),
+        cancellation_policy: Default::default(),
+        ..trip_kind(None)
+    };
+    let harbour = LocationId(Uuid::now_v7());
+    let beach = LocationId(Uuid::now_v7());
+    let day = now() + Duration::days(1);
+    let first = Trip {
+        max_participants: Some(4),
+        ..trip(&kind, &harbour, day)
+    };
+    let second = trip(&other_kind, &beach, day + Duration::days(1));
+    let third = trip(&kind, &beach, day + Duration::days(2));
+    for trip in [&first, &second, &third] {
+        repo.insert_trip(trip).await;
+    }
+
+    assert_eq!(
+        repo.find_trip(first.id.clone()).await.unwrap(),
+        Some(first.clone())
+    );
+    assert_eq!(
+        repo.find_trip(second.id.clone()).await.unwrap(),
+        Some(second.clone())
+    );
+    assert_eq!(repo.find_trip(TripId(Uuid::now_v7())).await.unwrap(), None);
+
+    let cases = [
+        (TripFilters::default(), vec![]),
+        (
+            TripFilters {
+                kind: Some(kind.id.clone()),
+                ..Default::default()
+            },
+            vec![&first, &third],
+        ),
+        (
+            TripFilters {
+                location: Some(beach.clone()),
+                ..Default::default()
+            },
+            vec![&second, &third],
+        ),
+        (
+            TripFilters {
+                date_range: Some((first.start_time, second.start_time)),
+                ..Default::default()
+            },
+            vec![&first, &second],
+        ),
+        (
+            TripFilters {
+                kind: Some(kind.id.clone()),
+                location: Some(beach),
+                date_range: Some((first.start_time, second.start_time)),
+            },
+            vec![],
+        ),
+    ];
+    for (filters, expected) in cases {
+        let mut found = repo.find_trips(&filters).await.unwrap();
+        found.sort_by(|a, b| a.id.cmp(&b.id));
+        let expected = expected.into_iter().cloned().collect::<Vec<_>>();
+        assert_eq!(found, expected, "{filters:?}");
+    }
+}
+
+pub(crate) async fn save_occurrences_materializes_each_occurrence_once<R>(repo: R)
+where
+    R: UnitOfWork + Catalog + ScheduleRepository,
+{
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let schedule = schedule(&trip);
+    repo.save_schedule(&schedule).await.unwrap();
+    let occurrences = upcoming_occurrences(&schedule);
+    assert_eq!(occurrences.len(), 3);
+
+    assert_eq!(
+        repo.save_occurrences(&schedule, &occurrences)
+            .await
+            .unwrap(),
+        3
+    );
+    let trips = scheduled_trips(&repo, &trip).await;
+    assert_eq!(
+        trips
+            .iter()
+            .map(|t| (t.location.clone(), t.start_time, t.end_time))
+            .collect::<Vec<_>>(),
+        occurrences
+            .iter()
+            .map(|o| (trip.location.clone(), o.start_time, o.end_time))
+            .collect::<Vec<_>>()
+    );
+
+    // Materializing again updates the same trips rather than adding new ones.
+    repo.save_occurrences(&schedule, &occurrences)
+        .await
+        .unwrap();
+    assert_eq!(scheduled_trips(&repo, &trip).await, trips);
+
+    // Cancelled occurrences aren't brought back.
+    repo.cancel_occurrence(&schedule.id, occurrences[1].scheduled_start)
+        .await
+        .unwrap();
+    repo.save_occurrences(&schedule, &occurrences)
+        .await
+        .unwrap();
+    assert_eq!(
+        scheduled_trips(&repo, &trip).await,
+        vec![trips[0].clone(), trips[2].clone()]
+    );
+}
+
+pub(crate) async fn save_occurrences_leaves_detached_and_booked_trips_in_place<R>(repo: R)
+where
+    R: UnitOfWork + Catalog + ScheduleRepository,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let elsewhere = seeded_trip(&repo, Some(8)).await.location;
+    let mut schedule = schedule(&trip);
+    repo.save_schedule(&schedule).await.unwrap();
+    let occurrences = upcoming_occurrences(&schedule);
+    repo.save_occurrences(&schedule, &occurrences)
+        .await
+        .unwrap();
+    let trips = scheduled_trips(&repo, &trip).await;
+
+    let moved = Occurrence {
+        start_time: occurrences[0].start_time + Duration::hours(1),
+        end_time: occurrences[0].end_time + Duration::hours(1),
+        ..occurrences[0].clone()
+    };
+    let detached = repo
+        .save_detached_occurrence(&schedule, &moved)
+        .await
+        .unwrap();
+    assert_eq!(detached, trips[0].id);
+    repo.save_booking(&booking(&customer, &trips[1], 2), OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    schedule.location = elsewhere.clone();
+    schedule.recurrence.duration = Duration::hours(3);
+    repo.save_schedule(&schedule).await.unwrap();
+    let occurrences = upcoming_occurrences(&schedule);
+    repo.save_occurrences(&schedule, &occurrences)
+        .await
+        .unwrap();
+
+    let edited = Trip {
+        start_time: moved.start_time,
+        end_time: moved.end_time,
+        ..trips[0].clone()
+    };
+    let rescheduled = Trip {
+        location: elsewhere,
+        end_time: trips[2].start_time + Duration::hours(3),
+        ..trips[2].clone()
+    };
+    assert_eq!(
+        scheduled_trips(&repo, &trip).await,
+        vec![edited.clone(), trips[1].clone(), rescheduled]
+    );
+
+    // Pruning every occurrence keeps the detached trip, and the booked one is reported.
+    let booked = repo
+        .prune_occurrences(&schedule.id, now(), &[])
+        .await
+        .unwrap();
+    assert_eq!(booked, vec![trips[1].id.clone()]);
+    assert_eq!(
+        scheduled_trips(&repo, &trip).await,
+        vec![edited, trips[1].clone()]
+    );
+}
+
+pub(crate) async fn save_booking_rentals_replaces_and_sums_rentals<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let wetsuit = seeded_equipment(&repo, 4).await;
+    let helmet = seeded_equipment(&repo, 4).await;
+    let booking = booking(&customer, &trip, 2);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    let empty = repo.find_booking_rentals(booking.id.clone()).await.unwrap();
+    assert!(empty.rentals.is_empty());
+
+    let rentals = BookingRentals {
+        booking_id: booking.id.clone(),
+        rentals: HashMap::from([(wetsuit.id.clone(), 2), (helmet.id.clone(), 1)]),
+    };
+    repo.save_booking_rentals(&rentals).await.unwrap();
+    assert_eq!(
+        repo.find_booking_rentals(booking.id.clone()).await.unwrap(),
+        rentals
+    );
+
+    let rentals = BookingRentals {
+        booking_id: booking.id.clone(),
+        rentals: HashMap::from([(wetsuit.id.clone(), 4)]),
+    };
+    repo.save_booking_rentals(&rentals).await.unwrap();
+    assert_eq!(
+        repo.find_booking_rentals(booking.id.clone()).await.unwrap(),
+        rentals
+    );
+}
+
+pub(crate) async fn save_booking_rentals_rejects_missing_bookings_and_equipment<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let wetsuit = seeded_equipment(&repo, 4).await;
+    let booking = booking(&customer, &trip, 1);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    let missing_booking = BookingId(Uuid::now_v7());
+    let result = repo
+        .save_booking_rentals(&BookingRentals {
+            booking_id: missing_booking.clone(),
+            rentals: HashMap::from([(wetsuit.id.clone(), 1)]),
+        })
+        .await;
+    assert!(matches!(result, Err(EquipmentError::BookingNotFound(id)) if id == missing_booking));
+
+    let missing_equipment = EquipmentId(Uuid::now_v7());
+    let result = repo
+        .save_booking_rentals(&BookingRentals {
+            booking_id: booking.id.clone(),
+            rentals: HashMap::from([(wetsuit.id, 1), (missing_equipment.clone(), 1)]),
+        })
+        .await;
+    assert!(matches!(result, Err(EquipmentError::NotFound(id)) if id == missing_equipment));
+
+    let rentals = repo.find_booking_rentals(booking.id).await.unwrap();
+    assert!(rentals.rentals.is_empty());
+}
+
+pub(crate) async fn save_booking_rentals_checks_inventory_of_overlapping_trips<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let wetsuit = seeded_equipment(&repo, 4).await;
+    let kind = trip_kind(None);
+    let location = LocationId(Uuid::now_v7());
+    let start_time = now() + Duration::days(1);
+    let morning = trip(&kind, &location, start_time);
+    let midday = trip(&kind, &location, start_time + Duration::hours(2));
+    let afternoon = trip(&kind, &location, start_time + Duration::hours(3));
+    for trip in [&morning, &midday, &afternoon] {
+        repo.insert_trip(trip).await;
+    }
+
+    let mut morning_booking = booking(&customer, &morning, 1);
+    let midday_booking = booking(&customer, &midday, 1);
+    let afternoon_booking = booking(&customer, &afternoon, 1);
+    for booking in [&morning_booking, &midday_booking, &afternoon_booking] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+    let rent = |booking: &Booking, quantity| BookingRentals {
+        booking_id: booking.id.clone(),
+        rentals: HashMap::from([(wetsuit.id.clone(), quantity)]),
+    };
+
+    repo.save_booking_rentals(&rent(&morning_booking, 3))
+        .await
+        .unwrap();
+    repo.save_booking_rentals(&rent(&morning_booking, 4))
+        .await
+        .unwrap();
+    let result = repo.save_booking_rentals(&rent(&midday_booking, 1)).await;
+    assert!(matches!(
+        result,
+        Err(EquipmentError::InsufficientInventory {
+            requested: 1,
+            available: 0,
+            ..
+        })
+    ));
+
+    repo.save_booking_rentals(&rent(&afternoon_booking, 4))
+        .await
+        .unwrap();
+
+    morning_booking.status = BookingStatus::Cancelled;
+    morning_booking.cancellation = Some(Cancellation {
+        reason: "weather".to_string(),
+        cancelled_at: now(),
+        refund: Money(0),
+    });
+    repo.save_booking(&morning_booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    let result = repo.save_booking_rentals(&rent(&midday_booking, 1)).await;
+    assert!(matches!(
+        result,
+        Err(EquipmentError::InsufficientInventory {
+            requested: 1,
+            available: 0,
+            ..
+        })
+    ));
+
+    repo.save_booking_rentals(&rent(&afternoon_booking, 2))
+        .await
+        .unwrap();
+    repo.save_booking_rentals(&rent(&midday_booking, 2))
+        .await
+        .unwrap();
+}
+
+pub(crate) async fn concurrent_rentals_cannot_exceed_inventory<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let wetsuit = seeded_equipment(&repo, 4).await;
+
+    let mut attempts = vec![];
+    for _ in 0..4 {
+        let booking = booking(&customer, &trip, 1);
+        repo.save_booking(&booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+
+        let repo = repo.clone();
+        let rentals = BookingRentals {
+            booking_id: booking.id,
+            rentals: HashMap::from([(wetsuit.id.clone(), 3)]),
+        };
+        attempts.push(tokio::spawn(async move {
+            repo.save_booking_rentals(&rentals).await
+        }));
+    }
+
+    let mut saved = 0;
+    for attempt in attempts {
+        match attempt.await.unwrap() {
+            Ok(()) => saved += 1,
+            Err(EquipmentError::InsufficientInventory { .. }) => {}
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+    assert_eq!(saved, 1);
+}
+
+pub(crate) async fn find_equipment_availability_subtracts_active_rentals<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let wetsuit = seeded_equipment(&repo, 4).await;
+    let helmet = seeded_equipment(&repo, 2).await;
+    let trip = seeded_trip(&repo, None).await;
+
+    let confirmed = booking(&customer, &trip, 1);
+    let held = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(now() + Duration::minutes(15)),
+        ..booking(&customer, &trip, 1)
+    };
+    let expired = Booking {
+        status: BookingStatus::Held,
+        hold_expires_at: Some(now() + Duration::minutes(15)),
+        ..booking(&customer, &trip, 1)
+    };
+    for (booking, wetsuits) in [(&confirmed, 1), (&held, 2), (&expired, 1)] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+        repo.save_booking_rentals(&BookingRentals {
+            booking_id: booking.id.clone(),
+            rentals: HashMap::from([(wetsuit.id.clone(), wetsuits)]),
+        })
+        .await
+        .unwrap();
+    }
+    let expired = Booking {
+        hold_expires_at: Some(now() - Duration::minutes(1)),
+        ..expired
+    };
+    repo.save_booking(&expired, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+
+    let availability = repo
+        .find_equipment_availability(trip.start_time, trip.end_time)
+        .await
+        .unwrap();
+    assert_eq!(availability.start_time, trip.start_time);
+    assert_eq!(availability.end_time, trip.end_time);
+    assert_eq!(
+        availability.available,
+        HashMap::from([(wetsuit.id.clone(), 1), (helmet.id.clone(), 2)])
+    );
+
+    let availability = repo
+        .find_equipment_availability(trip.end_time, trip.end_time + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(
+        availability.available,
+        HashMap::from([(wetsuit.id, 4), (helmet.id, 2)])
+    );
+}
+
+pub(crate) async fn find_current_waiver_picks_the_version_in_force<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let id = WaiverId(Uuid::now_v7());
+    let at = now();
+    let versions = [
+        (1, at - Duration::days(30)),
+        (2, at - Duration::days(1)),
+        (3, at + Duration::days(1)),
+    ]
+    .map(|(version, effective_at)| Waiver {
+        id: id.clone(),
+        version,
+        content: format!("Version {version} of the waiver"),
+        effective_at,
+    });
+    for waiver in &versions {
+        repo.save_waiver(waiver).await.unwrap();
+    }
+
+    assert_eq!(
+        repo.find_current_waiver(id.clone(), at).await.unwrap(),
+        Some(versions[1].clone())
+    );
+    assert_eq!(
+        repo.find_current_waiver(id.clone(), at - Duration::days(7))
+            .await
+            .unwrap(),
+        Some(versions[0].clone())
+    );
+    assert_eq!(
+        repo.find_current_waiver(id.clone(), at - Duration::days(60))
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        repo.find_latest_waiver(id).await.unwrap(),
+        Some(versions[2].clone())
+    );
+
+    let missing = WaiverId(Uuid::now_v7());
+    assert_eq!(repo.find_latest_waiver(missing).await.unwrap(), None);
+}
+
+pub(crate) async fn save_waiver_signature_sets_the_participant_waiver<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let booking = booking(&customer, &trip, 2);
+    repo.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    let waiver = Waiver {
+        id: WaiverId(Uuid::now_v7()),
+        version: 1,
+        content: "I accept the risks of sea kayaking".to_string(),
+        effective_at: now() - Duration::days(1),
+    };
+    repo.save_waiver(&waiver).await.unwrap();
+
+    let [signed, unsigned] = [&booking.participants[0], &booking.participants[1]];
+    let signature = WaiverSignature {
+        id: WaiverSignatureId(Uuid::now_v7()),
+        participant: signed.id.clone(),
+        waiver: waiver.id.clone(),
+        version: waiver.version,
+        content_hash: Some("5d41402abc4b2a76b9719d911017c592".to_string()),
+        signed_at: now(),
+        signer: Some(Signer {
+            name: "Ana Reyes".to_string(),
+            ip: Some("203.0.113.7".parse().unwrap()),
+            user_agent: Some("Mozilla/5.0".to_string()),
+            guardian_relationship: Some("parent".to_string()),
+        }),
+        signature_image: Some(BlobKey("signatures/ana.png".to_string())),
+    };
+    repo.save_waiver_signature(&signature).await.unwrap();
+
+    let signed = Participant {
+        waiver: Some(waiver.id.clone()),
+        ..signed.clone()
+    };
+    assert_eq!(
+        repo.find_participant(signed.id.clone()).await.unwrap(),
+        Some(signed.clone())
+    );
+    assert_eq!(
+        found(&repo, &booking.id).await.participants,
+        vec![signed.clone(), unsigned.clone()]
+    );
+    assert_eq!(
+        repo.find_waiver_signatures(&[signed.id, unsigned.id.clone()])
+            .await
+            .unwrap(),
+        vec![signature]
+    );
+    assert!(repo
+        .find_waiver_signatures(std::slice::from_ref(&unsigned.id))
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(repo.find_waiver_signatures(&[]).await.unwrap().is_empty());
+}
+
+pub(crate) async fn find_price_list_picks_matching_prices_and_modifiers<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let trip = seeded_trip(&repo, None).await;
+    let other_trip = seeded_trip(&repo, None).await;
+    let kind = trip.kind.id.clone();
+    let wetsuit = seeded_equipment(&repo, 4).await;
+    let helmet = Equipment {
+        rental_price: None,
+        ..seeded_equipment(&repo, 4).await
+    };
+    repo.insert_equipment(&helmet).await;
+
+    for (kind, category, price) in [
+        (&kind, ParticipantCategory::Adult, 7500),
+        (&kind, ParticipantCategory::Child, 4500),
+        (&other_trip.kind.id, ParticipantCategory::Adult, 9900),
+    ] {
+        repo.insert_base_price(kind, category, Money(price)).await;
+    }
+
+    let modifier = |name: &str, trip_kind: Option<&TripKindId>| PriceModifier {
+        id: PriceModifierId(Uuid::now_v7()),
+        name: name.to_string(),
+        trip_kind: trip_kind.cloned(),
+        months: vec![6, 7, 8],
+        weekdays: vec![Weekday::Sat, Weekday::Sun],
+        percent: 20,
+    };
+    let weekend = modifier("Weekend", None);
+    let summer = PriceModifier {
+        weekdays: vec![],
+        percent: -10,
+        ..modifier("Summer", Some(&kind))
+    };
+    let other = modifier("Other", Some(&other_trip.kind.id));
+    for modifier in [&weekend, &summer, &other] {
+        repo.insert_price_modifier(modifier).await;
+    }
+
+    let prices = repo.find_price_list(kind).await.unwrap();
+    assert_eq!(
+        prices,
+        PriceList {
+            base_prices: HashMap::from([
+                (ParticipantCategory::Adult, Money(7500)),
+                (ParticipantCategory::Child, Money(4500)),
+            ]),
+            rentals: HashMap::from([(wetsuit.id, Money(1500))]),
+            modifiers: vec![summer, weekend],
+        }
+    );
+}
+
+pub(crate) async fn save_payment_builds_the_ledger<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let customer = seeded_customer(&repo).await;
+    let trip = seeded_trip(&repo, None).await;
+    let booking = booking(&customer, &trip, 1);
+    let other_booking = self::booking(&customer, &trip, 1);
+    for booking in [&booking, &other_booking] {
+        repo.save_booking(booking, OverbookingPolicy::Reject)
+            .await
+            .unwrap();
+    }
+
+    let created_at = now();
+    let payment = |kind, parent: Option<&Payment>, amount, status, offset| Payment {
+        id: PaymentId(Uuid::now_v7()),
+        booking: booking.id.clone(),
+        kind,
+        parent: parent.map(|p| p.id.clone()),
+        amount: Money(amount),
+        status,
+        reference: Some(ProviderReference(format!("ch_{offset}"))),
+        created_at: created_at + Duration::seconds(offset),
+    };
+    let authorization = payment(
+        PaymentKind::Authorization,
+        None,
+        7500,
+        PaymentStatus::Succeeded,
+        0,
+    );
+    let capture = payment(
+        PaymentKind::Capture,
+        Some(&authorization),
+        7500,
+        PaymentStatus::Succeeded,
+        1,
+    );
+    let refund = Payment {
+        reference: None,
+        ..payment(
+            PaymentKind::Refund,
+            Some(&capture),
+            2500,
+            PaymentStatus::Declined("insufficient funds".to_string()),
+            2,
+        )
+    };
+    for payment in [&authorization, &capture, &refund] {
+        repo.save_payment(payment).await.unwrap();
+    }
+
+    assert_eq!(
+        repo.find_payment(capture.id.clone()).await.unwrap(),
+        Some(capture.clone())
+    );
+    assert_eq!(
+        repo.find_payment(PaymentId(Uuid::now_v7())).await.unwrap(),
+        None
+    );
+    assert_eq!(
+        repo.find_payment_ledger(booking.id.clone()).await.unwrap(),
+        PaymentLedger {
+            booking: booking.id,
+            payments: vec![authorization, capture, refund],
+        }
+    );
+    assert!(repo
+        .find_payment_ledger(other_booking.id)
+        .await
+        .unwrap()
+        .payments
+        .is_empty());
+}
+
+pub(crate) async fn unit_of_work_commits_changes_across_repositories<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let equipment = seeded_equipment(&repo, 4).await;
+    let customer = customer("Ada Lovelace", "ada@example.com", "+15550100");
+    let booking = booking(&customer, &trip, 2);
+    let rentals = BookingRentals {
+        booking_id: booking.id.clone(),
+        rentals: HashMap::from([(equipment.id.clone(), 2)]),
+    };
+
+    let unit = repo.begin().await.unwrap();
+    assert!(unit.begin().await.is_err());
+    unit.save_customer(&customer).await.unwrap();
+    unit.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    unit.save_booking_rentals(&rentals).await.unwrap();
+    assert_eq!(found(&unit, &booking.id).await, booking);
+    unit.commit().await.unwrap();
+
+    assert_eq!(
+        repo.find_customer(customer.id.clone()).await.unwrap(),
+        Some(customer)
+    );
+    assert_eq!(found(&repo, &booking.id).await, booking);
+    assert_eq!(
+        repo.find_booking_rentals(booking.id).await.unwrap(),
+        rentals
+    );
+}
+
+pub(crate) async fn unit_of_work_discards_uncommitted_changes<R>(repo: R)
+where
+    R: UnitOfWork + Catalog,
+{
+    let trip = seeded_trip(&repo, Some(8)).await;
+    let customer = customer("Ada Lovelace", "ada@example.com", "+15550100");
+    let booking = booking(&customer, &trip, 2);
+
+    let unit = repo.begin().await.unwrap();
+    unit.save_customer(&customer).await.unwrap();
+    unit.save_booking(&booking, OverbookingPolicy::Reject)
+        .await
+        .unwrap();
+    drop(unit);
+
+    assert_eq!(repo.find_customer(customer.id).await.unwrap(), None);
+    assert_eq!(repo.find_booking(booking.id).await.unwrap(), None);
+
+    // Writes outside of a unit of work still go through once it has ended.
+    let other = seeded_customer(&repo).await;
+    assert!(repo.find_customer(other.id).await.unwrap().is_some());
+}
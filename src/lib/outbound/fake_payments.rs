@@ -0,0 +1,112 @@
+//! Module [fake_payments] is an in-process outbound adapter that pretends to be a payment
+//! provider, so the payment flow can be exercised without a real processor.
+//!
+//! Payment methods are matched against well-known test tokens:
+//!
+//! - [DECLINED_METHOD] is always declined.
+//! - [INSUFFICIENT_FUNDS_METHOD] is declined for amounts over [INSUFFICIENT_FUNDS_LIMIT].
+//! - Any other token is accepted.
+
+use crate::domain::booking::models::payment::{PaymentError, PaymentMethod, ProviderReference};
+use crate::domain::booking::models::pricing::Money;
+use crate::domain::booking::ports::PaymentGateway;
+use anyhow::anyhow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+pub const DECLINED_METHOD: &str = "tok_declined";
+pub const INSUFFICIENT_FUNDS_METHOD: &str = "tok_insufficient_funds";
+pub const INSUFFICIENT_FUNDS_LIMIT: Money = Money(10_000);
+
+/// [FakePaymentGateway] keeps authorizations and captures in memory and waits for
+/// a configurable delay before responding, like a slow network call would.
+#[derive(Clone, Debug, Default)]
+pub struct FakePaymentGateway {
+    delay: Duration,
+    /// The amount left to settle of every authorization and capture, by reference.
+    remaining: Arc<Mutex<HashMap<String, Money>>>,
+}
+
+impl FakePaymentGateway {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long every operation takes to respond.
+    pub fn with_delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    /// Settles `amount` of an existing operation, returning a reference for the new one.
+    fn settle(
+        &self,
+        reference: &ProviderReference,
+        amount: Money,
+        prefix: &str,
+    ) -> Result<ProviderReference, PaymentError> {
+        let mut remaining = self.remaining.lock().expect("fake payment state poisoned");
+        let left = remaining
+            .get_mut(&reference.0)
+            .ok_or_else(|| PaymentError::Unknown(anyhow!("unknown reference {}", reference.0)))?;
+        if amount > *left {
+            return Err(PaymentError::Declined(format!(
+                "amount exceeds the {} remaining on {}",
+                left.0, reference.0
+            )));
+        }
+        *left = Money(left.0 - amount.0);
+
+        let reference = format!("{prefix}_{}", Uuid::now_v7().simple());
+        remaining.insert(reference.clone(), amount);
+
+        Ok(ProviderReference(reference))
+    }
+}
+
+impl PaymentGateway for FakePaymentGateway {
+    async fn authorize(
+        &self,
+        method: &PaymentMethod,
+        amount: Money,
+    ) -> Result<ProviderReference, PaymentError> {
+        tokio::time::sleep(self.delay).await;
+
+        match method.0.as_str() {
+            DECLINED_METHOD => return Err(PaymentError::Declined("card declined".to_string())),
+            INSUFFICIENT_FUNDS_METHOD if amount > INSUFFICIENT_FUNDS_LIMIT => {
+                return Err(PaymentError::Declined("insufficient funds".to_string()))
+            }
+            _ => {}
+        }
+
+        let reference = format!("auth_{}", Uuid::now_v7().simple());
+        self.remaining
+            .lock()
+            .expect("fake payment state poisoned")
+            .insert(reference.clone(), amount);
+
+        Ok(ProviderReference(reference))
+    }
+
+    async fn capture(
+        &self,
+        authorization: &ProviderReference,
+        amount: Money,
+    ) -> Result<ProviderReference, PaymentError> {
+        tokio::time::sleep(self.delay).await;
+
+        self.settle(authorization, amount, "capt")
+    }
+
+    async fn refund(
+        &self,
+        capture: &ProviderReference,
+        amount: Money,
+    ) -> Result<ProviderReference, PaymentError> {
+        tokio::time::sleep(self.delay).await;
+
+        self.settle(capture, amount, "refd")
+    }
+}
@@ -0,0 +1,78 @@
+//! Module [filesystem] is an outbound adapter that stores blobs as files in a local directory.
+
+use crate::domain::booking::models::blob::{BlobError, BlobKey};
+use crate::domain::booking::ports::BlobStore;
+use anyhow::Context;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// [LocalBlobStore] is a [BlobStore] backed by a directory on the local filesystem.
+#[derive(Clone, Debug)]
+pub struct LocalBlobStore {
+    root: Arc<PathBuf>,
+}
+
+impl LocalBlobStore {
+    /// Creates a new [LocalBlobStore] rooted at `root`, creating the directory if needed.
+    pub async fn new(root: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let root = root.into();
+        tokio::fs::create_dir_all(&root)
+            .await
+            .with_context(|| format!("failed to create blob directory {}", root.display()))?;
+
+        Ok(Self {
+            root: Arc::new(root),
+        })
+    }
+
+    fn path(&self, key: &BlobKey) -> Result<PathBuf, BlobError> {
+        let valid = !key.0.is_empty()
+            && key
+                .0
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+            && !key.0.starts_with('.');
+
+        if valid {
+            Ok(self.root.join(&key.0))
+        } else {
+            Err(BlobError::InvalidKey(key.0.clone()))
+        }
+    }
+}
+
+impl BlobStore for LocalBlobStore {
+    async fn put_blob(&self, bytes: &[u8], extension: &str) -> Result<BlobKey, BlobError> {
+        let key = BlobKey(format!("{}.{extension}", Uuid::now_v7()));
+        let path = self.path(&key)?;
+
+        tokio::fs::write(&path, bytes)
+            .await
+            .with_context(|| format!("failed to write blob {}", path.display()))
+            .map_err(BlobError::Unknown)?;
+
+        Ok(key)
+    }
+
+    async fn get_blob(&self, key: &BlobKey) -> Result<Option<Vec<u8>>, BlobError> {
+        let path = self.path(key)?;
+
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(BlobError::Unknown(e.into())),
+        }
+    }
+
+    async fn delete_blob(&self, key: &BlobKey) -> Result<(), BlobError> {
+        let path = self.path(key)?;
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(BlobError::Unknown(e.into())),
+        }
+    }
+}
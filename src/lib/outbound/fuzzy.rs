@@ -0,0 +1,159 @@
+//! Module [fuzzy] approximates the `pg_trgm` customer search and duplicate detection of the
+//! [postgres](crate::outbound::postgres) adapter, for adapters whose storage has no trigram
+//! matching of its own. Customers are matched in process, so it suits small customer bases.
+
+use crate::domain::booking::models::customer::*;
+use std::collections::HashSet;
+
+/// The `pg_trgm.similarity_threshold` used by the `%` operator.
+const SIMILARITY_THRESHOLD: f64 = 0.3;
+/// The `pg_trgm.word_similarity_threshold` used by the `<%` operator.
+const WORD_SIMILARITY_THRESHOLD: f64 = 0.6;
+
+/// Finds the customers whose name or email resembles or contains the search query, or
+/// whose phone number contains its digits, best matches first.
+pub(crate) fn search_customers<'a>(
+    customers: impl IntoIterator<Item = &'a Customer>,
+    search: &CustomerSearch,
+) -> CustomerSearchResults {
+    let query = search.query.to_lowercase();
+
+    let mut matches = customers
+        .into_iter()
+        .filter_map(|c| {
+            let name = c.name.0.to_lowercase();
+            let email = c.email.0.to_lowercase();
+            let phone_match = search
+                .phone_digits
+                .as_ref()
+                .is_some_and(|digits| c.phone.0.contains(digits.as_str()));
+            let score = word_similarity(&query, &name)
+                .max(word_similarity(&query, &email))
+                .max(if phone_match { 1.0 } else { 0.0 });
+
+            let matched = name.contains(&query)
+                || email.contains(&query)
+                || phone_match
+                || score >= WORD_SIMILARITY_THRESHOLD;
+            matched.then_some((score, c))
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    CustomerSearchResults {
+        total: matches.len() as i64,
+        customers: matches
+            .into_iter()
+            .skip(search.page.offset as usize)
+            .take(search.page.limit as usize)
+            .map(|(_, c)| c.clone())
+            .collect(),
+    }
+}
+
+/// Finds pairs of customers that share a phone number or have similar names, most suspicious
+/// pairs first. Sharing the user part of their email address only adds to another reason.
+pub(crate) fn find_duplicate_customers<'a>(
+    customers: impl IntoIterator<Item = &'a Customer>,
+    page: Page,
+) -> Vec<DuplicateCandidate> {
+    let mut customers = customers.into_iter().collect::<Vec<_>>();
+    customers.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut candidates = vec![];
+    for (i, first) in customers.iter().enumerate() {
+        for second in &customers[i + 1..] {
+            let name_similarity = similarity(&first.name.0, &second.name.0);
+            let reasons = [
+                (first.phone == second.phone, DuplicateReason::SamePhone),
+                (
+                    name_similarity >= SIMILARITY_THRESHOLD,
+                    DuplicateReason::SimilarName,
+                ),
+                (
+                    email_user(&first.email) == email_user(&second.email),
+                    DuplicateReason::SameEmailUser,
+                ),
+            ]
+            .into_iter()
+            .filter_map(|(flagged, reason)| flagged.then_some(reason))
+            .collect::<Vec<_>>();
+
+            if reasons.iter().any(|r| r != &DuplicateReason::SameEmailUser) {
+                candidates.push((
+                    name_similarity,
+                    DuplicateCandidate {
+                        first: (*first).clone(),
+                        second: (*second).clone(),
+                        reasons,
+                    },
+                ));
+            }
+        }
+    }
+    candidates.sort_by(|(a_similarity, a), (b_similarity, b)| {
+        b.reasons
+            .len()
+            .cmp(&a.reasons.len())
+            .then_with(|| b_similarity.total_cmp(a_similarity))
+            .then_with(|| a.first.id.cmp(&b.first.id))
+            .then_with(|| a.second.id.cmp(&b.second.id))
+    });
+
+    candidates
+        .into_iter()
+        .skip(page.offset as usize)
+        .take(page.limit as usize)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// The part of an email address before the `@`, ignoring case.
+fn email_user(email: &EmailAddress) -> String {
+    email.0.split('@').next().unwrap_or_default().to_lowercase()
+}
+
+/// The trigrams of each word of `text`, as extracted by `pg_trgm`.
+fn trigrams(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let padded = format!("  {word} ").chars().collect::<Vec<_>>();
+            padded
+                .windows(3)
+                .map(|w| w.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The share of trigrams two strings have in common, like `pg_trgm`'s `similarity`.
+fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (trigrams(a), trigrams(b));
+    let shared = a.intersection(&b).count();
+    let total = a.len() + b.len() - shared;
+
+    if total == 0 {
+        0.0
+    } else {
+        shared as f64 / total as f64
+    }
+}
+
+/// The share of the trigrams of `query` that also occur in `text`, like `pg_trgm`'s
+/// `word_similarity`.
+fn word_similarity(query: &str, text: &str) -> f64 {
+    let (query, text) = (trigrams(query), trigrams(text));
+
+    if query.is_empty() {
+        0.0
+    } else {
+        query.intersection(&text).count() as f64 / query.len() as f64
+    }
+}
@@ -0,0 +1,315 @@
+//! Module [memory] is an outbound adapter that keeps all data in process memory, so the
+//! domain can be exercised without a database, e.g. in unit tests or demos.
+//!
+//! Data is stored in tables shaped like the PostgreSQL schema, and every port method
+//! follows the same rules as the [postgres](crate::outbound::postgres) adapter, including
+//! its constraints. All tables sit behind a single lock rather than in a concurrent map
+//! each, since many methods check and write several tables at once (e.g. seats, rentals
+//! and waitlist offers when saving a booking), and a single lock keeps each method atomic
+//! without ordering locks across tables.
+//!
+//! A unit of work runs on a copy of all the tables, which replaces them when it commits.
+//! Writes outside of it wait until it ends, so none are lost. Units of work therefore run
+//! one at a time and copy every table, which is fine for tests and demos but doesn't scale
+//! to production data.
+//!
+//! Reference data that the application never writes, such as trip kinds and equipment,
+//! is added with the `insert_*` methods, which shouldn't be called while a unit of work is
+//! open.
+
+mod booking_repository;
+mod customer_repository;
+mod rental_repository;
+mod schedule_repository;
+mod trip_repository;
+mod unit_of_work;
+mod waiver_repository;
+
+use crate::domain::booking::models::booking::{
+    Booking, BookingId, BookingStatus, Participant, ParticipantId,
+};
+use crate::domain::booking::models::customer::{Customer, CustomerId, CustomerMerge};
+use crate::domain::booking::models::equipment::{Equipment, EquipmentId};
+use crate::domain::booking::models::payment::Payment;
+use crate::domain::booking::models::pricing::{Money, ParticipantCategory, PriceModifier};
+use crate::domain::booking::models::trip::{
+    Location, LocationId, Trip, TripId, TripKind, TripKindId,
+};
+use crate::domain::booking::models::waitlist::{WaitlistEntry, WaitlistEntryId};
+use crate::domain::booking::models::waiver::{Waiver, WaiverId, WaiverSignature};
+use crate::domain::scheduling::models::schedule::{Schedule, ScheduleId};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
+#[cfg(test)]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tokio::sync::{Mutex, MutexGuard};
+
+#[derive(Clone, Debug, Default)]
+pub struct Memory {
+    tables: Arc<RwLock<Tables>>,
+    /// Held by the open unit of work, if any.
+    gate: Arc<Mutex<()>>,
+    /// The unit of work this instance is bound to, in which case `tables` is its copy.
+    unit: Option<Arc<unit_of_work::Unit>>,
+    /// Whether saving waiver signatures fails, as if the database were unavailable.
+    #[cfg(test)]
+    failing_waiver_signatures: Arc<AtomicBool>,
+}
+
+impl Memory {
+    /// Creates an empty instance of [Memory].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a [Location].
+    pub fn insert_location(&self, location: Location) {
+        self.write_now()
+            .locations
+            .insert(location.id.clone(), location);
+    }
+
+    /// Adds or replaces a [TripKind], including its eligibility and cancellation policy.
+    pub fn insert_trip_kind(&self, kind: TripKind) {
+        self.write_now().trip_kinds.insert(kind.id.clone(), kind);
+    }
+
+    /// Adds or replaces a one-off [Trip], along with its kind.
+    pub fn insert_trip(&self, trip: Trip) {
+        let mut tables = self.write_now();
+        tables.trips.insert(
+            trip.id.clone(),
+            TripRow {
+                id: trip.id,
+                kind: trip.kind.id.clone(),
+                location: trip.location,
+                start_time: trip.start_time,
+                end_time: trip.end_time,
+                max_participants: trip.max_participants,
+                occurrence: None,
+                detached: false,
+                timezone: trip.timezone,
+            },
+        );
+        tables.trip_kinds.insert(trip.kind.id.clone(), trip.kind);
+    }
+
+    /// Adds or replaces an [Equipment] item, including its rental price.
+    pub fn insert_equipment(&self, equipment: Equipment) {
+        self.write_now()
+            .equipment
+            .insert(equipment.id.clone(), equipment);
+    }
+
+    /// Sets the base price of a participant category on trips of the given kind.
+    pub fn insert_base_price(&self, kind: TripKindId, category: ParticipantCategory, price: Money) {
+        self.write_now()
+            .base_prices
+            .entry(kind)
+            .or_default()
+            .insert(category, price);
+    }
+
+    /// Adds or replaces a [PriceModifier].
+    pub fn insert_price_modifier(&self, modifier: PriceModifier) {
+        let mut tables = self.write_now();
+        tables.price_modifiers.retain(|m| m.id != modifier.id);
+        tables.price_modifiers.push(modifier);
+    }
+
+    /// Makes every later attempt to save a waiver signature fail, so that tests can check
+    /// how callers clean up after it.
+    #[cfg(test)]
+    pub(crate) fn fail_waiver_signatures(&self) {
+        self.failing_waiver_signatures.store(true, Ordering::SeqCst);
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Tables> {
+        self.tables.read().expect("memory state poisoned")
+    }
+
+    /// Locks the tables for writing, first waiting for any unit of work this instance isn't
+    /// bound to.
+    async fn write(&self) -> TablesGuard<'_> {
+        let gate = match self.unit {
+            Some(_) => None,
+            None => Some(self.gate.lock().await),
+        };
+
+        TablesGuard {
+            tables: self.write_now(),
+            _gate: gate,
+        }
+    }
+
+    fn write_now(&self) -> RwLockWriteGuard<'_, Tables> {
+        self.tables.write().expect("memory state poisoned")
+    }
+}
+
+/// [TablesGuard] is a write lock on the tables, which also keeps units of work out.
+struct TablesGuard<'a> {
+    tables: RwLockWriteGuard<'a, Tables>,
+    _gate: Option<MutexGuard<'a, ()>>,
+}
+
+impl Deref for TablesGuard<'_> {
+    type Target = Tables;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tables
+    }
+}
+
+impl DerefMut for TablesGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tables
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Tables {
+    locations: HashMap<LocationId, Location>,
+    trip_kinds: HashMap<TripKindId, TripKind>,
+    trips: HashMap<TripId, TripRow>,
+    customers: HashMap<CustomerId, Customer>,
+    customer_merges: Vec<CustomerMerge>,
+    bookings: HashMap<BookingId, BookingRow>,
+    /// Participants outlive their bookings, and keep their [Participant::waiver] unset;
+    /// it is derived from their signatures when read.
+    participants: HashMap<ParticipantId, Participant>,
+    rentals: HashMap<BookingId, HashMap<EquipmentId, i32>>,
+    equipment: HashMap<EquipmentId, Equipment>,
+    /// Every version of each waiver, oldest first.
+    waivers: HashMap<WaiverId, Vec<Waiver>>,
+    waiver_signatures: Vec<WaiverSignature>,
+    base_prices: HashMap<TripKindId, HashMap<ParticipantCategory, Money>>,
+    price_modifiers: Vec<PriceModifier>,
+    payments: Vec<Payment>,
+    waitlist: HashMap<WaitlistEntryId, WaitlistEntry>,
+    schedules: HashMap<ScheduleId, Schedule>,
+    /// The cancelled occurrences of each schedule, by scheduled start.
+    schedule_exceptions: HashSet<(ScheduleId, DateTime<Utc>)>,
+}
+
+#[derive(Clone, Debug)]
+struct TripRow {
+    id: TripId,
+    kind: TripKindId,
+    location: LocationId,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    max_participants: Option<i32>,
+    /// The schedule and scheduled start of the occurrence this trip was materialized from.
+    occurrence: Option<(ScheduleId, DateTime<Utc>)>,
+    /// Whether the trip was moved on its own, so later edits to its schedule leave it alone.
+    detached: bool,
+    timezone: Tz,
+}
+
+#[derive(Clone, Debug)]
+struct BookingRow {
+    /// The booking without its participants, which are joined in when read.
+    booking: Booking,
+    participants: Vec<ParticipantId>,
+}
+
+impl Tables {
+    fn trip(&self, row: &TripRow) -> Option<Trip> {
+        let kind = self.trip_kinds.get(&row.kind)?;
+
+        Some(Trip {
+            id: row.id.clone(),
+            kind: kind.clone(),
+            location: row.location.clone(),
+            start_time: row.start_time,
+            end_time: row.end_time,
+            max_participants: row.max_participants,
+            timezone: row.timezone,
+        })
+    }
+
+    fn booking(&self, row: &BookingRow) -> Booking {
+        Booking {
+            participants: row
+                .participants
+                .iter()
+                .filter_map(|id| self.participant(id))
+                .collect(),
+            ..row.booking.clone()
+        }
+    }
+
+    fn participant(&self, id: &ParticipantId) -> Option<Participant> {
+        let participant = self.participants.get(id)?;
+        let waiver = self
+            .waiver_signatures
+            .iter()
+            .filter(|s| &s.participant == id)
+            .max_by_key(|s| s.signed_at)
+            .map(|s| s.waiver.clone());
+
+        Some(Participant {
+            waiver,
+            ..participant.clone()
+        })
+    }
+
+    /// Whether a booking still reserves its seats and rentals at `at`.
+    fn is_active(booking: &Booking, at: DateTime<Utc>) -> bool {
+        match booking.status {
+            BookingStatus::Cancelled => false,
+            BookingStatus::Held => booking.hold_expires_at.is_none_or(|t| t > at),
+            _ => true,
+        }
+    }
+
+    fn has_bookings(&self, trip: &TripId) -> bool {
+        self.bookings.values().any(|b| &b.booking.trip == trip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::booking::models::trip::{LocationDescription, LocationName};
+    use crate::outbound::conformance::{booking_repository_conformance, Catalog};
+
+    impl Catalog for Memory {
+        async fn insert_trip(&self, trip: &Trip) {
+            if !self.read().locations.contains_key(&trip.location) {
+                Memory::insert_location(
+                    self,
+                    Location {
+                        id: trip.location.clone(),
+                        name: LocationName(trip.location.0.to_string()),
+                        description: LocationDescription(String::new()),
+                    },
+                );
+            }
+            Memory::insert_trip(self, trip.clone());
+        }
+
+        async fn insert_equipment(&self, equipment: &Equipment) {
+            Memory::insert_equipment(self, equipment.clone());
+        }
+
+        async fn insert_base_price(
+            &self,
+            kind: &TripKindId,
+            category: ParticipantCategory,
+            price: Money,
+        ) {
+            Memory::insert_base_price(self, kind.clone(), category, price);
+        }
+
+        async fn insert_price_modifier(&self, modifier: &PriceModifier) {
+            Memory::insert_price_modifier(self, modifier.clone());
+        }
+    }
+
+    booking_repository_conformance!(#[tokio::test] () => Memory::new());
+}
@@ -0,0 +1,629 @@
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::equipment::*;
+use crate::domain::booking::models::payment::*;
+use crate::domain::booking::models::pricing::*;
+use crate::domain::booking::models::trip::*;
+use crate::domain::booking::models::waitlist::*;
+use crate::domain::booking::ports::BookingRepository;
+use crate::outbound::memory::{BookingRow, Memory, Tables};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+impl BookingRepository for Memory {
+    async fn find_booking(&self, id: BookingId) -> Result<Option<Booking>, BookingError> {
+        let tables = self.read();
+
+        Ok(tables.bookings.get(&id).map(|row| tables.booking(row)))
+    }
+
+    async fn find_bookings(&self, filters: &BookingFilters) -> Result<Vec<Booking>, BookingError> {
+        if filters.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let tables = self.read();
+        let mut bookings = tables
+            .bookings
+            .values()
+            .filter(|row| {
+                let booking = &row.booking;
+                filters
+                    .reference
+                    .as_ref()
+                    .is_none_or(|r| &booking.reference == r)
+                    && filters
+                        .customer
+                        .as_ref()
+                        .is_none_or(|c| &booking.customer == c)
+                    && filters.trip.as_ref().is_none_or(|t| &booking.trip == t)
+                    && filters
+                        .participant
+                        .as_ref()
+                        .is_none_or(|p| row.participants.contains(p))
+                    && filters.status.is_none_or(|s| booking.status == s)
+            })
+            .map(|row| tables.booking(row))
+            .collect::<Vec<_>>();
+        bookings.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(bookings)
+    }
+
+    async fn save_booking(
+        &self,
+        booking: &Booking,
+        overbooking: OverbookingPolicy,
+    ) -> Result<bool, BookingError> {
+        let mut tables = self.write().await;
+
+        let overbooked =
+            reserve_seats(&tables, booking, overbooking, Utc::now())? || booking.overbooked;
+        if !tables.customers.contains_key(&booking.customer) {
+            return Err(BookingError::Unknown(anyhow!(
+                "booking {} references missing customer {}",
+                booking.id.0,
+                booking.customer.0
+            )));
+        }
+        if let Some(entry) = &booking.waitlist_entry {
+            let Some(entry) = tables.waitlist.get_mut(entry) else {
+                return Err(BookingError::Unknown(anyhow!(
+                    "booking {} references missing waitlist entry {}",
+                    booking.id.0,
+                    entry.0
+                )));
+            };
+            if let WaitlistStatus::Offered { .. } = entry.status {
+                entry.status = WaitlistStatus::Accepted;
+            }
+        }
+
+        for participant in &booking.participants {
+            tables.participants.insert(
+                participant.id.clone(),
+                Participant {
+                    waiver: None,
+                    ..participant.clone()
+                },
+            );
+        }
+        tables.bookings.insert(
+            booking.id.clone(),
+            BookingRow {
+                booking: Booking {
+                    participants: vec![],
+                    overbooked,
+                    ..booking.clone()
+                },
+                participants: booking.participants.iter().map(|p| p.id.clone()).collect(),
+            },
+        );
+
+        Ok(overbooked)
+    }
+
+    async fn reschedule_booking(
+        &self,
+        booking: &Booking,
+        overbooking: OverbookingPolicy,
+    ) -> Result<bool, BookingError> {
+        let now = Utc::now();
+        let mut tables = self.write().await;
+
+        let status = tables
+            .bookings
+            .get(&booking.id)
+            .map(|row| row.booking.status);
+        if status != Some(booking.status) {
+            return Err(BookingError::StatusChanged {
+                booking: booking.id.clone(),
+                from: booking.status,
+            });
+        }
+        let overbooked = reserve_seats(&tables, booking, overbooking, now)? || booking.overbooked;
+
+        let trip = tables
+            .trips
+            .get(&booking.trip)
+            .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+        let rentals = tables.rentals.get(&booking.id).cloned().unwrap_or_default();
+
+        reserve_inventory(
+            &tables,
+            &booking.id,
+            trip.start_time,
+            trip.end_time,
+            &rentals,
+            now,
+        )
+        .map_err(|e| match e {
+            EquipmentError::InsufficientInventory {
+                equipment,
+                requested,
+                available,
+            } => BookingError::InsufficientInventory {
+                equipment,
+                requested,
+                available,
+            },
+            e => BookingError::Unknown(e.into()),
+        })?;
+
+        if let Some(row) = tables.bookings.get_mut(&booking.id) {
+            row.booking.trip = booking.trip.clone();
+            row.booking.overbooked = overbooked;
+        }
+
+        Ok(overbooked)
+    }
+
+    async fn confirm_hold(&self, id: BookingId, at: DateTime<Utc>) -> Result<(), BookingError> {
+        let mut tables = self.write().await;
+
+        let booking = tables
+            .bookings
+            .get_mut(&id)
+            .map(|row| &mut row.booking)
+            .filter(|b| b.status == BookingStatus::Held && !b.hold_expired(at))
+            .ok_or(BookingError::HoldExpired(id))?;
+        booking.status = BookingStatus::Confirmed;
+        booking.hold_expires_at = None;
+
+        Ok(())
+    }
+
+    async fn save_booking_status(
+        &self,
+        booking: &Booking,
+        from: BookingStatus,
+    ) -> Result<(), BookingError> {
+        let mut tables = self.write().await;
+
+        let saved = tables
+            .bookings
+            .get_mut(&booking.id)
+            .map(|row| &mut row.booking)
+            .filter(|b| b.status == from)
+            .ok_or_else(|| BookingError::StatusChanged {
+                booking: booking.id.clone(),
+                from,
+            })?;
+        saved.status = booking.status;
+        saved.hold_expires_at = booking.hold_expires_at;
+        saved.checked_in_at = booking.checked_in_at;
+        saved.cancellation = booking.cancellation.clone();
+
+        Ok(())
+    }
+
+    async fn cancel_expired_holds(
+        &self,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<BookingId>, BookingError> {
+        let mut tables = self.write().await;
+
+        let mut cancelled = vec![];
+        for row in tables.bookings.values_mut() {
+            let booking = &mut row.booking;
+            if booking.status == BookingStatus::Held
+                && booking.hold_expires_at.is_some_and(|t| t <= at)
+            {
+                booking.status = BookingStatus::Cancelled;
+                booking.hold_expires_at = None;
+                booking.cancellation = Some(Cancellation {
+                    reason: HOLD_EXPIRED_REASON.to_string(),
+                    cancelled_at: at,
+                    refund: Money::default(),
+                });
+                cancelled.push(booking.id.clone());
+            }
+        }
+
+        Ok(cancelled)
+    }
+
+    async fn delete_booking(&self, id: BookingId) -> Result<(), BookingError> {
+        let mut tables = self.write().await;
+
+        if tables.payments.iter().any(|p| p.booking == id) {
+            return Err(BookingError::Unknown(anyhow!(
+                "booking {} is still referenced by its payments",
+                id.0
+            )));
+        }
+        tables.rentals.remove(&id);
+        tables.bookings.remove(&id);
+
+        Ok(())
+    }
+
+    async fn find_waitlist_entry(
+        &self,
+        id: WaitlistEntryId,
+    ) -> Result<Option<WaitlistEntry>, WaitlistError> {
+        Ok(self.read().waitlist.get(&id).cloned())
+    }
+
+    async fn find_waitlist(&self, trip: TripId) -> Result<Vec<WaitlistEntry>, WaitlistError> {
+        Ok(waitlist_of(&self.read(), &trip))
+    }
+
+    async fn save_waitlist_entry(&self, entry: &WaitlistEntry) -> Result<(), WaitlistError> {
+        let mut tables = self.write().await;
+
+        if entry.party_size <= 0 {
+            return Err(WaitlistError::Unknown(anyhow!(
+                "waitlist entry {} has invalid party size {}",
+                entry.id.0,
+                entry.party_size
+            )));
+        }
+
+        match tables.waitlist.get_mut(&entry.id) {
+            Some(existing) => {
+                existing.party_size = entry.party_size;
+                existing.status = entry.status;
+            }
+            None => {
+                if !tables.customers.contains_key(&entry.customer) {
+                    return Err(WaitlistError::Unknown(anyhow!(
+                        "waitlist entry {} references missing customer {}",
+                        entry.id.0,
+                        entry.customer.0
+                    )));
+                }
+                if !tables.trips.contains_key(&entry.trip) {
+                    return Err(WaitlistError::Unknown(anyhow!(
+                        "waitlist entry {} references missing trip {}",
+                        entry.id.0,
+                        entry.trip.0
+                    )));
+                }
+                tables.waitlist.insert(entry.id.clone(), entry.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn find_waitlisted_trips(&self, at: DateTime<Utc>) -> Result<Vec<TripId>, WaitlistError> {
+        let tables = self.read();
+
+        let mut trips = tables
+            .waitlist
+            .values()
+            .filter(|e| match e.status {
+                WaitlistStatus::Waiting => true,
+                WaitlistStatus::Offered { expires_at } => expires_at <= at,
+                _ => false,
+            })
+            .filter(|e| tables.trips.get(&e.trip).is_some_and(|t| t.start_time > at))
+            .map(|e| e.trip.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        trips.sort();
+
+        Ok(trips)
+    }
+
+    async fn offer_waitlist_seats(
+        &self,
+        trip: TripId,
+        at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<WaitlistEntry>, WaitlistError> {
+        let mut tables = self.write().await;
+
+        let capacity = tables
+            .trips
+            .get(&trip)
+            .and_then(|row| tables.trip(row))
+            .ok_or_else(|| WaitlistError::TripNotFound(trip.clone()))?
+            .capacity();
+        let taken = seats_taken(&tables, &trip, None, at);
+
+        for entry in tables.waitlist.values_mut() {
+            if entry.trip == trip
+                && matches!(entry.status, WaitlistStatus::Offered { expires_at } if expires_at <= at)
+            {
+                entry.status = WaitlistStatus::Expired;
+            }
+        }
+
+        let entries = waitlist_of(&tables, &trip)
+            .into_iter()
+            .filter(|e| {
+                matches!(
+                    e.status,
+                    WaitlistStatus::Waiting | WaitlistStatus::Offered { .. }
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let offered_seats = entries
+            .iter()
+            .filter(|e| e.status != WaitlistStatus::Waiting)
+            .map(|e| e.party_size as i64)
+            .sum::<i64>();
+        let mut available =
+            capacity.map(|capacity| (capacity as i64 - taken - offered_seats).max(0));
+
+        let mut offers = vec![];
+        for entry in entries {
+            if entry.status != WaitlistStatus::Waiting {
+                continue;
+            }
+            match &mut available {
+                Some(available) if *available < entry.party_size as i64 => break,
+                Some(available) => *available -= entry.party_size as i64,
+                None => {}
+            }
+            offers.push(WaitlistEntry {
+                status: WaitlistStatus::Offered { expires_at },
+                ..entry
+            });
+        }
+
+        for offer in &offers {
+            tables.waitlist.insert(offer.id.clone(), offer.clone());
+        }
+
+        Ok(offers)
+    }
+
+    async fn find_participant(
+        &self,
+        id: ParticipantId,
+    ) -> Result<Option<Participant>, BookingError> {
+        Ok(self.read().participant(&id))
+    }
+
+    async fn find_price_list(&self, trip_kind: TripKindId) -> Result<PriceList, PricingError> {
+        let tables = self.read();
+
+        let mut modifiers = tables
+            .price_modifiers
+            .iter()
+            .filter(|m| m.trip_kind.as_ref().is_none_or(|k| k == &trip_kind))
+            .cloned()
+            .collect::<Vec<_>>();
+        modifiers.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(PriceList {
+            base_prices: tables
+                .base_prices
+                .get(&trip_kind)
+                .cloned()
+                .unwrap_or_default(),
+            rentals: tables
+                .equipment
+                .values()
+                .filter_map(|e| e.rental_price.map(|price| (e.id.clone(), price)))
+                .collect(),
+            modifiers,
+        })
+    }
+
+    async fn find_payment(&self, id: PaymentId) -> Result<Option<Payment>, PaymentError> {
+        Ok(self.read().payments.iter().find(|p| p.id == id).cloned())
+    }
+
+    async fn find_payment_ledger(&self, booking: BookingId) -> Result<PaymentLedger, PaymentError> {
+        let mut payments = self
+            .read()
+            .payments
+            .iter()
+            .filter(|p| p.booking == booking)
+            .cloned()
+            .collect::<Vec<_>>();
+        payments.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+
+        Ok(PaymentLedger { booking, payments })
+    }
+
+    async fn save_payment(&self, payment: &Payment) -> Result<(), PaymentError> {
+        let mut tables = self.write().await;
+
+        if tables.payments.iter().any(|p| p.id == payment.id) {
+            return Err(PaymentError::Unknown(anyhow!(
+                "payment {} already exists",
+                payment.id.0
+            )));
+        }
+        if !tables.bookings.contains_key(&payment.booking) {
+            return Err(PaymentError::Unknown(anyhow!(
+                "payment {} references missing booking {}",
+                payment.id.0,
+                payment.booking.0
+            )));
+        }
+        if let Some(parent) = &payment.parent {
+            if !tables.payments.iter().any(|p| &p.id == parent) {
+                return Err(PaymentError::Unknown(anyhow!(
+                    "payment {} references missing payment {}",
+                    payment.id.0,
+                    parent.0
+                )));
+            }
+        }
+        tables.payments.push(payment.clone());
+
+        Ok(())
+    }
+}
+
+/// Checks a booking's trip has room for its participants, applying the [OverbookingPolicy]
+/// if it doesn't, and that none of them are already on another active booking of the trip.
+/// Seats the booking already holds on the trip are kept.
+///
+/// Returns whether the booking was flagged as overbooked.
+fn reserve_seats(
+    tables: &Tables,
+    booking: &Booking,
+    overbooking: OverbookingPolicy,
+    now: DateTime<Utc>,
+) -> Result<bool, BookingError> {
+    let trip = tables
+        .trips
+        .get(&booking.trip)
+        .and_then(|row| tables.trip(row))
+        .ok_or_else(|| {
+            BookingError::Unknown(anyhow!(
+                "booking {} references missing trip {}",
+                booking.id.0,
+                booking.trip.0
+            ))
+        })?;
+
+    let others = tables
+        .bookings
+        .values()
+        .filter(|row| row.booking.trip == trip.id && Tables::is_active(&row.booking, now))
+        .map(|row| tables.booking(row))
+        .collect::<Vec<_>>();
+    booking.ensure_not_double_booked(
+        others
+            .iter()
+            .flat_map(|other| other.participants.iter().map(move |p| (&other.id, p))),
+    )?;
+
+    let Some(capacity) = trip.capacity() else {
+        return Ok(false);
+    };
+
+    let taken = seats_taken(tables, &trip.id, Some(&booking.id), now);
+    let offered = tables
+        .waitlist
+        .values()
+        .filter(|e| e.trip == trip.id && Some(&e.id) != booking.waitlist_entry.as_ref())
+        .filter(|e| e.has_open_offer(now))
+        .map(|e| e.party_size as i64)
+        .sum::<i64>();
+    let current = tables
+        .bookings
+        .get(&booking.id)
+        .filter(|row| row.booking.trip == trip.id)
+        .map_or(0, |row| row.participants.len() as i64);
+
+    let requested = booking.participants.len() as i64;
+    let taken = taken + offered;
+    let available = (capacity as i64 - taken).max(0);
+    if requested > available && requested > current {
+        match overbooking {
+            OverbookingPolicy::Reject => {
+                return Err(BookingError::TripFull {
+                    trip: booking.trip.clone(),
+                    capacity,
+                    available: available as i32,
+                });
+            }
+            OverbookingPolicy::Flag => {
+                tracing::warn!(
+                    trip_id = %booking.trip.0,
+                    booking_id = %booking.id.0,
+                    capacity,
+                    booked = taken + requested,
+                    "trip is overbooked"
+                );
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Counts the participants of bookings on a trip that still reserve their seats at `at`,
+/// besides those of the `except` booking.
+fn seats_taken(
+    tables: &Tables,
+    trip: &TripId,
+    except: Option<&BookingId>,
+    at: DateTime<Utc>,
+) -> i64 {
+    tables
+        .bookings
+        .values()
+        .filter(|row| &row.booking.trip == trip && Some(&row.booking.id) != except)
+        .filter(|row| Tables::is_active(&row.booking, at))
+        .map(|row| row.participants.len() as i64)
+        .sum()
+}
+
+/// Checks enough of each rented item is left for a booking's rentals across all trips
+/// overlapping `start_time..end_time`, besides what the booking itself rents.
+pub(super) fn reserve_inventory(
+    tables: &Tables,
+    booking: &BookingId,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    rentals: &HashMap<EquipmentId, i32>,
+    now: DateTime<Utc>,
+) -> Result<(), EquipmentError> {
+    for (equipment_id, quantity) in rentals {
+        let Some(equipment) = tables.equipment.get(equipment_id) else {
+            return Err(EquipmentError::NotFound(equipment_id.clone()));
+        };
+
+        let reserved = units_reserved(
+            tables,
+            equipment_id,
+            Some(booking),
+            start_time,
+            end_time,
+            now,
+        );
+        let available = (equipment.total_inventory as i64 - reserved).max(0) as i32;
+        if *quantity > available {
+            return Err(EquipmentError::InsufficientInventory {
+                equipment: equipment_id.clone(),
+                requested: *quantity,
+                available,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts the units of an item rented by bookings that are active at `now`, on trips
+/// overlapping `start_time..end_time`, besides those of the `except` booking.
+pub(super) fn units_reserved(
+    tables: &Tables,
+    equipment: &EquipmentId,
+    except: Option<&BookingId>,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> i64 {
+    tables
+        .rentals
+        .iter()
+        .filter(|(booking, _)| Some(*booking) != except)
+        .filter_map(|(booking, rentals)| Some((tables.bookings.get(booking)?, rentals)))
+        .filter(|(row, _)| Tables::is_active(&row.booking, now))
+        .filter(|(row, _)| {
+            tables
+                .trips
+                .get(&row.booking.trip)
+                .is_some_and(|trip| trip.start_time < end_time && trip.end_time > start_time)
+        })
+        .filter_map(|(_, rentals)| rentals.get(equipment))
+        .map(|quantity| *quantity as i64)
+        .sum()
+}
+
+/// Gets the waitlist of a trip, in the order the entries joined.
+fn waitlist_of(tables: &Tables, trip: &TripId) -> Vec<WaitlistEntry> {
+    let mut entries = tables
+        .waitlist
+        .values()
+        .filter(|e| &e.trip == trip)
+        .cloned()
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+
+    entries
+}
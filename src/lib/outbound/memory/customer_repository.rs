@@ -0,0 +1,163 @@
+use crate::domain::booking::models::customer::*;
+use crate::domain::booking::ports::CustomerRepository;
+use crate::outbound::fuzzy;
+use crate::outbound::memory::{Memory, Tables};
+use anyhow::anyhow;
+use chrono::Utc;
+use uuid::Uuid;
+
+impl CustomerRepository for Memory {
+    async fn find_customer(&self, id: CustomerId) -> Result<Option<Customer>, CustomerError> {
+        Ok(self.read().customers.get(&id).cloned())
+    }
+
+    async fn find_customers(&self, page: Page) -> Result<Vec<Customer>, CustomerError> {
+        let mut customers = self.read().customers.values().cloned().collect::<Vec<_>>();
+        customers.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(customers
+            .into_iter()
+            .skip(page.offset as usize)
+            .take(page.limit as usize)
+            .collect())
+    }
+
+    async fn save_customer(&self, customer: &Customer) -> Result<(), CustomerError> {
+        let mut tables = self.write().await;
+
+        ensure_email_free(&tables, customer, &[])?;
+        tables
+            .customers
+            .insert(customer.id.clone(), customer.clone());
+
+        Ok(())
+    }
+
+    async fn search_customers(
+        &self,
+        search: &CustomerSearch,
+    ) -> Result<CustomerSearchResults, CustomerError> {
+        Ok(fuzzy::search_customers(
+            self.read().customers.values(),
+            search,
+        ))
+    }
+
+    async fn find_duplicate_customers(
+        &self,
+        page: Page,
+    ) -> Result<Vec<DuplicateCandidate>, CustomerError> {
+        Ok(fuzzy::find_duplicate_customers(
+            self.read().customers.values(),
+            page,
+        ))
+    }
+
+    async fn merge_customers(
+        &self,
+        request: &MergeCustomersRequest,
+    ) -> Result<CustomerMerge, CustomerError> {
+        let mut tables = self.write().await;
+
+        let before = tables
+            .customers
+            .get(&request.survivor)
+            .cloned()
+            .ok_or_else(|| CustomerError::NotFound(request.survivor.clone()))?;
+        let merged = tables
+            .customers
+            .get(&request.merged)
+            .cloned()
+            .ok_or_else(|| CustomerError::NotFound(request.merged.clone()))?;
+        let survivor = before.clone().absorb(&merged, request);
+        ensure_email_free(&tables, &survivor, &[&merged.id])?;
+
+        let mut bookings = vec![];
+        for row in tables.bookings.values_mut() {
+            if row.booking.customer == merged.id {
+                row.booking.customer = survivor.id.clone();
+                bookings.push(row.booking.id.clone());
+            }
+        }
+        bookings.sort();
+
+        let mut waitlist_entries = vec![];
+        for entry in tables.waitlist.values_mut() {
+            if entry.customer == merged.id {
+                entry.customer = survivor.id.clone();
+                waitlist_entries.push(entry.id.clone());
+            }
+        }
+        waitlist_entries.sort();
+
+        tables.customers.remove(&merged.id);
+        tables
+            .customers
+            .insert(survivor.id.clone(), survivor.clone());
+
+        let merge = CustomerMerge {
+            id: CustomerMergeId(Uuid::now_v7()),
+            survivor: survivor.id.clone(),
+            survivor_before: Some(before),
+            merged,
+            bookings,
+            waitlist_entries,
+            merged_at: Utc::now(),
+        };
+        tables.customer_merges.push(merge.clone());
+
+        Ok(merge)
+    }
+
+    async fn find_customer_merges(
+        &self,
+        id: CustomerId,
+    ) -> Result<Vec<CustomerMerge>, CustomerError> {
+        let mut merges = self
+            .read()
+            .customer_merges
+            .iter()
+            .filter(|m| m.survivor == id || m.merged.id == id)
+            .cloned()
+            .collect::<Vec<_>>();
+        merges.sort_by(|a, b| (a.merged_at, &a.id).cmp(&(b.merged_at, &b.id)));
+
+        Ok(merges)
+    }
+
+    async fn delete_customer(&self, id: CustomerId) -> Result<(), CustomerError> {
+        let mut tables = self.write().await;
+
+        let referenced = tables.bookings.values().any(|b| b.booking.customer == id)
+            || tables.waitlist.values().any(|e| e.customer == id);
+        if referenced {
+            return Err(CustomerError::Unknown(anyhow!(
+                "customer {} is still referenced by bookings or waitlist entries",
+                id.0
+            )));
+        }
+        tables.customers.remove(&id);
+
+        Ok(())
+    }
+}
+
+/// Rejects a customer whose email address only differs by case from that of another
+/// customer, besides those being replaced.
+fn ensure_email_free(
+    tables: &Tables,
+    customer: &Customer,
+    replaced: &[&CustomerId],
+) -> Result<(), CustomerError> {
+    let email = customer.email.0.to_lowercase();
+    let taken = tables.customers.values().any(|other| {
+        other.id != customer.id
+            && !replaced.contains(&&other.id)
+            && other.email.0.to_lowercase() == email
+    });
+    if taken {
+        return Err(CustomerError::EmailTaken(customer.email.0.clone()));
+    }
+
+    Ok(())
+}
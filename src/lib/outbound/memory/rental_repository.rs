@@ -0,0 +1,79 @@
+use super::booking_repository::{reserve_inventory, units_reserved};
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::equipment::*;
+use crate::domain::booking::ports::RentalRepository;
+use crate::outbound::memory::Memory;
+use chrono::{DateTime, Utc};
+
+impl RentalRepository for Memory {
+    async fn find_booking_rentals(
+        &self,
+        booking_id: BookingId,
+    ) -> Result<BookingRentals, EquipmentError> {
+        let rentals = self
+            .read()
+            .rentals
+            .get(&booking_id)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(BookingRentals {
+            booking_id,
+            rentals,
+        })
+    }
+
+    async fn save_booking_rentals(
+        &self,
+        booking_rentals: &BookingRentals,
+    ) -> Result<(), EquipmentError> {
+        let mut tables = self.write().await;
+
+        let booking_id = &booking_rentals.booking_id;
+        let trip = tables
+            .bookings
+            .get(booking_id)
+            .and_then(|row| tables.trips.get(&row.booking.trip))
+            .ok_or_else(|| EquipmentError::BookingNotFound(booking_id.clone()))?;
+
+        reserve_inventory(
+            &tables,
+            booking_id,
+            trip.start_time,
+            trip.end_time,
+            &booking_rentals.rentals,
+            Utc::now(),
+        )?;
+
+        tables
+            .rentals
+            .insert(booking_id.clone(), booking_rentals.rentals.clone());
+
+        Ok(())
+    }
+
+    async fn find_equipment_availability(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<EquipmentAvailability, EquipmentError> {
+        let tables = self.read();
+        let now = Utc::now();
+
+        Ok(EquipmentAvailability {
+            start_time,
+            end_time,
+            available: tables
+                .equipment
+                .values()
+                .map(|equipment| {
+                    let reserved =
+                        units_reserved(&tables, &equipment.id, None, start_time, end_time, now);
+                    let available = (equipment.total_inventory as i64 - reserved).max(0);
+
+                    (equipment.id.clone(), available as i32)
+                })
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,267 @@
+use crate::domain::booking::models::trip::TripId;
+use crate::domain::scheduling::models::schedule::*;
+use crate::domain::scheduling::ports::ScheduleRepository;
+use crate::outbound::memory::{Memory, Tables, TripRow};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+impl ScheduleRepository for Memory {
+    async fn find_schedule(&self, id: ScheduleId) -> Result<Option<Schedule>, ScheduleError> {
+        Ok(self.read().schedules.get(&id).cloned())
+    }
+
+    async fn find_active_schedules(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Schedule>, ScheduleError> {
+        let today = now.date_naive();
+
+        Ok(self
+            .read()
+            .schedules
+            .values()
+            .filter(|s| s.cancelled_at.is_none())
+            .filter(|s| s.recurrence.ends_on.is_none_or(|ends_on| ends_on >= today))
+            .cloned()
+            .collect())
+    }
+
+    async fn save_schedule(&self, schedule: &Schedule) -> Result<(), ScheduleError> {
+        let mut tables = self.write().await;
+
+        if !tables.trip_kinds.contains_key(&schedule.trip_kind) {
+            return Err(ScheduleError::Unknown(anyhow!(
+                "schedule {} references missing trip kind {}",
+                schedule.id.0,
+                schedule.trip_kind.0
+            )));
+        }
+        if !tables.locations.contains_key(&schedule.location) {
+            return Err(ScheduleError::Unknown(anyhow!(
+                "schedule {} references missing location {}",
+                schedule.id.0,
+                schedule.location.0
+            )));
+        }
+
+        let trip_kind = tables
+            .schedules
+            .get(&schedule.id)
+            .map_or(&schedule.trip_kind, |existing| &existing.trip_kind)
+            .clone();
+        tables.schedules.insert(
+            schedule.id.clone(),
+            Schedule {
+                trip_kind,
+                ..schedule.clone()
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn save_occurrences(
+        &self,
+        schedule: &Schedule,
+        occurrences: &[Occurrence],
+    ) -> Result<u64, ScheduleError> {
+        let mut tables = self.write().await;
+
+        let booked = tables
+            .trips
+            .keys()
+            .filter(|trip| tables.has_bookings(trip))
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        let mut written = 0;
+        for occurrence in occurrences {
+            let key = (schedule.id.clone(), occurrence.scheduled_start);
+            if tables.schedule_exceptions.contains(&key) {
+                continue;
+            }
+
+            match tables
+                .trips
+                .values_mut()
+                .find(|trip| trip.occurrence.as_ref() == Some(&key))
+            {
+                Some(trip) if trip.detached || booked.contains(&trip.id) => {}
+                Some(trip) => {
+                    trip.location = schedule.location.clone();
+                    trip.start_time = occurrence.start_time;
+                    trip.end_time = occurrence.end_time;
+                    trip.max_participants = schedule.max_participants;
+                    trip.timezone = schedule.recurrence.timezone;
+                    written += 1;
+                }
+                None => {
+                    let id = TripId(Uuid::now_v7());
+                    tables.trips.insert(
+                        id.clone(),
+                        TripRow {
+                            id,
+                            kind: schedule.trip_kind.clone(),
+                            location: schedule.location.clone(),
+                            start_time: occurrence.start_time,
+                            end_time: occurrence.end_time,
+                            max_participants: schedule.max_participants,
+                            occurrence: Some(key),
+                            detached: false,
+                            timezone: schedule.recurrence.timezone,
+                        },
+                    );
+                    written += 1;
+                }
+            }
+        }
+
+        Ok(written)
+    }
+
+    async fn prune_occurrences(
+        &self,
+        id: &ScheduleId,
+        after: DateTime<Utc>,
+        keep: &[Occurrence],
+    ) -> Result<Vec<TripId>, ScheduleError> {
+        let mut tables = self.write().await;
+
+        let stale = upcoming_occurrences(&tables, id, after)
+            .into_iter()
+            .filter(|trip| !trip.detached)
+            .filter(|trip| {
+                trip.occurrence
+                    .as_ref()
+                    .is_some_and(|(_, start)| keep.iter().all(|o| o.scheduled_start != *start))
+            })
+            .map(|trip| trip.id)
+            .collect::<Vec<_>>();
+
+        delete_unbooked(&mut tables, stale)
+    }
+
+    async fn delete_upcoming_occurrences(
+        &self,
+        id: &ScheduleId,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<TripId>, ScheduleError> {
+        let mut tables = self.write().await;
+
+        let upcoming = upcoming_occurrences(&tables, id, after)
+            .into_iter()
+            .map(|trip| trip.id)
+            .collect::<Vec<_>>();
+
+        delete_unbooked(&mut tables, upcoming)
+    }
+
+    async fn save_detached_occurrence(
+        &self,
+        schedule: &Schedule,
+        occurrence: &Occurrence,
+    ) -> Result<TripId, ScheduleError> {
+        let mut tables = self.write().await;
+
+        let key = (schedule.id.clone(), occurrence.scheduled_start);
+        if let Some(trip) = tables
+            .trips
+            .values_mut()
+            .find(|trip| trip.occurrence.as_ref() == Some(&key))
+        {
+            trip.start_time = occurrence.start_time;
+            trip.end_time = occurrence.end_time;
+            trip.detached = true;
+
+            return Ok(trip.id.clone());
+        }
+
+        let id = TripId(Uuid::now_v7());
+        tables.trips.insert(
+            id.clone(),
+            TripRow {
+                id: id.clone(),
+                kind: schedule.trip_kind.clone(),
+                location: schedule.location.clone(),
+                start_time: occurrence.start_time,
+                end_time: occurrence.end_time,
+                max_participants: schedule.max_participants,
+                occurrence: Some(key),
+                detached: true,
+                timezone: schedule.recurrence.timezone,
+            },
+        );
+
+        Ok(id)
+    }
+
+    async fn cancel_occurrence(
+        &self,
+        id: &ScheduleId,
+        scheduled_start: DateTime<Utc>,
+    ) -> Result<(), ScheduleError> {
+        let mut tables = self.write().await;
+
+        let key = (id.clone(), scheduled_start);
+        let trip = tables
+            .trips
+            .values()
+            .find(|trip| trip.occurrence.as_ref() == Some(&key))
+            .map(|trip| trip.id.clone());
+
+        if let Some(trip) = trip {
+            if tables.has_bookings(&trip) {
+                return Err(ScheduleError::OccurrenceHasBookings(trip));
+            }
+            ensure_unreferenced(&tables, &trip)?;
+            tables.trips.remove(&trip);
+        }
+        tables.schedule_exceptions.insert(key);
+
+        Ok(())
+    }
+}
+
+/// Gets the trips materialized from a schedule's occurrences after `after`.
+fn upcoming_occurrences(tables: &Tables, id: &ScheduleId, after: DateTime<Utc>) -> Vec<TripRow> {
+    tables
+        .trips
+        .values()
+        .filter(|trip| {
+            trip.occurrence
+                .as_ref()
+                .is_some_and(|(schedule, start)| schedule == id && *start > after)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Deletes the given trips that have no bookings, and returns the ones that do.
+fn delete_unbooked(tables: &mut Tables, trips: Vec<TripId>) -> Result<Vec<TripId>, ScheduleError> {
+    let (booked, unbooked): (Vec<_>, Vec<_>) = trips
+        .into_iter()
+        .partition(|trip| tables.has_bookings(trip));
+
+    for trip in &unbooked {
+        ensure_unreferenced(tables, trip)?;
+    }
+    for trip in &unbooked {
+        tables.trips.remove(trip);
+    }
+
+    Ok(booked)
+}
+
+/// Rejects deleting a trip that customers are still waitlisted for.
+fn ensure_unreferenced(tables: &Tables, trip: &TripId) -> Result<(), ScheduleError> {
+    if tables.waitlist.values().any(|e| &e.trip == trip) {
+        return Err(ScheduleError::Unknown(anyhow!(
+            "trip {} is still referenced by waitlist entries",
+            trip.0
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::domain::booking::models::trip::*;
+use crate::domain::booking::ports::TripRepository;
+use crate::outbound::memory::Memory;
+
+impl TripRepository for Memory {
+    async fn find_trip(&self, id: TripId) -> Result<Option<Trip>, TripError> {
+        let tables = self.read();
+
+        Ok(tables.trips.get(&id).and_then(|row| tables.trip(row)))
+    }
+
+    async fn find_trips(&self, trip_filters: &TripFilters) -> Result<Vec<Trip>, TripError> {
+        if trip_filters.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let tables = self.read();
+        let mut trips = tables
+            .trips
+            .values()
+            .filter(|row| {
+                trip_filters.kind.as_ref().is_none_or(|k| &row.kind == k)
+                    && trip_filters
+                        .location
+                        .as_ref()
+                        .is_none_or(|l| &row.location == l)
+                    && trip_filters
+                        .date_range
+                        .is_none_or(|(start, end)| (start..=end).contains(&row.start_time))
+            })
+            .filter_map(|row| tables.trip(row))
+            .collect::<Vec<_>>();
+        trips.sort_by(|a, b| (a.start_time, &a.id).cmp(&(b.start_time, &b.id)));
+
+        Ok(trips)
+    }
+}
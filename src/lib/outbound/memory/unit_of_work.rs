@@ -0,0 +1,54 @@
+use crate::domain::booking::ports::UnitOfWork;
+use crate::outbound::memory::{Memory, Tables};
+use anyhow::anyhow;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::OwnedMutexGuard;
+
+/// [Unit] is an open unit of work, which holds the gate until it commits or is dropped.
+#[derive(Debug)]
+pub(super) struct Unit {
+    /// The tables the unit of work's copy replaces when it commits.
+    tables: Arc<RwLock<Tables>>,
+    gate: Mutex<Option<OwnedMutexGuard<()>>>,
+}
+
+impl UnitOfWork for Memory {
+    async fn begin(&self) -> anyhow::Result<Self> {
+        if self.unit.is_some() {
+            return Err(anyhow!("a unit of work is already open"));
+        }
+
+        let gate = self.gate.clone().lock_owned().await;
+        let copy = self.read().clone();
+
+        Ok(Self {
+            tables: Arc::new(RwLock::new(copy)),
+            gate: self.gate.clone(),
+            unit: Some(Arc::new(Unit {
+                tables: self.tables.clone(),
+                gate: Mutex::new(Some(gate)),
+            })),
+            #[cfg(test)]
+            failing_waiver_signatures: self.failing_waiver_signatures.clone(),
+        })
+    }
+
+    async fn commit(self) -> anyhow::Result<()> {
+        let Some(unit) = self.unit else {
+            return Ok(());
+        };
+
+        let gate = unit
+            .gate
+            .lock()
+            .expect("memory state poisoned")
+            .take()
+            .ok_or_else(|| anyhow!("the unit of work was already committed"))?;
+
+        let copy = self.tables.read().expect("memory state poisoned").clone();
+        *unit.tables.write().expect("memory state poisoned") = copy;
+        drop(gate);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::waiver::*;
+use crate::domain::booking::ports::WaiverRepository;
+use crate::outbound::memory::Memory;
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+
+impl WaiverRepository for Memory {
+    async fn find_current_waiver(
+        &self,
+        id: WaiverId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<Waiver>, WaiverError> {
+        Ok(self.read().waivers.get(&id).and_then(|versions| {
+            versions
+                .iter()
+                .filter(|w| w.effective_at <= at)
+                .max_by_key(|w| w.version)
+                .cloned()
+        }))
+    }
+
+    async fn find_latest_waiver(&self, id: WaiverId) -> Result<Option<Waiver>, WaiverError> {
+        Ok(self
+            .read()
+            .waivers
+            .get(&id)
+            .and_then(|versions| versions.iter().max_by_key(|w| w.version).cloned()))
+    }
+
+    async fn save_waiver(&self, waiver: &Waiver) -> Result<(), WaiverError> {
+        let mut tables = self.write().await;
+
+        let versions = tables.waivers.entry(waiver.id.clone()).or_default();
+        if versions.iter().any(|w| w.version == waiver.version) {
+            return Err(WaiverError::Unknown(anyhow!(
+                "version {} of waiver {} already exists",
+                waiver.version,
+                waiver.id.0
+            )));
+        }
+        versions.push(waiver.clone());
+        versions.sort_by_key(|w| w.version);
+
+        Ok(())
+    }
+
+    async fn save_waiver_signature(&self, signature: &WaiverSignature) -> Result<(), WaiverError> {
+        #[cfg(test)]
+        if self
+            .failing_waiver_signatures
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            return Err(WaiverError::Unknown(anyhow!(
+                "waiver signatures are failing"
+            )));
+        }
+        let mut tables = self.write().await;
+
+        if tables
+            .waiver_signatures
+            .iter()
+            .any(|s| s.id == signature.id)
+        {
+            return Err(WaiverError::Unknown(anyhow!(
+                "waiver signature {} already exists",
+                signature.id.0
+            )));
+        }
+        if !tables.participants.contains_key(&signature.participant) {
+            return Err(WaiverError::Unknown(anyhow!(
+                "waiver signature {} references missing participant {}",
+                signature.id.0,
+                signature.participant.0
+            )));
+        }
+        let version_exists = tables
+            .waivers
+            .get(&signature.waiver)
+            .is_some_and(|versions| versions.iter().any(|w| w.version == signature.version));
+        if !version_exists {
+            return Err(WaiverError::Unknown(anyhow!(
+                "waiver signature {} references missing version {} of waiver {}",
+                signature.id.0,
+                signature.version,
+                signature.waiver.0
+            )));
+        }
+        tables.waiver_signatures.push(signature.clone());
+
+        Ok(())
+    }
+
+    async fn find_waiver_signatures(
+        &self,
+        participants: &[ParticipantId],
+    ) -> Result<Vec<WaiverSignature>, WaiverError> {
+        Ok(self
+            .read()
+            .waiver_signatures
+            .iter()
+            .filter(|s| participants.contains(&s.participant))
+            .cloned()
+            .collect())
+    }
+}
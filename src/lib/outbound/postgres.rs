@@ -1,7 +1,14 @@
 //! Module [postgres] is an outbound adapter for a PostgreSQL relational database.
 
 mod booking_repository;
+mod customer_repository;
+mod rental_repository;
+mod schedule_repository;
+mod trip_repository;
+mod unit_of_work;
+mod waiver_repository;
 
+use crate::outbound::transaction::{self, SharedTransaction};
 use anyhow::Context;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
@@ -14,6 +21,8 @@ pub struct PgConfig<'cfg> {
 #[derive(Clone, Debug)]
 pub struct Postgres {
     pool: PgPool,
+    /// The open transaction of a unit of work, if this instance is bound to one.
+    transaction: Option<SharedTransaction<sqlx::Postgres>>,
 }
 
 impl Postgres {
@@ -31,7 +40,7 @@ impl Postgres {
     pub async fn from_config(config: PgConfig<'_>) -> anyhow::Result<Self> {
         let pool = PgPoolOptions::new()
             .max_connections(5)
-            .connect(&config.url)
+            .connect(config.url)
             .await
             .context("failed to connect to DB")?;
 
@@ -40,6 +49,201 @@ impl Postgres {
             .await
             .context("failed to run DB migrations")?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            transaction: None,
+        })
     }
+
+    /// Gets the connection of the open unit of work, or one from the pool outside of one.
+    async fn connection(&self) -> Result<transaction::Connection<'_, sqlx::Postgres>, sqlx::Error> {
+        transaction::connection(&self.pool, self.transaction.as_ref()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::booking::models::equipment::Equipment;
+    use crate::domain::booking::models::pricing::{Money, ParticipantCategory, PriceModifier};
+    use crate::domain::booking::models::trip::{Trip, TripKindId};
+    use crate::outbound::conformance::{booking_repository_conformance, Catalog};
+
+    impl Catalog for Postgres {
+        async fn insert_trip(&self, trip: &Trip) {
+            let kind = &trip.kind;
+            let eligibility = &kind.eligibility;
+            sqlx::query(
+                // language=postgresql
+                "INSERT INTO trip_kind (
+                    trip_kind_id,
+                    name,
+                    description,
+                    guided,
+                    meal_provided,
+                    max_participants,
+                    waiver_id,
+                    min_age,
+                    max_age,
+                    guardian_required_under,
+                    requirements
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                 ON CONFLICT (trip_kind_id)
+                 DO UPDATE SET max_participants = EXCLUDED.max_participants",
+            )
+            .bind(kind.id.0)
+            .bind(&kind.name)
+            .bind(&kind.description)
+            .bind(kind.guided)
+            .bind(kind.meal_provided)
+            .bind(kind.max_participants)
+            .bind(kind.waiver.as_ref().map(|w| w.0))
+            .bind(eligibility.min_age.map(|age| age as i32))
+            .bind(eligibility.max_age.map(|age| age as i32))
+            .bind(eligibility.guardian_required_under.map(|age| age as i32))
+            .bind(&eligibility.requirements)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+
+            for tier in &kind.cancellation_policy.tiers {
+                sqlx::query(
+                    // language=postgresql
+                    "INSERT INTO cancellation_tier (trip_kind_id, notice_minutes, refund_percent)
+                     VALUES ($1, $2, $3)
+                     ON CONFLICT DO NOTHING",
+                )
+                .bind(kind.id.0)
+                .bind(tier.notice.num_minutes() as i32)
+                .bind(tier.percent)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+            }
+
+            sqlx::query(
+                // language=postgresql
+                "INSERT INTO location (location_id, name) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            )
+            .bind(trip.location.0)
+            .bind(trip.location.0.to_string())
+            .execute(&self.pool)
+            .await
+            .unwrap();
+
+            sqlx::query(
+                // language=postgresql
+                "INSERT INTO trip (
+                    trip_id,
+                    trip_kind_id,
+                    location_id,
+                    start_time,
+                    end_time,
+                    max_participants,
+                    timezone
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (trip_id)
+                 DO UPDATE SET max_participants = EXCLUDED.max_participants",
+            )
+            .bind(trip.id.0)
+            .bind(kind.id.0)
+            .bind(trip.location.0)
+            .bind(trip.start_time)
+            .bind(trip.end_time)
+            .bind(trip.max_participants)
+            .bind(trip.timezone.name())
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+
+        async fn insert_equipment(&self, equipment: &Equipment) {
+            sqlx::query(
+                // language=postgresql
+                "INSERT INTO equipment (
+                    equipment_id,
+                    name,
+                    description,
+                    total_inventory,
+                    rental_price
+                 )
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (equipment_id)
+                 DO UPDATE SET
+                    total_inventory = EXCLUDED.total_inventory,
+                    rental_price = EXCLUDED.rental_price",
+            )
+            .bind(equipment.id.0)
+            .bind(&equipment.name.0)
+            .bind(&equipment.description.0)
+            .bind(equipment.total_inventory)
+            .bind(equipment.rental_price.map(|price| price.0))
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+
+        async fn insert_base_price(
+            &self,
+            kind: &TripKindId,
+            category: ParticipantCategory,
+            price: Money,
+        ) {
+            sqlx::query(
+                // language=postgresql
+                "INSERT INTO trip_kind_price (trip_kind_id, category, price)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (trip_kind_id, category)
+                 DO UPDATE SET price = EXCLUDED.price",
+            )
+            .bind(kind.0)
+            .bind(format!("{category:?}").to_lowercase())
+            .bind(price.0)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+
+        async fn insert_price_modifier(&self, modifier: &PriceModifier) {
+            sqlx::query(
+                // language=postgresql
+                "INSERT INTO price_modifier (
+                    price_modifier_id,
+                    name,
+                    trip_kind_id,
+                    months,
+                    weekdays,
+                    percent
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(modifier.id.0)
+            .bind(&modifier.name)
+            .bind(modifier.trip_kind.as_ref().map(|kind| kind.0))
+            .bind(
+                modifier
+                    .months
+                    .iter()
+                    .map(|m| *m as i32)
+                    .collect::<Vec<_>>(),
+            )
+            .bind(
+                modifier
+                    .weekdays
+                    .iter()
+                    .map(|day| day.number_from_monday() as i32)
+                    .collect::<Vec<_>>(),
+            )
+            .bind(modifier.percent)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    // Each test gets a disposable database, created and migrated by `sqlx::test` on the
+    // server at DATABASE_URL.
+    booking_repository_conformance!(#[sqlx::test] (pool: PgPool) => Postgres { pool, transaction: None });
 }
@@ -1,62 +1,59 @@
 use crate::domain::booking::models::booking::*;
 use crate::domain::booking::models::customer::*;
 use crate::domain::booking::models::equipment::*;
+use crate::domain::booking::models::payment::*;
+use crate::domain::booking::models::pricing::*;
 use crate::domain::booking::models::trip::*;
+use crate::domain::booking::models::waitlist::*;
 use crate::domain::booking::models::waiver::*;
 use crate::domain::booking::ports::BookingRepository;
 use crate::outbound::postgres::Postgres;
-use chrono::{DateTime, NaiveDate, Utc};
-use sqlx::{query, query_as, FromRow, QueryBuilder};
+use anyhow::anyhow;
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use sqlx::{query, query_as, Connection, FromRow, PgConnection, QueryBuilder};
 use std::collections::HashMap;
 use uuid::Uuid;
 
 impl BookingRepository for Postgres {
     async fn find_booking(&self, id: BookingId) -> Result<Option<Booking>, BookingError> {
-        let query = query_as!(
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
             BookingDto,
             // language=postgresql
             "SELECT
                 booking_id,
+                reference,
                 customer_id,
                 trip_id,
-                participant_id,
-                name,
-                dob,
-                notes,
-                waiver_id
+                status,
+                hold_expires_at,
+                checked_in_at,
+                cancelled_at,
+                cancellation_reason,
+                refund_amount,
+                waitlist_entry_id,
+                overbooked
              FROM booking
-                JOIN booking_participant USING (booking_id)
-                JOIN participant USING (participant_id)
-                LEFT JOIN participant_waiver USING (participant_id)
              WHERE booking_id = $1",
             id.0
-        );
-
-        let results = query.fetch_all(&self.pool).await?;
-        let Some(first) = results.first() else {
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+        let Some(dto) = result else {
             return Ok(None);
         };
 
-        Ok(Some(Booking {
-            id: BookingId(first.booking_id),
-            customer: CustomerId(first.customer_id),
-            trip: TripId(first.trip_id),
-            participants: results
-                .into_iter()
-                .map(|r| Participant {
-                    id: ParticipantId(r.participant_id),
-                    name: r.name,
-                    dob: r.dob,
-                    notes: r.notes,
-                    waiver: r.waiver_id.map(|id| WaiverId(id)),
-                })
-                .collect(),
-        }))
+        let mut bookings = with_participants(&mut conn, vec![dto]).await?;
+
+        Ok(bookings.pop())
     }
 
     async fn find_bookings(&self, filters: &BookingFilters) -> Result<Vec<Booking>, BookingError> {
+        let mut conn = self.connection().await?;
+
+        // The service rejects empty filters, and listing every booking here never helps.
         if filters.is_empty() {
-            // TODO: should actually return an error
             return Ok(vec![]);
         }
 
@@ -64,22 +61,26 @@ impl BookingRepository for Postgres {
         let query = "
             SELECT
                 booking_id,
+                reference,
                 customer_id,
                 trip_id,
-                participant_id,
-                name,
-                dob,
-                notes,
-                waiver_id
+                status,
+                hold_expires_at,
+                checked_in_at,
+                cancelled_at,
+                cancellation_reason,
+                refund_amount,
+                waitlist_entry_id,
+                overbooked
             FROM booking
-               JOIN booking_participant USING (booking_id)
-               JOIN participant USING (participant_id)
-               LEFT JOIN participant_waiver USING (participant_id)
             WHERE TRUE
         ";
 
         let mut qb = QueryBuilder::<sqlx::Postgres>::new(query);
 
+        if let Some(BookingReference(reference)) = &filters.reference {
+            qb.push(" AND reference = ").push_bind(reference);
+        }
         if let Some(CustomerId(id)) = filters.customer {
             qb.push(" AND customer_id = ").push_bind(id);
         }
@@ -87,62 +88,92 @@ impl BookingRepository for Postgres {
             qb.push(" AND trip_id = ").push_bind(id);
         }
         if let Some(ParticipantId(id)) = filters.participant {
-            qb.push(" AND participant_id = ").push_bind(id);
+            qb.push(
+                " AND EXISTS (
+                    SELECT 1 FROM booking_participant
+                    WHERE booking_participant.booking_id = booking.booking_id
+                      AND participant_id = ",
+            )
+            .push_bind(id)
+            .push(")");
+        }
+        if let Some(status) = filters.status {
+            qb.push(" AND status = ").push_bind(status.as_str());
         }
 
         qb.push(" ORDER BY booking_id ");
 
         let result = qb
             .build_query_as::<BookingDto>()
-            .fetch_all(&self.pool)
+            .fetch_all(&mut *conn)
             .await?;
 
-        let mut bookings = HashMap::<BookingId, Booking>::new();
-
-        for dto in result {
-            let id = BookingId(dto.booking_id);
-            let participant = Participant {
-                id: ParticipantId(dto.participant_id),
-                name: dto.name,
-                dob: dto.dob,
-                notes: dto.notes,
-                waiver: dto.waiver_id.map(WaiverId),
-            };
-
-            if let Some(booking) = bookings.get_mut(&id) {
-                booking.participants.push(participant);
-            } else {
-                bookings.insert(
-                    id.clone(),
-                    Booking {
-                        id,
-                        customer: CustomerId(dto.customer_id),
-                        trip: TripId(dto.trip_id),
-                        participants: vec![participant],
-                    },
-                );
-            }
-        }
-
-        Ok(bookings.into_values().collect())
+        with_participants(&mut conn, result).await
     }
 
-    async fn save_booking(&self, booking: &Booking) -> Result<(), BookingError> {
+    async fn save_booking(
+        &self,
+        booking: &Booking,
+        overbooking: OverbookingPolicy,
+    ) -> Result<bool, BookingError> {
+        let mut conn = self.connection().await?;
+
         let (ids, names, dobs, notes) = participants_to_tuples(&booking.participants);
 
-        let mut txn = self.pool.begin().await?;
+        let mut txn = conn.begin().await?;
+
+        let overbooked = reserve_seats(&mut txn, booking, overbooking).await? || booking.overbooked;
+
         for command in [
             query!(
                 // language=postgresql
-                "INSERT INTO booking (booking_id, customer_id, trip_id)
-                 VALUES ($1, $2, $3)
+                "INSERT INTO booking (
+                    booking_id,
+                    reference,
+                    customer_id,
+                    trip_id,
+                    status,
+                    hold_expires_at,
+                    checked_in_at,
+                    cancelled_at,
+                    cancellation_reason,
+                    refund_amount,
+                    waitlist_entry_id,
+                    overbooked
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
                  ON CONFLICT (booking_id)
                  DO UPDATE SET
+                    reference = EXCLUDED.reference,
                     customer_id = EXCLUDED.customer_id,
-                    trip_id = EXCLUDED.trip_id",
+                    trip_id = EXCLUDED.trip_id,
+                    status = EXCLUDED.status,
+                    hold_expires_at = EXCLUDED.hold_expires_at,
+                    checked_in_at = EXCLUDED.checked_in_at,
+                    cancelled_at = EXCLUDED.cancelled_at,
+                    cancellation_reason = EXCLUDED.cancellation_reason,
+                    refund_amount = EXCLUDED.refund_amount,
+                    waitlist_entry_id = EXCLUDED.waitlist_entry_id,
+                    overbooked = EXCLUDED.overbooked",
                 booking.id.0,
+                booking.reference.0,
                 booking.customer.0,
-                booking.trip.0
+                booking.trip.0,
+                booking.status.as_str(),
+                booking.hold_expires_at,
+                booking.checked_in_at,
+                booking.cancellation.as_ref().map(|c| c.cancelled_at),
+                booking.cancellation.as_ref().map(|c| c.reason.as_str()),
+                booking.cancellation.as_ref().map(|c| c.refund.0),
+                booking.waitlist_entry.as_ref().map(|e| e.0),
+                overbooked,
+            ),
+            query!(
+                // language=postgresql
+                "UPDATE waitlist_entry
+                 SET status = 'accepted', offer_expires_at = NULL
+                 WHERE waitlist_entry_id = $1 AND status = 'offered'",
+                booking.waitlist_entry.as_ref().map(|e| e.0),
             ),
             query!(
                 // language=postgresql
@@ -165,8 +196,8 @@ impl BookingRepository for Postgres {
             ),
             query!(
                 // language=postgresql
-                "INSERT INTO booking_participant (booking_id, participant_id)
-                 SELECT * FROM UNNEST($1::UUID[], $2::UUID[])",
+                "INSERT INTO booking_participant (booking_id, participant_id, ordinal)
+                 SELECT * FROM UNNEST($1::UUID[], $2::UUID[]) WITH ORDINALITY",
                 &vec![booking.id.0; booking.participants.len()],
                 &booking
                     .participants
@@ -179,11 +210,180 @@ impl BookingRepository for Postgres {
         }
         txn.commit().await?;
 
+        Ok(overbooked)
+    }
+
+    async fn reschedule_booking(
+        &self,
+        booking: &Booking,
+        overbooking: OverbookingPolicy,
+    ) -> Result<bool, BookingError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let overbooked = reserve_seats(&mut txn, booking, overbooking).await? || booking.overbooked;
+
+        let window = query!(
+            // language=postgresql
+            "SELECT start_time, end_time FROM trip WHERE trip_id = $1",
+            booking.trip.0
+        )
+        .fetch_optional(&mut *txn)
+        .await?
+        .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+
+        let (equipment_ids, quantities): (Vec<_>, Vec<_>) = query!(
+            // language=postgresql
+            "SELECT equipment_id, SUM(quantity)::INT AS \"quantity!\"
+             FROM booking_equipment
+             WHERE booking_id = $1
+             GROUP BY equipment_id",
+            booking.id.0
+        )
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(|r| (r.equipment_id, r.quantity))
+        .unzip();
+
+        reserve_inventory(
+            &mut txn,
+            &booking.id,
+            window.start_time,
+            window.end_time,
+            &equipment_ids,
+            &quantities,
+        )
+        .await
+        .map_err(|e| match e {
+            EquipmentError::InsufficientInventory {
+                equipment,
+                requested,
+                available,
+            } => BookingError::InsufficientInventory {
+                equipment,
+                requested,
+                available,
+            },
+            e => BookingError::Unknown(e.into()),
+        })?;
+
+        let result = query!(
+            // language=postgresql
+            "UPDATE booking
+             SET trip_id = $2, overbooked = $4
+             WHERE booking_id = $1 AND status = $3",
+            booking.id.0,
+            booking.trip.0,
+            booking.status.as_str(),
+            overbooked,
+        )
+        .execute(&mut *txn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(BookingError::StatusChanged {
+                booking: booking.id.clone(),
+                from: booking.status,
+            });
+        }
+        txn.commit().await?;
+
+        Ok(overbooked)
+    }
+
+    async fn confirm_hold(&self, id: BookingId, at: DateTime<Utc>) -> Result<(), BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query!(
+            // language=postgresql
+            "UPDATE booking
+             SET status = 'confirmed', hold_expires_at = NULL
+             WHERE booking_id = $1
+               AND status = 'held'
+               AND (hold_expires_at IS NULL OR hold_expires_at > $2)",
+            id.0,
+            at,
+        )
+        .execute(&mut *conn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(BookingError::HoldExpired(id));
+        }
+
         Ok(())
     }
 
+    async fn save_booking_status(
+        &self,
+        booking: &Booking,
+        from: BookingStatus,
+    ) -> Result<(), BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query!(
+            // language=postgresql
+            "UPDATE booking
+             SET status = $3,
+                 hold_expires_at = $4,
+                 checked_in_at = $5,
+                 cancelled_at = $6,
+                 cancellation_reason = $7,
+                 refund_amount = $8
+             WHERE booking_id = $1 AND status = $2",
+            booking.id.0,
+            from.as_str(),
+            booking.status.as_str(),
+            booking.hold_expires_at,
+            booking.checked_in_at,
+            booking.cancellation.as_ref().map(|c| c.cancelled_at),
+            booking.cancellation.as_ref().map(|c| c.reason.as_str()),
+            booking.cancellation.as_ref().map(|c| c.refund.0),
+        )
+        .execute(&mut *conn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(BookingError::StatusChanged {
+                booking: booking.id.clone(),
+                from,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn cancel_expired_holds(
+        &self,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<BookingId>, BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query!(
+            // language=postgresql
+            "UPDATE booking
+             SET status = 'cancelled',
+                 hold_expires_at = NULL,
+                 cancelled_at = $1,
+                 cancellation_reason = $2,
+                 refund_amount = 0
+             WHERE status = 'held' AND hold_expires_at <= $1
+             RETURNING booking_id",
+            at,
+            HOLD_EXPIRED_REASON,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(result
+            .into_iter()
+            .map(|r| BookingId(r.booking_id))
+            .collect())
+    }
+
     async fn delete_booking(&self, id: BookingId) -> Result<(), BookingError> {
-        let mut txn = self.pool.begin().await?;
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
         for command in [
             query!(
                 // language=postgresql
@@ -208,150 +408,372 @@ impl BookingRepository for Postgres {
         Ok(())
     }
 
-    async fn find_customer(&self, id: CustomerId) -> Result<Option<Customer>, CustomerError> {
+    async fn find_waitlist_entry(
+        &self,
+        id: WaitlistEntryId,
+    ) -> Result<Option<WaitlistEntry>, WaitlistError> {
+        let mut conn = self.connection().await?;
+
         let result = query_as!(
-            CustomerDto,
+            WaitlistEntryDto,
             // language=postgresql
-            "SELECT * FROM customer WHERE customer_id = $1",
+            "SELECT * FROM waitlist_entry WHERE waitlist_entry_id = $1",
             id.0
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
-        Ok(result.map(Customer::from))
+        result.map(WaitlistEntry::try_from).transpose()
     }
 
-    async fn save_customer(&self, customer: &Customer) -> Result<(), CustomerError> {
-        query!(
+    async fn find_waitlist(&self, trip: TripId) -> Result<Vec<WaitlistEntry>, WaitlistError> {
+        let mut conn = self.connection().await?;
+
+        query_as!(
+            WaitlistEntryDto,
             // language=postgresql
-            "INSERT INTO customer (customer_id, name, email, phone) VALUES ($1, $2, $3, $4)",
-            customer.id.0,
-            customer.name.0,
-            customer.email.0,
-            customer.phone.0
+            "SELECT * FROM waitlist_entry
+             WHERE trip_id = $1
+             ORDER BY created_at, waitlist_entry_id",
+            trip.0
         )
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(WaitlistEntry::try_from)
+        .collect()
     }
 
-    async fn delete_customer(&self, id: CustomerId) -> Result<(), CustomerError> {
+    async fn save_waitlist_entry(&self, entry: &WaitlistEntry) -> Result<(), WaitlistError> {
+        let mut conn = self.connection().await?;
+
+        let offer_expires_at = match entry.status {
+            WaitlistStatus::Offered { expires_at } => Some(expires_at),
+            _ => None,
+        };
+
         query!(
             // language=postgresql
-            "DELETE FROM customer WHERE customer_id = $1",
-            id.0
+            "INSERT INTO waitlist_entry (
+                waitlist_entry_id,
+                customer_id,
+                trip_id,
+                party_size,
+                status,
+                offer_expires_at,
+                created_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (waitlist_entry_id)
+             DO UPDATE SET
+                party_size = EXCLUDED.party_size,
+                status = EXCLUDED.status,
+                offer_expires_at = EXCLUDED.offer_expires_at",
+            entry.id.0,
+            entry.customer.0,
+            entry.trip.0,
+            entry.party_size,
+            entry.status.as_str(),
+            offer_expires_at,
+            entry.created_at,
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         Ok(())
     }
 
-    async fn find_trip(&self, id: TripId) -> Result<Option<Trip>, TripError> {
-        let result = query_as!(
-            TripDto,
+    async fn find_waitlisted_trips(&self, at: DateTime<Utc>) -> Result<Vec<TripId>, WaitlistError> {
+        let mut conn = self.connection().await?;
+
+        let result = query!(
             // language=postgresql
-            "SELECT * 
-             FROM trip JOIN trip_kind USING (trip_kind_id)
-             WHERE trip_id = $1",
-            id.0
+            "SELECT DISTINCT trip_id
+             FROM waitlist_entry JOIN trip USING (trip_id)
+             WHERE trip.start_time > $1
+               AND (waitlist_entry.status = 'waiting'
+                    OR (waitlist_entry.status = 'offered' AND waitlist_entry.offer_expires_at <= $1))",
+            at
         )
-        .fetch_optional(&self.pool)
+        .fetch_all(&mut *conn)
         .await?;
 
-        Ok(result.map(Trip::from))
+        Ok(result.into_iter().map(|r| TripId(r.trip_id)).collect())
     }
 
-    async fn find_trips(&self, trip_filters: &TripFilters) -> Result<Vec<Trip>, TripError> {
-        if trip_filters.is_empty() {
-            // TODO: should return error
-            return Ok(vec![]);
-        }
+    async fn offer_waitlist_seats(
+        &self,
+        trip: TripId,
+        at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<WaitlistEntry>, WaitlistError> {
+        let mut conn = self.connection().await?;
 
-        // language=postgresql
-        let query = "
-            SELECT *
-            FROM trip JOIN trip_kind USING (trip_kind_id)
-            WHERE true
-        ";
+        let mut txn = conn.begin().await?;
 
-        let mut qb = QueryBuilder::<sqlx::Postgres>::new(query);
+        // Locking the trip row serializes offers with concurrent bookings for the same trip,
+        // so the same seats can't be both booked and offered.
+        let seats = query!(
+            // language=postgresql
+            "SELECT
+                COALESCE(trip.max_participants, trip_kind.max_participants) AS capacity,
+                (SELECT COUNT(*)
+                 FROM booking JOIN booking_participant USING (booking_id)
+                 WHERE booking.trip_id = trip.trip_id
+                   AND booking.status <> 'cancelled'
+                   AND NOT (booking.status = 'held' AND booking.hold_expires_at <= $2)) AS \"taken!\"
+             FROM trip JOIN trip_kind USING (trip_kind_id)
+             WHERE trip_id = $1
+             FOR UPDATE OF trip",
+            trip.0,
+            at,
+        )
+        .fetch_optional(&mut *txn)
+        .await?
+        .ok_or_else(|| WaitlistError::TripNotFound(trip.clone()))?;
 
-        if let Some(TripKindId(id)) = trip_filters.kind {
-            qb.push(" AND trip_kind_id = ").push_bind(id);
-        }
-        if let Some(LocationId(id)) = trip_filters.location {
-            qb.push(" AND location_id = ").push_bind(id);
-        }
-        if let Some((start, end)) = trip_filters.date_range {
-            qb.push(" AND start_time BETWEEN ")
-                .push_bind(start)
-                .push(" AND ")
-                .push_bind(end);
+        query!(
+            // language=postgresql
+            "UPDATE waitlist_entry
+             SET status = 'expired', offer_expires_at = NULL
+             WHERE trip_id = $1 AND status = 'offered' AND offer_expires_at <= $2",
+            trip.0,
+            at,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        let entries = query_as!(
+            WaitlistEntryDto,
+            // language=postgresql
+            "SELECT * FROM waitlist_entry
+             WHERE trip_id = $1 AND status IN ('waiting', 'offered')
+             ORDER BY created_at, waitlist_entry_id",
+            trip.0
+        )
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(WaitlistEntry::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let offered_seats = entries
+            .iter()
+            .filter(|e| e.status != WaitlistStatus::Waiting)
+            .map(|e| e.party_size as i64)
+            .sum::<i64>();
+        let mut available = seats
+            .capacity
+            .map(|capacity| (capacity as i64 - seats.taken - offered_seats).max(0));
+
+        let mut offers = vec![];
+        for entry in entries {
+            if entry.status != WaitlistStatus::Waiting {
+                continue;
+            }
+            match &mut available {
+                Some(available) if *available < entry.party_size as i64 => break,
+                Some(available) => *available -= entry.party_size as i64,
+                None => {}
+            }
+            offers.push(WaitlistEntry {
+                status: WaitlistStatus::Offered { expires_at },
+                ..entry
+            });
         }
 
-        let result = qb.build_query_as::<TripDto>().fetch_all(&self.pool).await?;
+        query!(
+            // language=postgresql
+            "UPDATE waitlist_entry
+             SET status = 'offered', offer_expires_at = $2
+             WHERE waitlist_entry_id = ANY($1)",
+            &offers.iter().map(|e| e.id.0).collect::<Vec<_>>(),
+            expires_at,
+        )
+        .execute(&mut *txn)
+        .await?;
+        txn.commit().await?;
 
-        Ok(result.into_iter().map(Trip::from).collect())
+        Ok(offers)
     }
 
-    async fn find_booking_rentals(
+    async fn find_participant(
         &self,
-        booking_id: BookingId,
-    ) -> Result<BookingRentals, EquipmentError> {
-        let result = query_as!(
-            RentalDto,
+        id: ParticipantId,
+    ) -> Result<Option<Participant>, BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query!(
             // language=postgresql
-            "SELECT equipment_id, quantity
-             FROM booking_equipment
-             WHERE booking_id = $1",
-            booking_id.0
+            "SELECT
+                participant_id,
+                name,
+                dob,
+                notes,
+                (SELECT waiver_id
+                 FROM participant_waiver
+                 WHERE participant_waiver.participant_id = participant.participant_id
+                 ORDER BY signed_at DESC
+                 LIMIT 1) AS waiver_id
+             FROM participant
+             WHERE participant_id = $1",
+            id.0
         )
-        .fetch_all(&self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
-        let mut rentals = HashMap::<EquipmentId, i32>::with_capacity(result.len());
-        for dto in result {
-            let equipment_id = EquipmentId(dto.equipment_id);
-            if let Some(count) = rentals.get_mut(&equipment_id) {
-                *count += dto.quantity;
-            } else {
-                rentals.insert(equipment_id, dto.quantity);
-            }
-        }
+        Ok(result.map(|r| Participant {
+            id: ParticipantId(r.participant_id),
+            name: r.name,
+            dob: r.dob,
+            notes: r.notes,
+            waiver: r.waiver_id.map(WaiverId),
+        }))
+    }
 
-        Ok(BookingRentals {
-            booking_id,
+    async fn find_price_list(&self, trip_kind: TripKindId) -> Result<PriceList, PricingError> {
+        let mut conn = self.connection().await?;
+
+        let base_prices = query!(
+            // language=postgresql
+            "SELECT category, price FROM trip_kind_price WHERE trip_kind_id = $1",
+            trip_kind.0
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|row| Ok((category_from_str(&row.category)?, Money(row.price))))
+        .collect::<Result<_, PricingError>>()?;
+
+        let rentals = query!(
+            // language=postgresql
+            "SELECT equipment_id, rental_price AS \"rental_price!\"
+             FROM equipment
+             WHERE rental_price IS NOT NULL"
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|row| (EquipmentId(row.equipment_id), Money(row.rental_price)))
+        .collect();
+
+        let modifiers = query_as!(
+            PriceModifierDto,
+            // language=postgresql
+            "SELECT price_modifier_id, name, trip_kind_id, months, weekdays, percent
+             FROM price_modifier
+             WHERE trip_kind_id IS NULL OR trip_kind_id = $1
+             ORDER BY name",
+            trip_kind.0
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(PriceModifier::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(PriceList {
+            base_prices,
             rentals,
+            modifiers,
         })
     }
 
-    async fn save_booking_rentals(
-        &self,
-        booking_rentals: &BookingRentals,
-    ) -> Result<(), EquipmentError> {
-        let (equipment_ids, quantities) = &rentals_to_tuples(&booking_rentals.rentals);
+    async fn find_payment(&self, id: PaymentId) -> Result<Option<Payment>, PaymentError> {
+        let mut conn = self.connection().await?;
 
-        let mut txn = self.pool.begin().await?;
-        for command in [
-            query!(
-                // language=postgresql
-                "DELETE FROM booking_equipment WHERE booking_id = $1",
-                booking_rentals.booking_id.0
-            ),
-            query!(
-                // language=postgresql
-                "INSERT INTO booking_equipment (booking_id, equipment_id, quantity)
-                 SELECT * FROM UNNEST($1::UUID[], $2::UUID[], $3::INT[])",
-                &vec![booking_rentals.booking_id.0; equipment_ids.len()],
-                equipment_ids,
-                quantities
-            ),
-        ] {
-            command.execute(&mut *txn).await?;
-        }
-        txn.commit().await?;
+        let result = query_as!(
+            PaymentDto,
+            // language=postgresql
+            "SELECT
+                payment_id,
+                booking_id,
+                kind,
+                parent_id,
+                amount,
+                declined,
+                decline_reason,
+                reference,
+                created_at
+             FROM payment
+             WHERE payment_id = $1",
+            id.0
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        result.map(Payment::try_from).transpose()
+    }
+
+    async fn find_payment_ledger(&self, booking: BookingId) -> Result<PaymentLedger, PaymentError> {
+        let mut conn = self.connection().await?;
+
+        let payments = query_as!(
+            PaymentDto,
+            // language=postgresql
+            "SELECT
+                payment_id,
+                booking_id,
+                kind,
+                parent_id,
+                amount,
+                declined,
+                decline_reason,
+                reference,
+                created_at
+             FROM payment
+             WHERE booking_id = $1
+             ORDER BY created_at, payment_id",
+            booking.0
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(Payment::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(PaymentLedger { booking, payments })
+    }
+
+    async fn save_payment(&self, payment: &Payment) -> Result<(), PaymentError> {
+        let mut conn = self.connection().await?;
+
+        let kind = match payment.kind {
+            PaymentKind::Authorization => "authorization",
+            PaymentKind::Capture => "capture",
+            PaymentKind::Refund => "refund",
+        };
+        let decline_reason = match &payment.status {
+            PaymentStatus::Succeeded => None,
+            PaymentStatus::Declined(reason) => Some(reason.as_str()),
+        };
+
+        query!(
+            // language=postgresql
+            "INSERT INTO payment (
+                payment_id,
+                booking_id,
+                kind,
+                parent_id,
+                amount,
+                declined,
+                decline_reason,
+                reference,
+                created_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            payment.id.0,
+            payment.booking.0,
+            kind,
+            payment.parent.as_ref().map(|p| p.0),
+            payment.amount.0,
+            decline_reason.is_some(),
+            decline_reason,
+            payment.reference.as_ref().map(|r| r.0.as_str()),
+            payment.created_at,
+        )
+        .execute(&mut *conn)
+        .await?;
 
         Ok(())
     }
@@ -360,67 +782,85 @@ impl BookingRepository for Postgres {
 #[derive(FromRow, Debug)]
 struct BookingDto {
     booking_id: Uuid,
+    reference: String,
     customer_id: Uuid,
     trip_id: Uuid,
-    participant_id: Uuid,
-    name: String,
-    dob: NaiveDate,
-    notes: String,
-    waiver_id: Option<Uuid>,
+    status: String,
+    hold_expires_at: Option<DateTime<Utc>>,
+    checked_in_at: Option<DateTime<Utc>>,
+    cancelled_at: Option<DateTime<Utc>>,
+    cancellation_reason: Option<String>,
+    refund_amount: Option<i64>,
+    waitlist_entry_id: Option<Uuid>,
+    overbooked: bool,
 }
 
-struct CustomerDto {
-    customer_id: Uuid,
-    name: String,
-    email: String,
-    phone: String,
-}
-
-impl From<CustomerDto> for Customer {
-    fn from(dto: CustomerDto) -> Self {
-        Self {
-            id: CustomerId(dto.customer_id),
-            name: CustomerName(dto.name),
-            email: EmailAddress(dto.email),
-            phone: PhoneNumber(dto.phone),
-        }
-    }
-}
-
-#[derive(FromRow, Debug)]
-struct TripDto {
-    trip_id: Uuid,
-    trip_kind_id: Uuid,
-    name: String,
-    description: String,
-    guided: bool,
-    meal_provided: bool,
-    location_id: Uuid,
-    start_time: DateTime<Utc>,
-    end_time: DateTime<Utc>,
-}
+/// Joins each booking's participants in, in the order they were saved.
+///
+/// Participants are read in a separate query, so bookings without any are still found, and
+/// each participant's waiver is the one they signed last.
+async fn with_participants(
+    conn: &mut PgConnection,
+    bookings: Vec<BookingDto>,
+) -> Result<Vec<Booking>, BookingError> {
+    let ids = bookings.iter().map(|b| b.booking_id).collect::<Vec<_>>();
+    let rows = query!(
+        // language=postgresql
+        "SELECT
+            booking_id,
+            participant_id,
+            name,
+            dob,
+            notes,
+            (SELECT waiver_id
+             FROM participant_waiver
+             WHERE participant_waiver.participant_id = participant.participant_id
+             ORDER BY signed_at DESC
+             LIMIT 1) AS waiver_id
+         FROM booking_participant JOIN participant USING (participant_id)
+         WHERE booking_id = ANY($1)
+         ORDER BY booking_id, ordinal, participant_id",
+        &ids
+    )
+    .fetch_all(&mut *conn)
+    .await?;
 
-impl From<TripDto> for Trip {
-    fn from(dto: TripDto) -> Self {
-        Self {
-            id: TripId(dto.trip_id),
-            kind: TripKind {
-                id: TripKindId(dto.trip_kind_id),
-                name: dto.name,
-                description: dto.description,
-                guided: dto.guided,
-                meal_provided: dto.meal_provided,
-            },
-            location: LocationId(dto.location_id),
-            start_time: dto.start_time,
-            end_time: dto.end_time,
-        }
+    let mut participants = HashMap::<Uuid, Vec<Participant>>::new();
+    for row in rows {
+        participants
+            .entry(row.booking_id)
+            .or_default()
+            .push(Participant {
+                id: ParticipantId(row.participant_id),
+                name: row.name,
+                dob: row.dob,
+                notes: row.notes,
+                waiver: row.waiver_id.map(WaiverId),
+            });
     }
-}
 
-struct RentalDto {
-    equipment_id: Uuid,
-    quantity: i32,
+    bookings
+        .into_iter()
+        .map(|dto| {
+            Ok(Booking {
+                id: BookingId(dto.booking_id),
+                reference: BookingReference(dto.reference),
+                customer: CustomerId(dto.customer_id),
+                trip: TripId(dto.trip_id),
+                participants: participants.remove(&dto.booking_id).unwrap_or_default(),
+                status: dto.status.parse()?,
+                hold_expires_at: dto.hold_expires_at,
+                checked_in_at: dto.checked_in_at,
+                cancellation: dto.cancelled_at.map(|cancelled_at| Cancellation {
+                    reason: dto.cancellation_reason.unwrap_or_default(),
+                    cancelled_at,
+                    refund: Money(dto.refund_amount.unwrap_or_default()),
+                }),
+                waitlist_entry: dto.waitlist_entry_id.map(WaitlistEntryId),
+                overbooked: dto.overbooked,
+            })
+        })
+        .collect()
 }
 
 impl From<sqlx::Error> for BookingError {
@@ -429,26 +869,335 @@ impl From<sqlx::Error> for BookingError {
     }
 }
 
-impl From<sqlx::Error> for CustomerError {
+impl From<sqlx::Error> for PaymentError {
     fn from(error: sqlx::Error) -> Self {
         Self::Unknown(error.into())
     }
 }
 
-impl From<sqlx::Error> for TripError {
+impl From<sqlx::Error> for WaitlistError {
     fn from(error: sqlx::Error) -> Self {
         Self::Unknown(error.into())
     }
 }
 
-impl From<sqlx::Error> for EquipmentError {
+impl From<sqlx::Error> for PricingError {
     fn from(error: sqlx::Error) -> Self {
         Self::Unknown(error.into())
     }
 }
 
+struct PaymentDto {
+    payment_id: Uuid,
+    booking_id: Uuid,
+    kind: String,
+    parent_id: Option<Uuid>,
+    amount: i64,
+    declined: bool,
+    decline_reason: Option<String>,
+    reference: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PaymentDto> for Payment {
+    type Error = PaymentError;
+
+    fn try_from(dto: PaymentDto) -> Result<Self, Self::Error> {
+        let kind = match dto.kind.as_str() {
+            "authorization" => PaymentKind::Authorization,
+            "capture" => PaymentKind::Capture,
+            "refund" => PaymentKind::Refund,
+            other => {
+                return Err(PaymentError::Unknown(anyhow!(
+                    "invalid stored payment kind {other}"
+                )))
+            }
+        };
+        let status = if dto.declined {
+            PaymentStatus::Declined(dto.decline_reason.unwrap_or_default())
+        } else {
+            PaymentStatus::Succeeded
+        };
+
+        Ok(Self {
+            id: PaymentId(dto.payment_id),
+            booking: BookingId(dto.booking_id),
+            kind,
+            parent: dto.parent_id.map(PaymentId),
+            amount: Money(dto.amount),
+            status,
+            reference: dto.reference.map(ProviderReference),
+            created_at: dto.created_at,
+        })
+    }
+}
+
+struct WaitlistEntryDto {
+    waitlist_entry_id: Uuid,
+    customer_id: Uuid,
+    trip_id: Uuid,
+    party_size: i32,
+    status: String,
+    offer_expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<WaitlistEntryDto> for WaitlistEntry {
+    type Error = WaitlistError;
+
+    fn try_from(dto: WaitlistEntryDto) -> Result<Self, Self::Error> {
+        let status = match (dto.status.as_str(), dto.offer_expires_at) {
+            ("waiting", _) => WaitlistStatus::Waiting,
+            ("offered", Some(expires_at)) => WaitlistStatus::Offered { expires_at },
+            ("accepted", _) => WaitlistStatus::Accepted,
+            ("expired", _) => WaitlistStatus::Expired,
+            ("withdrawn", _) => WaitlistStatus::Withdrawn,
+            (other, _) => {
+                return Err(WaitlistError::Unknown(anyhow!(
+                    "invalid stored waitlist status {other}"
+                )))
+            }
+        };
+
+        Ok(Self {
+            id: WaitlistEntryId(dto.waitlist_entry_id),
+            customer: CustomerId(dto.customer_id),
+            trip: TripId(dto.trip_id),
+            party_size: dto.party_size,
+            status,
+            created_at: dto.created_at,
+        })
+    }
+}
+
+struct PriceModifierDto {
+    price_modifier_id: Uuid,
+    name: String,
+    trip_kind_id: Option<Uuid>,
+    months: Vec<i32>,
+    weekdays: Vec<i32>,
+    percent: i32,
+}
+
+impl TryFrom<PriceModifierDto> for PriceModifier {
+    type Error = PricingError;
+
+    fn try_from(dto: PriceModifierDto) -> Result<Self, Self::Error> {
+        let weekdays = dto
+            .weekdays
+            .into_iter()
+            .map(|day| {
+                u8::try_from(day - 1)
+                    .ok()
+                    .and_then(|day| Weekday::try_from(day).ok())
+                    .ok_or_else(|| PricingError::Unknown(anyhow!("invalid stored weekday {day}")))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            id: PriceModifierId(dto.price_modifier_id),
+            name: dto.name,
+            trip_kind: dto.trip_kind_id.map(TripKindId),
+            months: dto.months.into_iter().map(|m| m as u32).collect(),
+            weekdays,
+            percent: dto.percent,
+        })
+    }
+}
+
+fn category_from_str(category: &str) -> Result<ParticipantCategory, PricingError> {
+    match category {
+        "child" => Ok(ParticipantCategory::Child),
+        "adult" => Ok(ParticipantCategory::Adult),
+        "senior" => Ok(ParticipantCategory::Senior),
+        other => Err(PricingError::Unknown(anyhow!(
+            "invalid stored participant category {other}"
+        ))),
+    }
+}
+
+/// Locks a booking's trip and checks it has room for the booking's participants, applying
+/// the [OverbookingPolicy] if it doesn't, and that none of them are already on another active
+/// booking of the trip. Seats the booking already holds on the trip are kept.
+///
+/// Returns whether the booking was flagged as overbooked.
+///
+/// Locking the trip row serializes concurrent bookings for the same trip,
+/// so two bookings can't both take the last seat, or book the same participant.
+pub(super) async fn reserve_seats(
+    conn: &mut PgConnection,
+    booking: &Booking,
+    overbooking: OverbookingPolicy,
+) -> Result<bool, BookingError> {
+    query!(
+        // language=postgresql
+        "SELECT trip_id FROM trip WHERE trip_id = $1 FOR UPDATE",
+        booking.trip.0,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let others = query!(
+        // language=postgresql
+        "SELECT booking_id, participant_id, name, dob, notes
+         FROM booking
+             JOIN booking_participant USING (booking_id)
+             JOIN participant USING (participant_id)
+         WHERE booking.trip_id = $1
+           AND booking.booking_id <> $2
+           AND booking.status <> 'cancelled'
+           AND NOT (booking.status = 'held' AND booking.hold_expires_at <= now())",
+        booking.trip.0,
+        booking.id.0,
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| {
+        let participant = Participant {
+            id: ParticipantId(row.participant_id),
+            name: row.name,
+            dob: row.dob,
+            notes: row.notes,
+            waiver: None,
+        };
+        (BookingId(row.booking_id), participant)
+    })
+    .collect::<Vec<_>>();
+    booking.ensure_not_double_booked(others.iter().map(|(id, p)| (id, p)))?;
+
+    // The seats are counted in a new statement, once the lock is held, so the count sees
+    // bookings committed while waiting for it.
+    let seats = query!(
+        // language=postgresql
+        "SELECT
+            COALESCE(trip.max_participants, trip_kind.max_participants) AS capacity,
+            (SELECT COUNT(*)
+             FROM booking JOIN booking_participant USING (booking_id)
+             WHERE booking.trip_id = trip.trip_id
+               AND booking.booking_id <> $2
+               AND booking.status <> 'cancelled'
+               AND NOT (booking.status = 'held' AND booking.hold_expires_at <= now())) AS \"taken!\",
+            (SELECT COALESCE(SUM(party_size), 0)
+             FROM waitlist_entry
+             WHERE waitlist_entry.trip_id = trip.trip_id
+               AND waitlist_entry.waitlist_entry_id IS DISTINCT FROM $3
+               AND waitlist_entry.status = 'offered'
+               AND waitlist_entry.offer_expires_at > now()) AS \"offered!\",
+            (SELECT COUNT(*)
+             FROM booking_participant JOIN booking USING (booking_id)
+             WHERE booking_participant.booking_id = $2
+               AND booking.trip_id = trip.trip_id) AS \"current!\"
+         FROM trip JOIN trip_kind USING (trip_kind_id)
+         WHERE trip_id = $1",
+        booking.trip.0,
+        booking.id.0,
+        booking.waitlist_entry.as_ref().map(|e| e.0),
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if let Some(capacity) = seats.capacity {
+        let requested = booking.participants.len() as i64;
+        let taken = seats.taken + seats.offered;
+        let available = (capacity as i64 - taken).max(0);
+        if requested > available && requested > seats.current {
+            match overbooking {
+                OverbookingPolicy::Reject => {
+                    return Err(BookingError::TripFull {
+                        trip: booking.trip.clone(),
+                        capacity,
+                        available: available as i32,
+                    });
+                }
+                OverbookingPolicy::Flag => {
+                    tracing::warn!(
+                        trip_id = %booking.trip.0,
+                        booking_id = %booking.id.0,
+                        capacity,
+                        booked = taken + requested,
+                        "trip is overbooked"
+                    );
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Locks the given equipment and checks enough of it is left for a booking's rentals across
+/// all trips overlapping `start_time..end_time`, besides what the booking itself rents.
+///
+/// Locking the equipment rows serializes concurrent rentals of the same equipment,
+/// so two bookings can't both take the last unit.
+pub(super) async fn reserve_inventory(
+    conn: &mut PgConnection,
+    booking: &BookingId,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    equipment_ids: &[Uuid],
+    quantities: &[i32],
+) -> Result<(), EquipmentError> {
+    query!(
+        // language=postgresql
+        "SELECT equipment_id
+         FROM equipment
+         WHERE equipment_id = ANY($1)
+         ORDER BY equipment_id
+         FOR UPDATE",
+        equipment_ids,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // As in [reserve_seats], the rentals are summed in a new statement once the locks are held.
+    let stock = query!(
+        // language=postgresql
+        "SELECT
+            equipment_id,
+            total_inventory,
+            (SELECT COALESCE(SUM(quantity), 0)
+             FROM booking_equipment
+                JOIN booking USING (booking_id)
+                JOIN trip USING (trip_id)
+             WHERE booking_equipment.equipment_id = equipment.equipment_id
+               AND booking_equipment.booking_id <> $2
+               AND booking.status <> 'cancelled'
+               AND NOT (booking.status = 'held' AND booking.hold_expires_at <= now())
+               AND trip.start_time < $4
+               AND trip.end_time > $3) AS \"reserved!\"
+         FROM equipment
+         WHERE equipment_id = ANY($1)",
+        equipment_ids,
+        booking.0,
+        start_time,
+        end_time,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for (equipment_id, quantity) in equipment_ids.iter().zip(quantities) {
+        let Some(item) = stock.iter().find(|s| s.equipment_id == *equipment_id) else {
+            return Err(EquipmentError::NotFound(EquipmentId(*equipment_id)));
+        };
+
+        let available = (item.total_inventory as i64 - item.reserved).max(0) as i32;
+        if *quantity > available {
+            return Err(EquipmentError::InsufficientInventory {
+                equipment: EquipmentId(*equipment_id),
+                requested: *quantity,
+                available,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 fn participants_to_tuples(
-    participants: &Vec<Participant>,
+    participants: &[Participant],
 ) -> (Vec<Uuid>, Vec<String>, Vec<NaiveDate>, Vec<String>) {
     participants.iter().fold(
         (vec![], vec![], vec![], vec![]),
@@ -462,15 +1211,3 @@ fn participants_to_tuples(
         },
     )
 }
-
-fn rentals_to_tuples(rentals: &HashMap<EquipmentId, i32>) -> (Vec<Uuid>, Vec<i32>) {
-    rentals.iter().fold(
-        (vec![], vec![]),
-        |(mut equipment_ids, mut quantities), (equipment_id, quantity)| {
-            equipment_ids.push(equipment_id.0);
-            quantities.push(*quantity);
-
-            (equipment_ids, quantities)
-        },
-    )
-}
@@ -0,0 +1,484 @@
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::customer::*;
+use crate::domain::booking::models::waitlist::*;
+use crate::domain::booking::ports::CustomerRepository;
+use crate::outbound::postgres::Postgres;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, Connection};
+use uuid::Uuid;
+
+/// The unique index on customer emails, ignoring case.
+const CUSTOMER_EMAIL_KEY: &str = "customer_email_lower_key";
+
+impl CustomerRepository for Postgres {
+    async fn find_customer(&self, id: CustomerId) -> Result<Option<Customer>, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            CustomerDto,
+            // language=postgresql
+            "SELECT * FROM customer WHERE customer_id = $1",
+            id.0
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(Customer::from))
+    }
+
+    async fn find_customers(&self, page: Page) -> Result<Vec<Customer>, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            CustomerDto,
+            // language=postgresql
+            "SELECT * FROM customer ORDER BY customer_id LIMIT $1 OFFSET $2",
+            page.limit,
+            page.offset,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(result.into_iter().map(Customer::from).collect())
+    }
+
+    async fn save_customer(&self, customer: &Customer) -> Result<(), CustomerError> {
+        let mut conn = self.connection().await?;
+
+        query!(
+            // language=postgresql
+            "INSERT INTO customer (customer_id, name, email, phone)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (customer_id)
+             DO UPDATE SET
+                name = EXCLUDED.name,
+                email = EXCLUDED.email,
+                phone = EXCLUDED.phone",
+            customer.id.0,
+            customer.name.0,
+            customer.email.0,
+            customer.phone.0
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.constraint() == Some(CUSTOMER_EMAIL_KEY) => {
+                CustomerError::EmailTaken(customer.email.0.clone())
+            }
+            e => e.into(),
+        })?;
+
+        Ok(())
+    }
+
+    async fn search_customers(
+        &self,
+        search: &CustomerSearch,
+    ) -> Result<CustomerSearchResults, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let pattern = like_pattern(&search.query);
+        let phone_pattern = search.phone_digits.as_deref().map(like_pattern);
+
+        let customers = query_as!(
+            CustomerDto,
+            // language=postgresql
+            "SELECT customer_id, name, email, phone
+             FROM customer
+             WHERE $1 <% name
+                OR name ILIKE $2
+                OR lower($1) <% lower(email)
+                OR lower(email) LIKE lower($2)
+                OR phone LIKE $3
+             ORDER BY
+                GREATEST(
+                    word_similarity($1, name),
+                    word_similarity(lower($1), lower(email)),
+                    CASE WHEN phone LIKE $3 THEN 1 ELSE 0 END
+                ) DESC,
+                name,
+                customer_id
+             LIMIT $4 OFFSET $5",
+            search.query,
+            pattern,
+            phone_pattern,
+            search.page.limit,
+            search.page.offset
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let total = query!(
+            // language=postgresql
+            r#"SELECT COUNT(*) AS "count!"
+               FROM customer
+               WHERE $1 <% name
+                  OR name ILIKE $2
+                  OR lower($1) <% lower(email)
+                  OR lower(email) LIKE lower($2)
+                  OR phone LIKE $3"#,
+            search.query,
+            pattern,
+            phone_pattern
+        )
+        .fetch_one(&mut *conn)
+        .await?
+        .count;
+
+        Ok(CustomerSearchResults {
+            customers: customers.into_iter().map(Customer::from).collect(),
+            total,
+        })
+    }
+
+    async fn find_duplicate_customers(
+        &self,
+        page: Page,
+    ) -> Result<Vec<DuplicateCandidate>, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        // Pairs are found separately for each reason, so each join can use its index: the
+        // phone index for equal phones, and the name trigram index for similar names.
+        let rows = query_as!(
+            DuplicateCandidateDto,
+            // language=postgresql
+            r#"WITH pair AS (
+                SELECT a.customer_id AS first_id, b.customer_id AS second_id
+                FROM customer a
+                JOIN customer b ON b.phone = a.phone AND a.customer_id < b.customer_id
+                UNION
+                SELECT a.customer_id, b.customer_id
+                FROM customer a
+                JOIN customer b ON b.name % a.name AND a.customer_id < b.customer_id
+             )
+             SELECT
+                a.customer_id AS first_id,
+                a.name AS first_name,
+                a.email AS first_email,
+                a.phone AS first_phone,
+                b.customer_id AS second_id,
+                b.name AS second_name,
+                b.email AS second_email,
+                b.phone AS second_phone,
+                a.phone = b.phone AS "same_phone!",
+                a.name % b.name AS "similar_name!",
+                lower(split_part(a.email, '@', 1)) = lower(split_part(b.email, '@', 1))
+                    AS "same_email_user!"
+             FROM pair
+             JOIN customer a ON a.customer_id = pair.first_id
+             JOIN customer b ON b.customer_id = pair.second_id
+             ORDER BY
+                (a.phone = b.phone)::int
+                    + (a.name % b.name)::int
+                    + (lower(split_part(a.email, '@', 1)) = lower(split_part(b.email, '@', 1)))::int
+                    DESC,
+                similarity(a.name, b.name) DESC,
+                a.customer_id,
+                b.customer_id
+             LIMIT $1 OFFSET $2"#,
+            page.limit,
+            page.offset
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(rows.into_iter().map(DuplicateCandidate::from).collect())
+    }
+
+    async fn merge_customers(
+        &self,
+        request: &MergeCustomersRequest,
+    ) -> Result<CustomerMerge, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        // Locking both customers (in a consistent order) keeps concurrent merges or edits
+        // of either from interleaving with this one.
+        let customers = query_as!(
+            CustomerDto,
+            // language=postgresql
+            "SELECT * FROM customer
+             WHERE customer_id IN ($1, $2)
+             ORDER BY customer_id
+             FOR UPDATE",
+            request.survivor.0,
+            request.merged.0
+        )
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(Customer::from)
+        .collect::<Vec<_>>();
+
+        let before = customers
+            .iter()
+            .find(|c| c.id == request.survivor)
+            .cloned()
+            .ok_or_else(|| CustomerError::NotFound(request.survivor.clone()))?;
+        let merged = customers
+            .into_iter()
+            .find(|c| c.id == request.merged)
+            .ok_or_else(|| CustomerError::NotFound(request.merged.clone()))?;
+        let survivor = before.clone().absorb(&merged, request);
+
+        let mut bookings = query!(
+            // language=postgresql
+            "UPDATE booking SET customer_id = $1 WHERE customer_id = $2 RETURNING booking_id",
+            survivor.id.0,
+            merged.id.0
+        )
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(|row| BookingId(row.booking_id))
+        .collect::<Vec<_>>();
+        bookings.sort();
+
+        let mut waitlist_entries = query!(
+            // language=postgresql
+            "UPDATE waitlist_entry SET customer_id = $1
+             WHERE customer_id = $2
+             RETURNING waitlist_entry_id",
+            survivor.id.0,
+            merged.id.0
+        )
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(|row| WaitlistEntryId(row.waitlist_entry_id))
+        .collect::<Vec<_>>();
+        waitlist_entries.sort();
+
+        // The merged customer goes first, so the survivor can take its email address.
+        query!(
+            // language=postgresql
+            "DELETE FROM customer WHERE customer_id = $1",
+            merged.id.0
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        query!(
+            // language=postgresql
+            "UPDATE customer SET name = $2, email = $3, phone = $4 WHERE customer_id = $1",
+            survivor.id.0,
+            survivor.name.0,
+            survivor.email.0,
+            survivor.phone.0
+        )
+        .execute(&mut *txn)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.constraint() == Some(CUSTOMER_EMAIL_KEY) => {
+                CustomerError::EmailTaken(survivor.email.0.clone())
+            }
+            e => e.into(),
+        })?;
+
+        let id = CustomerMergeId(Uuid::now_v7());
+        let booking_ids = bookings.iter().map(|b| b.0).collect::<Vec<_>>();
+        let waitlist_entry_ids = waitlist_entries.iter().map(|e| e.0).collect::<Vec<_>>();
+        let merged_at = query!(
+            // language=postgresql
+            "INSERT INTO customer_merge (
+                customer_merge_id,
+                survivor_id,
+                survivor_name,
+                survivor_email,
+                survivor_phone,
+                merged_id,
+                merged_name,
+                merged_email,
+                merged_phone,
+                booking_ids,
+                waitlist_entry_ids
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+             RETURNING merged_at",
+            id.0,
+            survivor.id.0,
+            before.name.0,
+            before.email.0,
+            before.phone.0,
+            merged.id.0,
+            merged.name.0,
+            merged.email.0,
+            merged.phone.0,
+            &booking_ids,
+            &waitlist_entry_ids
+        )
+        .fetch_one(&mut *txn)
+        .await?
+        .merged_at;
+
+        txn.commit().await?;
+
+        Ok(CustomerMerge {
+            id,
+            survivor: survivor.id.clone(),
+            survivor_before: Some(before),
+            merged,
+            bookings,
+            waitlist_entries,
+            merged_at,
+        })
+    }
+
+    async fn find_customer_merges(
+        &self,
+        id: CustomerId,
+    ) -> Result<Vec<CustomerMerge>, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let rows = query_as!(
+            CustomerMergeDto,
+            // language=postgresql
+            "SELECT * FROM customer_merge
+             WHERE survivor_id = $1 OR merged_id = $1
+             ORDER BY merged_at, customer_merge_id",
+            id.0
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(rows.into_iter().map(CustomerMerge::from).collect())
+    }
+
+    async fn delete_customer(&self, id: CustomerId) -> Result<(), CustomerError> {
+        let mut conn = self.connection().await?;
+
+        query!(
+            // language=postgresql
+            "DELETE FROM customer WHERE customer_id = $1",
+            id.0
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+struct CustomerDto {
+    customer_id: Uuid,
+    name: String,
+    email: String,
+    phone: String,
+}
+
+impl From<CustomerDto> for Customer {
+    fn from(dto: CustomerDto) -> Self {
+        Self {
+            id: CustomerId(dto.customer_id),
+            name: CustomerName(dto.name),
+            email: EmailAddress(dto.email),
+            phone: PhoneNumber(dto.phone),
+        }
+    }
+}
+
+struct CustomerMergeDto {
+    customer_merge_id: Uuid,
+    survivor_id: Uuid,
+    survivor_name: Option<String>,
+    survivor_email: Option<String>,
+    survivor_phone: Option<String>,
+    merged_id: Uuid,
+    merged_name: String,
+    merged_email: String,
+    merged_phone: String,
+    booking_ids: Vec<Uuid>,
+    waitlist_entry_ids: Vec<Uuid>,
+    merged_at: DateTime<Utc>,
+}
+
+impl From<CustomerMergeDto> for CustomerMerge {
+    fn from(dto: CustomerMergeDto) -> Self {
+        Self {
+            id: CustomerMergeId(dto.customer_merge_id),
+            survivor: CustomerId(dto.survivor_id),
+            survivor_before: match (dto.survivor_name, dto.survivor_email, dto.survivor_phone) {
+                (Some(name), Some(email), Some(phone)) => Some(Customer {
+                    id: CustomerId(dto.survivor_id),
+                    name: CustomerName(name),
+                    email: EmailAddress(email),
+                    phone: PhoneNumber(phone),
+                }),
+                _ => None,
+            },
+            merged: Customer {
+                id: CustomerId(dto.merged_id),
+                name: CustomerName(dto.merged_name),
+                email: EmailAddress(dto.merged_email),
+                phone: PhoneNumber(dto.merged_phone),
+            },
+            bookings: dto.booking_ids.into_iter().map(BookingId).collect(),
+            waitlist_entries: dto
+                .waitlist_entry_ids
+                .into_iter()
+                .map(WaitlistEntryId)
+                .collect(),
+            merged_at: dto.merged_at,
+        }
+    }
+}
+
+struct DuplicateCandidateDto {
+    first_id: Uuid,
+    first_name: String,
+    first_email: String,
+    first_phone: String,
+    second_id: Uuid,
+    second_name: String,
+    second_email: String,
+    second_phone: String,
+    same_phone: bool,
+    similar_name: bool,
+    same_email_user: bool,
+}
+
+impl From<DuplicateCandidateDto> for DuplicateCandidate {
+    fn from(dto: DuplicateCandidateDto) -> Self {
+        let reasons = [
+            (dto.same_phone, DuplicateReason::SamePhone),
+            (dto.similar_name, DuplicateReason::SimilarName),
+            (dto.same_email_user, DuplicateReason::SameEmailUser),
+        ]
+        .into_iter()
+        .filter_map(|(flagged, reason)| flagged.then_some(reason))
+        .collect();
+
+        Self {
+            first: Customer {
+                id: CustomerId(dto.first_id),
+                name: CustomerName(dto.first_name),
+                email: EmailAddress(dto.first_email),
+                phone: PhoneNumber(dto.first_phone),
+            },
+            second: Customer {
+                id: CustomerId(dto.second_id),
+                name: CustomerName(dto.second_name),
+                email: EmailAddress(dto.second_email),
+                phone: PhoneNumber(dto.second_phone),
+            },
+            reasons,
+        }
+    }
+}
+
+impl From<sqlx::Error> for CustomerError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Unknown(error.into())
+    }
+}
+
+/// Builds a `LIKE` pattern matching `text` anywhere, with its wildcards escaped.
+fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("%{escaped}%")
+}
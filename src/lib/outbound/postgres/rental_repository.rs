@@ -0,0 +1,159 @@
+use super::booking_repository::reserve_inventory;
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::equipment::*;
+use crate::domain::booking::ports::RentalRepository;
+use crate::outbound::postgres::Postgres;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, Connection};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+impl RentalRepository for Postgres {
+    async fn find_booking_rentals(
+        &self,
+        booking_id: BookingId,
+    ) -> Result<BookingRentals, EquipmentError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            RentalDto,
+            // language=postgresql
+            "SELECT equipment_id, quantity
+             FROM booking_equipment
+             WHERE booking_id = $1",
+            booking_id.0
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut rentals = HashMap::<EquipmentId, i32>::with_capacity(result.len());
+        for dto in result {
+            let equipment_id = EquipmentId(dto.equipment_id);
+            if let Some(count) = rentals.get_mut(&equipment_id) {
+                *count += dto.quantity;
+            } else {
+                rentals.insert(equipment_id, dto.quantity);
+            }
+        }
+
+        Ok(BookingRentals {
+            booking_id,
+            rentals,
+        })
+    }
+
+    async fn save_booking_rentals(
+        &self,
+        booking_rentals: &BookingRentals,
+    ) -> Result<(), EquipmentError> {
+        let mut conn = self.connection().await?;
+
+        let (equipment_ids, quantities) = &rentals_to_tuples(&booking_rentals.rentals);
+
+        let mut txn = conn.begin().await?;
+
+        let window = query!(
+            // language=postgresql
+            "SELECT start_time, end_time
+             FROM booking JOIN trip USING (trip_id)
+             WHERE booking_id = $1",
+            booking_rentals.booking_id.0
+        )
+        .fetch_optional(&mut *txn)
+        .await?
+        .ok_or_else(|| EquipmentError::BookingNotFound(booking_rentals.booking_id.clone()))?;
+
+        reserve_inventory(
+            &mut txn,
+            &booking_rentals.booking_id,
+            window.start_time,
+            window.end_time,
+            equipment_ids,
+            quantities,
+        )
+        .await?;
+
+        for command in [
+            query!(
+                // language=postgresql
+                "DELETE FROM booking_equipment WHERE booking_id = $1",
+                booking_rentals.booking_id.0
+            ),
+            query!(
+                // language=postgresql
+                "INSERT INTO booking_equipment (booking_id, equipment_id, quantity)
+                 SELECT * FROM UNNEST($1::UUID[], $2::UUID[], $3::INT[])",
+                &vec![booking_rentals.booking_id.0; equipment_ids.len()],
+                equipment_ids,
+                quantities
+            ),
+        ] {
+            command.execute(&mut *txn).await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn find_equipment_availability(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<EquipmentAvailability, EquipmentError> {
+        let mut conn = self.connection().await?;
+
+        let result = query!(
+            // language=postgresql
+            "SELECT
+                equipment_id,
+                total_inventory - (
+                    SELECT COALESCE(SUM(quantity), 0)
+                    FROM booking_equipment
+                       JOIN booking USING (booking_id)
+                       JOIN trip USING (trip_id)
+                    WHERE booking_equipment.equipment_id = equipment.equipment_id
+                      AND booking.status <> 'cancelled'
+                      AND NOT (booking.status = 'held' AND booking.hold_expires_at <= now())
+                      AND trip.start_time < $2
+                      AND trip.end_time > $1
+                ) AS \"available!\"
+             FROM equipment",
+            start_time,
+            end_time,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(EquipmentAvailability {
+            start_time,
+            end_time,
+            available: result
+                .into_iter()
+                .map(|r| (EquipmentId(r.equipment_id), r.available.max(0) as i32))
+                .collect(),
+        })
+    }
+}
+
+struct RentalDto {
+    equipment_id: Uuid,
+    quantity: i32,
+}
+
+impl From<sqlx::Error> for EquipmentError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Unknown(error.into())
+    }
+}
+
+fn rentals_to_tuples(rentals: &HashMap<EquipmentId, i32>) -> (Vec<Uuid>, Vec<i32>) {
+    rentals.iter().fold(
+        (vec![], vec![]),
+        |(mut equipment_ids, mut quantities), (equipment_id, quantity)| {
+            equipment_ids.push(equipment_id.0);
+            quantities.push(*quantity);
+
+            (equipment_ids, quantities)
+        },
+    )
+}
@@ -0,0 +1,404 @@
+use crate::domain::booking::models::trip::{LocationId, TripId, TripKindId};
+use crate::domain::scheduling::models::schedule::*;
+use crate::domain::scheduling::ports::ScheduleRepository;
+use crate::outbound::postgres::Postgres;
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use sqlx::{query, query_as, Connection};
+use uuid::Uuid;
+
+impl ScheduleRepository for Postgres {
+    async fn find_schedule(&self, id: ScheduleId) -> Result<Option<Schedule>, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            ScheduleDto,
+            // language=postgresql
+            "SELECT * FROM schedule WHERE schedule_id = $1",
+            id.0
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        result.map(Schedule::try_from).transpose()
+    }
+
+    async fn find_active_schedules(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Schedule>, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            ScheduleDto,
+            // language=postgresql
+            "SELECT *
+             FROM schedule
+             WHERE cancelled_at IS NULL
+               AND (ends_on IS NULL OR ends_on >= $1::TIMESTAMPTZ::DATE)",
+            now
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        result.into_iter().map(Schedule::try_from).collect()
+    }
+
+    async fn save_schedule(&self, schedule: &Schedule) -> Result<(), ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let rule = &schedule.recurrence;
+
+        query!(
+            // language=postgresql
+            "INSERT INTO schedule (
+                schedule_id,
+                trip_kind_id,
+                location_id,
+                timezone,
+                starts_on,
+                ends_on,
+                weekdays,
+                months,
+                times,
+                duration_minutes,
+                max_participants,
+                cancelled_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT (schedule_id)
+             DO UPDATE SET
+                location_id = EXCLUDED.location_id,
+                timezone = EXCLUDED.timezone,
+                starts_on = EXCLUDED.starts_on,
+                ends_on = EXCLUDED.ends_on,
+                weekdays = EXCLUDED.weekdays,
+                months = EXCLUDED.months,
+                times = EXCLUDED.times,
+                duration_minutes = EXCLUDED.duration_minutes,
+                max_participants = EXCLUDED.max_participants,
+                cancelled_at = EXCLUDED.cancelled_at",
+            schedule.id.0,
+            schedule.trip_kind.0,
+            schedule.location.0,
+            rule.timezone.name(),
+            rule.starts_on,
+            rule.ends_on,
+            &rule
+                .weekdays
+                .iter()
+                .map(|day| day.number_from_monday() as i32)
+                .collect::<Vec<_>>(),
+            &rule.months.iter().map(|m| *m as i32).collect::<Vec<_>>(),
+            &rule.times,
+            rule.duration.num_minutes() as i32,
+            schedule.max_participants,
+            schedule.cancelled_at,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn save_occurrences(
+        &self,
+        schedule: &Schedule,
+        occurrences: &[Occurrence],
+    ) -> Result<u64, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let trip_ids = occurrences
+            .iter()
+            .map(|_| Uuid::now_v7())
+            .collect::<Vec<_>>();
+        let scheduled_starts = occurrences
+            .iter()
+            .map(|o| o.scheduled_start)
+            .collect::<Vec<_>>();
+        let start_times = occurrences.iter().map(|o| o.start_time).collect::<Vec<_>>();
+        let end_times = occurrences.iter().map(|o| o.end_time).collect::<Vec<_>>();
+
+        let result = query!(
+            // language=postgresql
+            "INSERT INTO trip (
+                trip_id,
+                trip_kind_id,
+                location_id,
+                start_time,
+                end_time,
+                max_participants,
+                schedule_id,
+                occurrence,
+                timezone
+             )
+             SELECT o.trip_id, $2, $3, o.start_time, o.end_time, $4, $1, o.occurrence, $9
+             FROM UNNEST($5::UUID[], $6::TIMESTAMPTZ[], $7::TIMESTAMPTZ[], $8::TIMESTAMPTZ[])
+                AS o (trip_id, occurrence, start_time, end_time)
+             WHERE NOT EXISTS (
+                SELECT 1
+                FROM schedule_exception
+                WHERE schedule_exception.schedule_id = $1
+                  AND schedule_exception.occurrence = o.occurrence
+             )
+             ON CONFLICT (schedule_id, occurrence)
+             DO UPDATE SET
+                location_id = EXCLUDED.location_id,
+                start_time = EXCLUDED.start_time,
+                end_time = EXCLUDED.end_time,
+                max_participants = EXCLUDED.max_participants,
+                timezone = EXCLUDED.timezone
+             WHERE NOT trip.detached
+               AND NOT EXISTS (SELECT 1 FROM booking WHERE booking.trip_id = trip.trip_id)",
+            schedule.id.0,
+            schedule.trip_kind.0,
+            schedule.location.0,
+            schedule.max_participants,
+            &trip_ids,
+            &scheduled_starts,
+            &start_times,
+            &end_times,
+            schedule.recurrence.timezone.name(),
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn prune_occurrences(
+        &self,
+        id: &ScheduleId,
+        after: DateTime<Utc>,
+        keep: &[Occurrence],
+    ) -> Result<Vec<TripId>, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let keep = keep.iter().map(|o| o.scheduled_start).collect::<Vec<_>>();
+
+        let mut txn = conn.begin().await?;
+
+        query!(
+            // language=postgresql
+            "DELETE FROM trip
+             WHERE schedule_id = $1
+               AND occurrence > $2
+               AND NOT detached
+               AND occurrence <> ALL($3)
+               AND NOT EXISTS (SELECT 1 FROM booking WHERE booking.trip_id = trip.trip_id)",
+            id.0,
+            after,
+            &keep,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        let booked = query!(
+            // language=postgresql
+            "SELECT trip_id
+             FROM trip
+             WHERE schedule_id = $1
+               AND occurrence > $2
+               AND NOT detached
+               AND occurrence <> ALL($3)",
+            id.0,
+            after,
+            &keep,
+        )
+        .fetch_all(&mut *txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(booked.into_iter().map(|r| TripId(r.trip_id)).collect())
+    }
+
+    async fn delete_upcoming_occurrences(
+        &self,
+        id: &ScheduleId,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<TripId>, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        query!(
+            // language=postgresql
+            "DELETE FROM trip
+             WHERE schedule_id = $1
+               AND occurrence > $2
+               AND NOT EXISTS (SELECT 1 FROM booking WHERE booking.trip_id = trip.trip_id)",
+            id.0,
+            after,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        let booked = query!(
+            // language=postgresql
+            "SELECT trip_id FROM trip WHERE schedule_id = $1 AND occurrence > $2",
+            id.0,
+            after,
+        )
+        .fetch_all(&mut *txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(booked.into_iter().map(|r| TripId(r.trip_id)).collect())
+    }
+
+    async fn save_detached_occurrence(
+        &self,
+        schedule: &Schedule,
+        occurrence: &Occurrence,
+    ) -> Result<TripId, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let result = query!(
+            // language=postgresql
+            "INSERT INTO trip (
+                trip_id,
+                trip_kind_id,
+                location_id,
+                start_time,
+                end_time,
+                max_participants,
+                schedule_id,
+                occurrence,
+                detached,
+                timezone
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, TRUE, $9)
+             ON CONFLICT (schedule_id, occurrence)
+             DO UPDATE SET
+                start_time = EXCLUDED.start_time,
+                end_time = EXCLUDED.end_time,
+                detached = TRUE
+             RETURNING trip_id",
+            Uuid::now_v7(),
+            schedule.trip_kind.0,
+            schedule.location.0,
+            occurrence.start_time,
+            occurrence.end_time,
+            schedule.max_participants,
+            schedule.id.0,
+            occurrence.scheduled_start,
+            schedule.recurrence.timezone.name(),
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(TripId(result.trip_id))
+    }
+
+    async fn cancel_occurrence(
+        &self,
+        id: &ScheduleId,
+        scheduled_start: DateTime<Utc>,
+    ) -> Result<(), ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let booked = query!(
+            // language=postgresql
+            "SELECT trip_id
+             FROM trip
+             WHERE schedule_id = $1
+               AND occurrence = $2
+               AND EXISTS (SELECT 1 FROM booking WHERE booking.trip_id = trip.trip_id)
+             FOR UPDATE",
+            id.0,
+            scheduled_start,
+        )
+        .fetch_optional(&mut *txn)
+        .await?;
+
+        if let Some(trip) = booked {
+            return Err(ScheduleError::OccurrenceHasBookings(TripId(trip.trip_id)));
+        }
+
+        for command in [
+            query!(
+                // language=postgresql
+                "INSERT INTO schedule_exception (schedule_id, occurrence)
+                 VALUES ($1, $2)
+                 ON CONFLICT DO NOTHING",
+                id.0,
+                scheduled_start,
+            ),
+            query!(
+                // language=postgresql
+                "DELETE FROM trip WHERE schedule_id = $1 AND occurrence = $2",
+                id.0,
+                scheduled_start,
+            ),
+        ] {
+            command.execute(&mut *txn).await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
+
+struct ScheduleDto {
+    schedule_id: Uuid,
+    trip_kind_id: Uuid,
+    location_id: Uuid,
+    timezone: String,
+    starts_on: NaiveDate,
+    ends_on: Option<NaiveDate>,
+    weekdays: Vec<i32>,
+    months: Vec<i32>,
+    times: Vec<NaiveTime>,
+    duration_minutes: i32,
+    max_participants: Option<i32>,
+    cancelled_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<ScheduleDto> for Schedule {
+    type Error = ScheduleError;
+
+    fn try_from(dto: ScheduleDto) -> Result<Self, Self::Error> {
+        let timezone = dto
+            .timezone
+            .parse::<Tz>()
+            .map_err(|e| ScheduleError::Unknown(anyhow!("invalid stored timezone: {e}")))?;
+        let weekdays = dto
+            .weekdays
+            .into_iter()
+            .map(|day| {
+                u8::try_from(day - 1)
+                    .ok()
+                    .and_then(|day| Weekday::try_from(day).ok())
+                    .ok_or_else(|| ScheduleError::Unknown(anyhow!("invalid stored weekday {day}")))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            id: ScheduleId(dto.schedule_id),
+            trip_kind: TripKindId(dto.trip_kind_id),
+            location: LocationId(dto.location_id),
+            recurrence: RecurrenceRule {
+                timezone,
+                starts_on: dto.starts_on,
+                ends_on: dto.ends_on,
+                weekdays,
+                months: dto.months.into_iter().map(|m| m as u32).collect(),
+                times: dto.times,
+                duration: Duration::minutes(dto.duration_minutes.into()),
+            },
+            max_participants: dto.max_participants,
+            cancelled_at: dto.cancelled_at,
+        })
+    }
+}
+
+impl From<sqlx::Error> for ScheduleError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Unknown(error.into())
+    }
+}
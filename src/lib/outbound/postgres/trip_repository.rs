@@ -0,0 +1,192 @@
+use crate::domain::booking::models::trip::*;
+use crate::domain::booking::models::waiver::*;
+use crate::domain::booking::ports::TripRepository;
+use crate::outbound::postgres::Postgres;
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use sqlx::{query_as, FromRow, QueryBuilder};
+use uuid::Uuid;
+
+impl TripRepository for Postgres {
+    async fn find_trip(&self, id: TripId) -> Result<Option<Trip>, TripError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            TripDto,
+            // language=postgresql
+            "SELECT
+                trip_id,
+                trip_kind_id,
+                name,
+                description,
+                guided,
+                meal_provided,
+                trip_kind.max_participants AS kind_max_participants,
+                waiver_id,
+                min_age,
+                max_age,
+                guardian_required_under,
+                requirements,
+                ARRAY(
+                    SELECT notice_minutes FROM cancellation_tier t
+                    WHERE t.trip_kind_id = trip_kind.trip_kind_id
+                    ORDER BY notice_minutes
+                ) AS \"refund_notice_minutes!\",
+                ARRAY(
+                    SELECT refund_percent FROM cancellation_tier t
+                    WHERE t.trip_kind_id = trip_kind.trip_kind_id
+                    ORDER BY notice_minutes
+                ) AS \"refund_percents!\",
+                location_id,
+                start_time,
+                end_time,
+                trip.max_participants AS trip_max_participants,
+                trip.timezone
+             FROM trip JOIN trip_kind USING (trip_kind_id)
+             WHERE trip_id = $1",
+            id.0
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        result.map(Trip::try_from).transpose()
+    }
+
+    async fn find_trips(&self, trip_filters: &TripFilters) -> Result<Vec<Trip>, TripError> {
+        let mut conn = self.connection().await?;
+
+        // The service rejects empty filters, and listing every trip here never helps.
+        if trip_filters.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // language=postgresql
+        let query = "
+            SELECT
+                trip_id,
+                trip_kind_id,
+                name,
+                description,
+                guided,
+                meal_provided,
+                trip_kind.max_participants AS kind_max_participants,
+                waiver_id,
+                min_age,
+                max_age,
+                guardian_required_under,
+                requirements,
+                ARRAY(
+                    SELECT notice_minutes FROM cancellation_tier t
+                    WHERE t.trip_kind_id = trip_kind.trip_kind_id
+                    ORDER BY notice_minutes
+                ) AS refund_notice_minutes,
+                ARRAY(
+                    SELECT refund_percent FROM cancellation_tier t
+                    WHERE t.trip_kind_id = trip_kind.trip_kind_id
+                    ORDER BY notice_minutes
+                ) AS refund_percents,
+                location_id,
+                start_time,
+                end_time,
+                trip.max_participants AS trip_max_participants,
+                trip.timezone
+            FROM trip JOIN trip_kind USING (trip_kind_id)
+            WHERE true
+        ";
+
+        let mut qb = QueryBuilder::<sqlx::Postgres>::new(query);
+
+        if let Some(TripKindId(id)) = trip_filters.kind {
+            qb.push(" AND trip_kind_id = ").push_bind(id);
+        }
+        if let Some(LocationId(id)) = trip_filters.location {
+            qb.push(" AND location_id = ").push_bind(id);
+        }
+        if let Some((start, end)) = trip_filters.date_range {
+            qb.push(" AND start_time BETWEEN ")
+                .push_bind(start)
+                .push(" AND ")
+                .push_bind(end);
+        }
+
+        let result = qb.build_query_as::<TripDto>().fetch_all(&mut *conn).await?;
+
+        result.into_iter().map(Trip::try_from).collect()
+    }
+}
+
+#[derive(FromRow, Debug)]
+struct TripDto {
+    trip_id: Uuid,
+    trip_kind_id: Uuid,
+    name: String,
+    description: String,
+    guided: bool,
+    meal_provided: bool,
+    kind_max_participants: Option<i32>,
+    waiver_id: Option<Uuid>,
+    min_age: Option<i32>,
+    max_age: Option<i32>,
+    guardian_required_under: Option<i32>,
+    requirements: Vec<String>,
+    refund_notice_minutes: Vec<i32>,
+    refund_percents: Vec<i32>,
+    location_id: Uuid,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    trip_max_participants: Option<i32>,
+    timezone: String,
+}
+
+impl TryFrom<TripDto> for Trip {
+    type Error = TripError;
+
+    fn try_from(dto: TripDto) -> Result<Self, Self::Error> {
+        let timezone = dto
+            .timezone
+            .parse::<Tz>()
+            .map_err(|e| TripError::Unknown(anyhow!("invalid stored timezone: {e}")))?;
+
+        Ok(Self {
+            id: TripId(dto.trip_id),
+            kind: TripKind {
+                id: TripKindId(dto.trip_kind_id),
+                name: dto.name,
+                description: dto.description,
+                guided: dto.guided,
+                meal_provided: dto.meal_provided,
+                max_participants: dto.kind_max_participants,
+                waiver: dto.waiver_id.map(WaiverId),
+                eligibility: Eligibility {
+                    min_age: dto.min_age.map(|age| age as u32),
+                    max_age: dto.max_age.map(|age| age as u32),
+                    guardian_required_under: dto.guardian_required_under.map(|age| age as u32),
+                    requirements: dto.requirements,
+                },
+                cancellation_policy: CancellationPolicy {
+                    tiers: dto
+                        .refund_notice_minutes
+                        .into_iter()
+                        .zip(dto.refund_percents)
+                        .map(|(minutes, percent)| RefundTier {
+                            notice: Duration::minutes(minutes.into()),
+                            percent,
+                        })
+                        .collect(),
+                },
+            },
+            location: LocationId(dto.location_id),
+            start_time: dto.start_time,
+            end_time: dto.end_time,
+            max_participants: dto.trip_max_participants,
+            timezone,
+        })
+    }
+}
+
+impl From<sqlx::Error> for TripError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Unknown(error.into())
+    }
+}
@@ -0,0 +1,211 @@
+use crate::domain::booking::models::blob::BlobKey;
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::waiver::*;
+use crate::domain::booking::ports::WaiverRepository;
+use crate::outbound::postgres::Postgres;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, Connection};
+use uuid::Uuid;
+
+impl WaiverRepository for Postgres {
+    async fn find_current_waiver(
+        &self,
+        id: WaiverId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<Waiver>, WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            WaiverDto,
+            // language=postgresql
+            "SELECT *
+             FROM waiver_version
+             WHERE waiver_id = $1 AND effective_at <= $2
+             ORDER BY version DESC
+             LIMIT 1",
+            id.0,
+            at
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(Waiver::from))
+    }
+
+    async fn find_latest_waiver(&self, id: WaiverId) -> Result<Option<Waiver>, WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            WaiverDto,
+            // language=postgresql
+            "SELECT *
+             FROM waiver_version
+             WHERE waiver_id = $1
+             ORDER BY version DESC
+             LIMIT 1",
+            id.0
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(Waiver::from))
+    }
+
+    async fn save_waiver(&self, waiver: &Waiver) -> Result<(), WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+        for command in [
+            query!(
+                // language=postgresql
+                "INSERT INTO waiver (waiver_id) VALUES ($1) ON CONFLICT DO NOTHING",
+                waiver.id.0
+            ),
+            query!(
+                // language=postgresql
+                "INSERT INTO waiver_version (waiver_id, version, content, effective_at)
+                 VALUES ($1, $2, $3, $4)",
+                waiver.id.0,
+                waiver.version,
+                waiver.content,
+                waiver.effective_at,
+            ),
+        ] {
+            command.execute(&mut *txn).await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn save_waiver_signature(&self, signature: &WaiverSignature) -> Result<(), WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let signer = signature.signer.as_ref();
+
+        query!(
+            // language=postgresql
+            "INSERT INTO participant_waiver (
+                participant_waiver_id,
+                participant_id,
+                waiver_id,
+                version,
+                date_signed,
+                content_hash,
+                signed_at,
+                signer_name,
+                signer_ip,
+                signer_user_agent,
+                guardian_relationship,
+                signature_blob
+             )
+             VALUES ($1, $2, $3, $4, $5::TIMESTAMPTZ::DATE, $6, $5, $7, $8, $9, $10, $11)",
+            signature.id.0,
+            signature.participant.0,
+            signature.waiver.0,
+            signature.version,
+            signature.signed_at,
+            signature.content_hash,
+            signer.map(|s| s.name.clone()),
+            signer.and_then(|s| s.ip).map(|ip| ip.to_string()),
+            signer.and_then(|s| s.user_agent.clone()),
+            signer.and_then(|s| s.guardian_relationship.clone()),
+            signature.signature_image.as_ref().map(|key| key.0.clone()),
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_waiver_signatures(
+        &self,
+        participants: &[ParticipantId],
+    ) -> Result<Vec<WaiverSignature>, WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as!(
+            WaiverSignatureDto,
+            // language=postgresql
+            "SELECT
+                participant_waiver_id,
+                participant_id,
+                waiver_id,
+                version,
+                content_hash,
+                signed_at,
+                signer_name,
+                signer_ip,
+                signer_user_agent,
+                guardian_relationship,
+                signature_blob
+             FROM participant_waiver
+             WHERE participant_id = ANY($1)",
+            &participants.iter().map(|p| p.0).collect::<Vec<_>>(),
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(result.into_iter().map(WaiverSignature::from).collect())
+    }
+}
+
+struct WaiverDto {
+    waiver_id: Uuid,
+    version: i32,
+    content: String,
+    effective_at: DateTime<Utc>,
+}
+
+impl From<WaiverDto> for Waiver {
+    fn from(dto: WaiverDto) -> Self {
+        Self {
+            id: WaiverId(dto.waiver_id),
+            version: dto.version,
+            content: dto.content,
+            effective_at: dto.effective_at,
+        }
+    }
+}
+
+struct WaiverSignatureDto {
+    participant_waiver_id: Uuid,
+    participant_id: Uuid,
+    waiver_id: Uuid,
+    version: i32,
+    content_hash: Option<String>,
+    signed_at: DateTime<Utc>,
+    signer_name: Option<String>,
+    signer_ip: Option<String>,
+    signer_user_agent: Option<String>,
+    guardian_relationship: Option<String>,
+    signature_blob: Option<String>,
+}
+
+impl From<WaiverSignatureDto> for WaiverSignature {
+    fn from(dto: WaiverSignatureDto) -> Self {
+        let signer = dto.signer_name.map(|name| Signer {
+            name,
+            ip: dto.signer_ip.and_then(|ip| ip.parse().ok()),
+            user_agent: dto.signer_user_agent,
+            guardian_relationship: dto.guardian_relationship,
+        });
+
+        Self {
+            id: WaiverSignatureId(dto.participant_waiver_id),
+            participant: ParticipantId(dto.participant_id),
+            waiver: WaiverId(dto.waiver_id),
+            version: dto.version,
+            content_hash: dto.content_hash,
+            signed_at: dto.signed_at,
+            signer,
+            signature_image: dto.signature_blob.map(BlobKey),
+        }
+    }
+}
+
+impl From<sqlx::Error> for WaiverError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Unknown(error.into())
+    }
+}
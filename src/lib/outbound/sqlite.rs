@@ -0,0 +1,266 @@
+//! Module [sqlite] is an outbound adapter for an SQLite database file, for single-site
+//! deployments that can't run PostgreSQL.
+//!
+//! The schema mirrors the [postgres](crate::outbound::postgres) one and has its own
+//! migrations in `migrations/sqlite`. SQLite has no trigram matching, so customer search
+//! and duplicate detection are done in process.
+
+mod booking_repository;
+mod customer_repository;
+mod rental_repository;
+mod schedule_repository;
+mod trip_repository;
+mod unit_of_work;
+mod waiver_repository;
+
+use crate::outbound::transaction::{self, SharedTransaction};
+use anyhow::Context;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::str::FromStr;
+
+/// [SqliteConfig] contains the database location needed to instantiate [Sqlite].
+pub struct SqliteConfig<'cfg> {
+    /// The database to open, e.g. `sqlite://tide.db`, which is created if missing.
+    pub url: &'cfg str,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sqlite {
+    pool: SqlitePool,
+    /// The open transaction of a unit of work, if this instance is bound to one.
+    transaction: Option<SharedTransaction<sqlx::Sqlite>>,
+}
+
+impl Sqlite {
+    /// Creates a new instance of [Sqlite] from a [SqliteConfig].
+    ///
+    /// `from_config` opens the database with foreign keys enforced, then runs any pending
+    /// migrations.
+    ///
+    /// The pool holds a single connection, which is never closed. SQLite only allows one
+    /// writer at a time anyway, and queuing every transaction behind the last one keeps
+    /// capacity and inventory checks from racing, as row locks do in PostgreSQL. It also
+    /// keeps `sqlite::memory:` databases alive for the lifetime of the pool.
+    ///
+    /// If opening the database or migrating it fails, the offending errors are surfaced
+    /// out for reporting.
+    pub async fn from_config(config: SqliteConfig<'_>) -> anyhow::Result<Self> {
+        let options = SqliteConnectOptions::from_str(config.url)
+            .context("invalid SQLite database URL")?
+            .create_if_missing(true)
+            .foreign_keys(true)
+            .journal_mode(SqliteJournalMode::Wal);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await
+            .context("failed to open SQLite database")?;
+
+        sqlx::migrate!("./migrations/sqlite")
+            .run(&pool)
+            .await
+            .context("failed to run DB migrations")?;
+
+        Ok(Self {
+            pool,
+            transaction: None,
+        })
+    }
+
+    /// Gets the connection of the open unit of work, or one from the pool outside of one.
+    async fn connection(&self) -> Result<transaction::Connection<'_, sqlx::Sqlite>, sqlx::Error> {
+        transaction::connection(&self.pool, self.transaction.as_ref()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::booking::models::equipment::Equipment;
+    use crate::domain::booking::models::pricing::{Money, ParticipantCategory, PriceModifier};
+    use crate::domain::booking::models::trip::{Trip, TripKindId};
+    use crate::outbound::conformance::{booking_repository_conformance, Catalog};
+    use sqlx::types::Json;
+
+    impl Catalog for Sqlite {
+        async fn insert_trip(&self, trip: &Trip) {
+            let kind = &trip.kind;
+            let eligibility = &kind.eligibility;
+            sqlx::query(
+                // language=sqlite
+                "INSERT INTO trip_kind (
+                    trip_kind_id,
+                    name,
+                    description,
+                    guided,
+                    meal_provided,
+                    max_participants,
+                    waiver_id,
+                    min_age,
+                    max_age,
+                    guardian_required_under,
+                    requirements
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                 ON CONFLICT (trip_kind_id)
+                 DO UPDATE SET max_participants = excluded.max_participants",
+            )
+            .bind(kind.id.0)
+            .bind(&kind.name)
+            .bind(&kind.description)
+            .bind(kind.guided)
+            .bind(kind.meal_provided)
+            .bind(kind.max_participants)
+            .bind(kind.waiver.as_ref().map(|w| w.0))
+            .bind(eligibility.min_age.map(|age| age as i32))
+            .bind(eligibility.max_age.map(|age| age as i32))
+            .bind(eligibility.guardian_required_under.map(|age| age as i32))
+            .bind(Json(&eligibility.requirements))
+            .execute(&self.pool)
+            .await
+            .unwrap();
+
+            for tier in &kind.cancellation_policy.tiers {
+                sqlx::query(
+                    // language=sqlite
+                    "INSERT INTO cancellation_tier (trip_kind_id, notice_minutes, refund_percent)
+                     VALUES ($1, $2, $3)
+                     ON CONFLICT DO NOTHING",
+                )
+                .bind(kind.id.0)
+                .bind(tier.notice.num_minutes())
+                .bind(tier.percent)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+            }
+
+            sqlx::query(
+                // language=sqlite
+                "INSERT INTO location (location_id, name) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            )
+            .bind(trip.location.0)
+            .bind(trip.location.0.to_string())
+            .execute(&self.pool)
+            .await
+            .unwrap();
+
+            sqlx::query(
+                // language=sqlite
+                "INSERT INTO trip (
+                    trip_id,
+                    trip_kind_id,
+                    location_id,
+                    start_time,
+                    end_time,
+                    max_participants,
+                    timezone
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (trip_id)
+                 DO UPDATE SET max_participants = excluded.max_participants",
+            )
+            .bind(trip.id.0)
+            .bind(kind.id.0)
+            .bind(trip.location.0)
+            .bind(trip.start_time)
+            .bind(trip.end_time)
+            .bind(trip.max_participants)
+            .bind(trip.timezone.name())
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+
+        async fn insert_equipment(&self, equipment: &Equipment) {
+            sqlx::query(
+                // language=sqlite
+                "INSERT INTO equipment (
+                    equipment_id,
+                    name,
+                    description,
+                    total_inventory,
+                    rental_price
+                 )
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (equipment_id)
+                 DO UPDATE SET
+                    total_inventory = excluded.total_inventory,
+                    rental_price = excluded.rental_price",
+            )
+            .bind(equipment.id.0)
+            .bind(&equipment.name.0)
+            .bind(&equipment.description.0)
+            .bind(equipment.total_inventory)
+            .bind(equipment.rental_price.map(|price| price.0))
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+
+        async fn insert_base_price(
+            &self,
+            kind: &TripKindId,
+            category: ParticipantCategory,
+            price: Money,
+        ) {
+            sqlx::query(
+                // language=sqlite
+                "INSERT INTO trip_kind_price (trip_kind_id, category, price)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (trip_kind_id, category)
+                 DO UPDATE SET price = excluded.price",
+            )
+            .bind(kind.0)
+            .bind(format!("{category:?}").to_lowercase())
+            .bind(price.0)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+
+        async fn insert_price_modifier(&self, modifier: &PriceModifier) {
+            sqlx::query(
+                // language=sqlite
+                "INSERT INTO price_modifier (
+                    price_modifier_id,
+                    name,
+                    trip_kind_id,
+                    months,
+                    weekdays,
+                    percent
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(modifier.id.0)
+            .bind(&modifier.name)
+            .bind(modifier.trip_kind.as_ref().map(|kind| kind.0))
+            .bind(Json(&modifier.months))
+            .bind(Json(
+                modifier
+                    .weekdays
+                    .iter()
+                    .map(|day| day.number_from_monday())
+                    .collect::<Vec<_>>(),
+            ))
+            .bind(modifier.percent)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    async fn sqlite() -> Sqlite {
+        Sqlite::from_config(SqliteConfig {
+            url: "sqlite::memory:",
+        })
+        .await
+        .unwrap()
+    }
+
+    booking_repository_conformance!(#[tokio::test] () => sqlite().await);
+}
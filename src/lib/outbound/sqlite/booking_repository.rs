@@ -0,0 +1,1117 @@
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::customer::*;
+use crate::domain::booking::models::equipment::*;
+use crate::domain::booking::models::payment::*;
+use crate::domain::booking::models::pricing::*;
+use crate::domain::booking::models::trip::*;
+use crate::domain::booking::models::waitlist::*;
+use crate::domain::booking::models::waiver::*;
+use crate::domain::booking::ports::BookingRepository;
+use crate::outbound::sqlite::Sqlite;
+use anyhow::anyhow;
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use sqlx::types::Json;
+use sqlx::{query, query_as, query_scalar, Connection, FromRow, QueryBuilder, SqliteConnection};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+impl BookingRepository for Sqlite {
+    async fn find_booking(&self, id: BookingId) -> Result<Option<Booking>, BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, BookingDto>(&format!("{SELECT_BOOKING} WHERE booking_id = $1"))
+            .bind(id.0)
+            .fetch_optional(&mut *conn)
+            .await?;
+        let Some(dto) = result else {
+            return Ok(None);
+        };
+
+        let mut bookings = with_participants(&mut conn, vec![dto]).await?;
+
+        Ok(bookings.pop())
+    }
+
+    async fn find_bookings(&self, filters: &BookingFilters) -> Result<Vec<Booking>, BookingError> {
+        let mut conn = self.connection().await?;
+
+        if filters.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut qb = QueryBuilder::<sqlx::Sqlite>::new(SELECT_BOOKING);
+        qb.push(" WHERE TRUE");
+
+        if let Some(BookingReference(reference)) = &filters.reference {
+            qb.push(" AND reference = ").push_bind(reference);
+        }
+        if let Some(CustomerId(id)) = filters.customer {
+            qb.push(" AND customer_id = ").push_bind(id);
+        }
+        if let Some(TripId(id)) = filters.trip {
+            qb.push(" AND trip_id = ").push_bind(id);
+        }
+        if let Some(ParticipantId(id)) = filters.participant {
+            qb.push(
+                " AND EXISTS (
+                    SELECT 1 FROM booking_participant
+                    WHERE booking_participant.booking_id = booking.booking_id
+                      AND participant_id = ",
+            )
+            .push_bind(id)
+            .push(")");
+        }
+        if let Some(status) = filters.status {
+            qb.push(" AND status = ").push_bind(status.as_str());
+        }
+
+        qb.push(" ORDER BY booking_id");
+
+        let result = qb
+            .build_query_as::<BookingDto>()
+            .fetch_all(&mut *conn)
+            .await?;
+
+        with_participants(&mut conn, result).await
+    }
+
+    async fn save_booking(
+        &self,
+        booking: &Booking,
+        overbooking: OverbookingPolicy,
+    ) -> Result<bool, BookingError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let overbooked = reserve_seats(&mut txn, booking, overbooking).await? || booking.overbooked;
+
+        query(
+            // language=sqlite
+            "INSERT INTO booking (
+                booking_id,
+                reference,
+                customer_id,
+                trip_id,
+                status,
+                hold_expires_at,
+                checked_in_at,
+                cancelled_at,
+                cancellation_reason,
+                refund_amount,
+                waitlist_entry_id,
+                overbooked
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT (booking_id)
+             DO UPDATE SET
+                reference = excluded.reference,
+                customer_id = excluded.customer_id,
+                trip_id = excluded.trip_id,
+                status = excluded.status,
+                hold_expires_at = excluded.hold_expires_at,
+                checked_in_at = excluded.checked_in_at,
+                cancelled_at = excluded.cancelled_at,
+                cancellation_reason = excluded.cancellation_reason,
+                refund_amount = excluded.refund_amount,
+                waitlist_entry_id = excluded.waitlist_entry_id,
+                overbooked = excluded.overbooked",
+        )
+        .bind(booking.id.0)
+        .bind(&booking.reference.0)
+        .bind(booking.customer.0)
+        .bind(booking.trip.0)
+        .bind(booking.status.as_str())
+        .bind(booking.hold_expires_at)
+        .bind(booking.checked_in_at)
+        .bind(booking.cancellation.as_ref().map(|c| c.cancelled_at))
+        .bind(booking.cancellation.as_ref().map(|c| c.reason.as_str()))
+        .bind(booking.cancellation.as_ref().map(|c| c.refund.0))
+        .bind(booking.waitlist_entry.as_ref().map(|e| e.0))
+        .bind(overbooked)
+        .execute(&mut *txn)
+        .await?;
+
+        query(
+            // language=sqlite
+            "UPDATE waitlist_entry
+             SET status = 'accepted', offer_expires_at = NULL
+             WHERE waitlist_entry_id = $1 AND status = 'offered'",
+        )
+        .bind(booking.waitlist_entry.as_ref().map(|e| e.0))
+        .execute(&mut *txn)
+        .await?;
+
+        query(
+            // language=sqlite
+            "DELETE FROM booking_participant WHERE booking_id = $1",
+        )
+        .bind(booking.id.0)
+        .execute(&mut *txn)
+        .await?;
+
+        for participant in &booking.participants {
+            query(
+                // language=sqlite
+                "INSERT INTO participant (participant_id, name, dob, notes)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (participant_id)
+                 DO UPDATE SET
+                    name = excluded.name,
+                    dob = excluded.dob,
+                    notes = excluded.notes",
+            )
+            .bind(participant.id.0)
+            .bind(&participant.name)
+            .bind(participant.dob)
+            .bind(&participant.notes)
+            .execute(&mut *txn)
+            .await?;
+
+            query(
+                // language=sqlite
+                "INSERT INTO booking_participant (booking_id, participant_id) VALUES ($1, $2)",
+            )
+            .bind(booking.id.0)
+            .bind(participant.id.0)
+            .execute(&mut *txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(overbooked)
+    }
+
+    async fn reschedule_booking(
+        &self,
+        booking: &Booking,
+        overbooking: OverbookingPolicy,
+    ) -> Result<bool, BookingError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let overbooked = reserve_seats(&mut txn, booking, overbooking).await? || booking.overbooked;
+
+        let (start_time, end_time) = query_as::<_, (DateTime<Utc>, DateTime<Utc>)>(
+            // language=sqlite
+            "SELECT start_time, end_time FROM trip WHERE trip_id = $1",
+        )
+        .bind(booking.trip.0)
+        .fetch_optional(&mut *txn)
+        .await?
+        .ok_or_else(|| BookingError::TripNotFound(booking.trip.clone()))?;
+
+        let rentals = query_as::<_, RentalDto>(
+            // language=sqlite
+            "SELECT equipment_id, quantity FROM booking_equipment WHERE booking_id = $1",
+        )
+        .bind(booking.id.0)
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(|r| (EquipmentId(r.equipment_id), r.quantity))
+        .collect();
+
+        reserve_inventory(&mut txn, &booking.id, start_time, end_time, &rentals)
+            .await
+            .map_err(|e| match e {
+                EquipmentError::InsufficientInventory {
+                    equipment,
+                    requested,
+                    available,
+                } => BookingError::InsufficientInventory {
+                    equipment,
+                    requested,
+                    available,
+                },
+                e => BookingError::Unknown(e.into()),
+            })?;
+
+        let result = query(
+            // language=sqlite
+            "UPDATE booking
+             SET trip_id = $2, overbooked = $4
+             WHERE booking_id = $1 AND status = $3",
+        )
+        .bind(booking.id.0)
+        .bind(booking.trip.0)
+        .bind(booking.status.as_str())
+        .bind(overbooked)
+        .execute(&mut *txn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(BookingError::StatusChanged {
+                booking: booking.id.clone(),
+                from: booking.status,
+            });
+        }
+        txn.commit().await?;
+
+        Ok(overbooked)
+    }
+
+    async fn confirm_hold(&self, id: BookingId, at: DateTime<Utc>) -> Result<(), BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query(
+            // language=sqlite
+            "UPDATE booking
+             SET status = 'confirmed', hold_expires_at = NULL
+             WHERE booking_id = $1
+               AND status = 'held'
+               AND (hold_expires_at IS NULL OR hold_expires_at > $2)",
+        )
+        .bind(id.0)
+        .bind(at)
+        .execute(&mut *conn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(BookingError::HoldExpired(id));
+        }
+
+        Ok(())
+    }
+
+    async fn save_booking_status(
+        &self,
+        booking: &Booking,
+        from: BookingStatus,
+    ) -> Result<(), BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query(
+            // language=sqlite
+            "UPDATE booking
+             SET status = $3,
+                 hold_expires_at = $4,
+                 checked_in_at = $5,
+                 cancelled_at = $6,
+                 cancellation_reason = $7,
+                 refund_amount = $8
+             WHERE booking_id = $1 AND status = $2",
+        )
+        .bind(booking.id.0)
+        .bind(from.as_str())
+        .bind(booking.status.as_str())
+        .bind(booking.hold_expires_at)
+        .bind(booking.checked_in_at)
+        .bind(booking.cancellation.as_ref().map(|c| c.cancelled_at))
+        .bind(booking.cancellation.as_ref().map(|c| c.reason.as_str()))
+        .bind(booking.cancellation.as_ref().map(|c| c.refund.0))
+        .execute(&mut *conn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(BookingError::StatusChanged {
+                booking: booking.id.clone(),
+                from,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn cancel_expired_holds(
+        &self,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<BookingId>, BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_scalar::<_, Uuid>(
+            // language=sqlite
+            "UPDATE booking
+             SET status = 'cancelled',
+                 hold_expires_at = NULL,
+                 cancelled_at = $1,
+                 cancellation_reason = $2,
+                 refund_amount = 0
+             WHERE status = 'held' AND hold_expires_at <= $1
+             RETURNING booking_id",
+        )
+        .bind(at)
+        .bind(HOLD_EXPIRED_REASON)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(result.into_iter().map(BookingId).collect())
+    }
+
+    async fn delete_booking(&self, id: BookingId) -> Result<(), BookingError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+        for command in [
+            // language=sqlite
+            "DELETE FROM booking_participant WHERE booking_id = $1",
+            // language=sqlite
+            "DELETE FROM booking_equipment WHERE booking_id = $1",
+            // language=sqlite
+            "DELETE FROM booking WHERE booking_id = $1",
+        ] {
+            query(command).bind(id.0).execute(&mut *txn).await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn find_waitlist_entry(
+        &self,
+        id: WaitlistEntryId,
+    ) -> Result<Option<WaitlistEntry>, WaitlistError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, WaitlistEntryDto>(
+            // language=sqlite
+            "SELECT * FROM waitlist_entry WHERE waitlist_entry_id = $1",
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        result.map(WaitlistEntry::try_from).transpose()
+    }
+
+    async fn find_waitlist(&self, trip: TripId) -> Result<Vec<WaitlistEntry>, WaitlistError> {
+        let mut conn = self.connection().await?;
+
+        query_as::<_, WaitlistEntryDto>(
+            // language=sqlite
+            "SELECT * FROM waitlist_entry
+             WHERE trip_id = $1
+             ORDER BY created_at, waitlist_entry_id",
+        )
+        .bind(trip.0)
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(WaitlistEntry::try_from)
+        .collect()
+    }
+
+    async fn save_waitlist_entry(&self, entry: &WaitlistEntry) -> Result<(), WaitlistError> {
+        let mut conn = self.connection().await?;
+
+        let offer_expires_at = match entry.status {
+            WaitlistStatus::Offered { expires_at } => Some(expires_at),
+            _ => None,
+        };
+
+        query(
+            // language=sqlite
+            "INSERT INTO waitlist_entry (
+                waitlist_entry_id,
+                customer_id,
+                trip_id,
+                party_size,
+                status,
+                offer_expires_at,
+                created_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (waitlist_entry_id)
+             DO UPDATE SET
+                party_size = excluded.party_size,
+                status = excluded.status,
+                offer_expires_at = excluded.offer_expires_at",
+        )
+        .bind(entry.id.0)
+        .bind(entry.customer.0)
+        .bind(entry.trip.0)
+        .bind(entry.party_size)
+        .bind(entry.status.as_str())
+        .bind(offer_expires_at)
+        .bind(entry.created_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_waitlisted_trips(&self, at: DateTime<Utc>) -> Result<Vec<TripId>, WaitlistError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_scalar::<_, Uuid>(
+            // language=sqlite
+            "SELECT DISTINCT trip_id
+             FROM waitlist_entry JOIN trip USING (trip_id)
+             WHERE trip.start_time > $1
+               AND (waitlist_entry.status = 'waiting'
+                    OR (waitlist_entry.status = 'offered' AND waitlist_entry.offer_expires_at <= $1))
+             ORDER BY trip_id",
+        )
+        .bind(at)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(result.into_iter().map(TripId).collect())
+    }
+
+    async fn offer_waitlist_seats(
+        &self,
+        trip: TripId,
+        at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<WaitlistEntry>, WaitlistError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let (capacity, taken) = query_as::<_, (Option<i32>, i64)>(
+            // language=sqlite
+            "SELECT
+                COALESCE(trip.max_participants, trip_kind.max_participants),
+                (SELECT COUNT(*)
+                 FROM booking JOIN booking_participant USING (booking_id)
+                 WHERE booking.trip_id = trip.trip_id
+                   AND booking.status <> 'cancelled'
+                   AND NOT (booking.status = 'held' AND booking.hold_expires_at <= $2))
+             FROM trip JOIN trip_kind USING (trip_kind_id)
+             WHERE trip_id = $1",
+        )
+        .bind(trip.0)
+        .bind(at)
+        .fetch_optional(&mut *txn)
+        .await?
+        .ok_or_else(|| WaitlistError::TripNotFound(trip.clone()))?;
+
+        query(
+            // language=sqlite
+            "UPDATE waitlist_entry
+             SET status = 'expired', offer_expires_at = NULL
+             WHERE trip_id = $1 AND status = 'offered' AND offer_expires_at <= $2",
+        )
+        .bind(trip.0)
+        .bind(at)
+        .execute(&mut *txn)
+        .await?;
+
+        let entries = query_as::<_, WaitlistEntryDto>(
+            // language=sqlite
+            "SELECT * FROM waitlist_entry
+             WHERE trip_id = $1 AND status IN ('waiting', 'offered')
+             ORDER BY created_at, waitlist_entry_id",
+        )
+        .bind(trip.0)
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(WaitlistEntry::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let offered_seats = entries
+            .iter()
+            .filter(|e| e.status != WaitlistStatus::Waiting)
+            .map(|e| e.party_size as i64)
+            .sum::<i64>();
+        let mut available =
+            capacity.map(|capacity| (capacity as i64 - taken - offered_seats).max(0));
+
+        let mut offers = vec![];
+        for entry in entries {
+            if entry.status != WaitlistStatus::Waiting {
+                continue;
+            }
+            match &mut available {
+                Some(available) if *available < entry.party_size as i64 => break,
+                Some(available) => *available -= entry.party_size as i64,
+                None => {}
+            }
+            offers.push(WaitlistEntry {
+                status: WaitlistStatus::Offered { expires_at },
+                ..entry
+            });
+        }
+
+        for offer in &offers {
+            query(
+                // language=sqlite
+                "UPDATE waitlist_entry
+                 SET status = 'offered', offer_expires_at = $2
+                 WHERE waitlist_entry_id = $1",
+            )
+            .bind(offer.id.0)
+            .bind(expires_at)
+            .execute(&mut *txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(offers)
+    }
+
+    async fn find_participant(
+        &self,
+        id: ParticipantId,
+    ) -> Result<Option<Participant>, BookingError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, ParticipantDto>(
+            // language=sqlite
+            "SELECT
+                participant_id,
+                name,
+                dob,
+                notes,
+                (SELECT waiver_id
+                 FROM participant_waiver
+                 WHERE participant_waiver.participant_id = participant.participant_id
+                 ORDER BY signed_at DESC
+                 LIMIT 1) AS waiver_id
+             FROM participant
+             WHERE participant_id = $1",
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(Participant::from))
+    }
+
+    async fn find_price_list(&self, trip_kind: TripKindId) -> Result<PriceList, PricingError> {
+        let mut conn = self.connection().await?;
+
+        let base_prices = query_as::<_, (String, i64)>(
+            // language=sqlite
+            "SELECT category, price FROM trip_kind_price WHERE trip_kind_id = $1",
+        )
+        .bind(trip_kind.0)
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|(category, price)| Ok((category_from_str(&category)?, Money(price))))
+        .collect::<Result<_, PricingError>>()?;
+
+        let rentals = query_as::<_, (Uuid, i64)>(
+            // language=sqlite
+            "SELECT equipment_id, rental_price
+             FROM equipment
+             WHERE rental_price IS NOT NULL",
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|(id, price)| (EquipmentId(id), Money(price)))
+        .collect();
+
+        let modifiers = query_as::<_, PriceModifierDto>(
+            // language=sqlite
+            "SELECT price_modifier_id, name, trip_kind_id, months, weekdays, percent
+             FROM price_modifier
+             WHERE trip_kind_id IS NULL OR trip_kind_id = $1
+             ORDER BY name",
+        )
+        .bind(trip_kind.0)
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(PriceModifier::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(PriceList {
+            base_prices,
+            rentals,
+            modifiers,
+        })
+    }
+
+    async fn find_payment(&self, id: PaymentId) -> Result<Option<Payment>, PaymentError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, PaymentDto>(
+            // language=sqlite
+            "SELECT * FROM payment WHERE payment_id = $1",
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        result.map(Payment::try_from).transpose()
+    }
+
+    async fn find_payment_ledger(&self, booking: BookingId) -> Result<PaymentLedger, PaymentError> {
+        let mut conn = self.connection().await?;
+
+        let payments = query_as::<_, PaymentDto>(
+            // language=sqlite
+            "SELECT * FROM payment
+             WHERE booking_id = $1
+             ORDER BY created_at, payment_id",
+        )
+        .bind(booking.0)
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(Payment::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(PaymentLedger { booking, payments })
+    }
+
+    async fn save_payment(&self, payment: &Payment) -> Result<(), PaymentError> {
+        let mut conn = self.connection().await?;
+
+        let kind = match payment.kind {
+            PaymentKind::Authorization => "authorization",
+            PaymentKind::Capture => "capture",
+            PaymentKind::Refund => "refund",
+        };
+        let decline_reason = match &payment.status {
+            PaymentStatus::Succeeded => None,
+            PaymentStatus::Declined(reason) => Some(reason.as_str()),
+        };
+
+        query(
+            // language=sqlite
+            "INSERT INTO payment (
+                payment_id,
+                booking_id,
+                kind,
+                parent_id,
+                amount,
+                declined,
+                decline_reason,
+                reference,
+                created_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(payment.id.0)
+        .bind(payment.booking.0)
+        .bind(kind)
+        .bind(payment.parent.as_ref().map(|p| p.0))
+        .bind(payment.amount.0)
+        .bind(decline_reason.is_some())
+        .bind(decline_reason)
+        .bind(payment.reference.as_ref().map(|r| r.0.as_str()))
+        .bind(payment.created_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+// language=sqlite
+const SELECT_BOOKING: &str = "
+    SELECT
+        booking_id,
+        reference,
+        customer_id,
+        trip_id,
+        status,
+        hold_expires_at,
+        checked_in_at,
+        cancelled_at,
+        cancellation_reason,
+        refund_amount,
+        waitlist_entry_id,
+        overbooked
+    FROM booking
+";
+
+#[derive(FromRow)]
+struct BookingDto {
+    booking_id: Uuid,
+    reference: String,
+    customer_id: Uuid,
+    trip_id: Uuid,
+    status: String,
+    hold_expires_at: Option<DateTime<Utc>>,
+    checked_in_at: Option<DateTime<Utc>>,
+    cancelled_at: Option<DateTime<Utc>>,
+    cancellation_reason: Option<String>,
+    refund_amount: Option<i64>,
+    waitlist_entry_id: Option<Uuid>,
+    overbooked: bool,
+}
+
+#[derive(FromRow)]
+struct ParticipantDto {
+    participant_id: Uuid,
+    name: String,
+    dob: NaiveDate,
+    notes: String,
+    waiver_id: Option<Uuid>,
+}
+
+impl From<ParticipantDto> for Participant {
+    fn from(dto: ParticipantDto) -> Self {
+        Self {
+            id: ParticipantId(dto.participant_id),
+            name: dto.name,
+            dob: dto.dob,
+            notes: dto.notes,
+            waiver: dto.waiver_id.map(WaiverId),
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct BookingParticipantDto {
+    booking_id: Uuid,
+    #[sqlx(flatten)]
+    participant: ParticipantDto,
+}
+
+#[derive(FromRow)]
+pub(super) struct RentalDto {
+    pub(super) equipment_id: Uuid,
+    pub(super) quantity: i32,
+}
+
+#[derive(FromRow)]
+struct PaymentDto {
+    payment_id: Uuid,
+    booking_id: Uuid,
+    kind: String,
+    parent_id: Option<Uuid>,
+    amount: i64,
+    declined: bool,
+    decline_reason: Option<String>,
+    reference: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PaymentDto> for Payment {
+    type Error = PaymentError;
+
+    fn try_from(dto: PaymentDto) -> Result<Self, Self::Error> {
+        let kind = match dto.kind.as_str() {
+            "authorization" => PaymentKind::Authorization,
+            "capture" => PaymentKind::Capture,
+            "refund" => PaymentKind::Refund,
+            other => {
+                return Err(PaymentError::Unknown(anyhow!(
+                    "invalid stored payment kind {other}"
+                )))
+            }
+        };
+        let status = if dto.declined {
+            PaymentStatus::Declined(dto.decline_reason.unwrap_or_default())
+        } else {
+            PaymentStatus::Succeeded
+        };
+
+        Ok(Self {
+            id: PaymentId(dto.payment_id),
+            booking: BookingId(dto.booking_id),
+            kind,
+            parent: dto.parent_id.map(PaymentId),
+            amount: Money(dto.amount),
+            status,
+            reference: dto.reference.map(ProviderReference),
+            created_at: dto.created_at,
+        })
+    }
+}
+
+#[derive(FromRow)]
+struct WaitlistEntryDto {
+    waitlist_entry_id: Uuid,
+    customer_id: Uuid,
+    trip_id: Uuid,
+    party_size: i32,
+    status: String,
+    offer_expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<WaitlistEntryDto> for WaitlistEntry {
+    type Error = WaitlistError;
+
+    fn try_from(dto: WaitlistEntryDto) -> Result<Self, Self::Error> {
+        let status = match (dto.status.as_str(), dto.offer_expires_at) {
+            ("waiting", _) => WaitlistStatus::Waiting,
+            ("offered", Some(expires_at)) => WaitlistStatus::Offered { expires_at },
+            ("accepted", _) => WaitlistStatus::Accepted,
+            ("expired", _) => WaitlistStatus::Expired,
+            ("withdrawn", _) => WaitlistStatus::Withdrawn,
+            (other, _) => {
+                return Err(WaitlistError::Unknown(anyhow!(
+                    "invalid stored waitlist status {other}"
+                )))
+            }
+        };
+
+        Ok(Self {
+            id: WaitlistEntryId(dto.waitlist_entry_id),
+            customer: CustomerId(dto.customer_id),
+            trip: TripId(dto.trip_id),
+            party_size: dto.party_size,
+            status,
+            created_at: dto.created_at,
+        })
+    }
+}
+
+#[derive(FromRow)]
+struct PriceModifierDto {
+    price_modifier_id: Uuid,
+    name: String,
+    trip_kind_id: Option<Uuid>,
+    months: Json<Vec<u32>>,
+    weekdays: Json<Vec<u8>>,
+    percent: i32,
+}
+
+impl TryFrom<PriceModifierDto> for PriceModifier {
+    type Error = PricingError;
+
+    fn try_from(dto: PriceModifierDto) -> Result<Self, Self::Error> {
+        let weekdays = dto
+            .weekdays
+            .0
+            .into_iter()
+            .map(|day| {
+                day.checked_sub(1)
+                    .and_then(|day| Weekday::try_from(day).ok())
+                    .ok_or_else(|| PricingError::Unknown(anyhow!("invalid stored weekday {day}")))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            id: PriceModifierId(dto.price_modifier_id),
+            name: dto.name,
+            trip_kind: dto.trip_kind_id.map(TripKindId),
+            months: dto.months.0,
+            weekdays,
+            percent: dto.percent,
+        })
+    }
+}
+
+fn category_from_str(category: &str) -> Result<ParticipantCategory, PricingError> {
+    match category {
+        "child" => Ok(ParticipantCategory::Child),
+        "adult" => Ok(ParticipantCategory::Adult),
+        "senior" => Ok(ParticipantCategory::Senior),
+        other => Err(PricingError::Unknown(anyhow!(
+            "invalid stored participant category {other}"
+        ))),
+    }
+}
+
+/// Reads the participants of the given bookings, keeping the bookings' order.
+async fn with_participants(
+    conn: &mut SqliteConnection,
+    bookings: Vec<BookingDto>,
+) -> Result<Vec<Booking>, BookingError> {
+    let mut participants = HashMap::<Uuid, Vec<Participant>>::new();
+    if !bookings.is_empty() {
+        let mut qb = QueryBuilder::<sqlx::Sqlite>::new(
+            // language=sqlite
+            "SELECT
+                booking_id,
+                participant_id,
+                name,
+                dob,
+                notes,
+                (SELECT waiver_id
+                 FROM participant_waiver
+                 WHERE participant_waiver.participant_id = participant.participant_id
+                 ORDER BY signed_at DESC
+                 LIMIT 1) AS waiver_id
+             FROM booking_participant JOIN participant USING (participant_id)
+             WHERE booking_id IN (",
+        );
+        let mut ids = qb.separated(", ");
+        for booking in &bookings {
+            ids.push_bind(booking.booking_id);
+        }
+        qb.push(") ORDER BY booking_participant.rowid");
+
+        for row in qb
+            .build_query_as::<BookingParticipantDto>()
+            .fetch_all(&mut *conn)
+            .await?
+        {
+            participants
+                .entry(row.booking_id)
+                .or_default()
+                .push(row.participant.into());
+        }
+    }
+
+    bookings
+        .into_iter()
+        .map(|dto| {
+            Ok(Booking {
+                id: BookingId(dto.booking_id),
+                reference: BookingReference(dto.reference),
+                customer: CustomerId(dto.customer_id),
+                trip: TripId(dto.trip_id),
+                participants: participants.remove(&dto.booking_id).unwrap_or_default(),
+                status: dto.status.parse()?,
+                hold_expires_at: dto.hold_expires_at,
+                checked_in_at: dto.checked_in_at,
+                cancellation: dto.cancelled_at.map(|cancelled_at| Cancellation {
+                    reason: dto.cancellation_reason.unwrap_or_default(),
+                    cancelled_at,
+                    refund: Money(dto.refund_amount.unwrap_or_default()),
+                }),
+                waitlist_entry: dto.waitlist_entry_id.map(WaitlistEntryId),
+                overbooked: dto.overbooked,
+            })
+        })
+        .collect()
+}
+
+/// Checks a booking's trip has room for the booking's participants, applying the
+/// [OverbookingPolicy] if it doesn't, and that none of them are already on another active
+/// booking of the trip. Seats the booking already holds on the trip are kept.
+///
+/// Returns whether the booking was flagged as overbooked.
+///
+/// The pool's single connection serializes transactions, so two bookings can't both take
+/// the last seat, or book the same participant.
+async fn reserve_seats(
+    conn: &mut SqliteConnection,
+    booking: &Booking,
+    overbooking: OverbookingPolicy,
+) -> Result<bool, BookingError> {
+    let now = Utc::now();
+
+    let others = query_as::<_, (Uuid, Uuid, String, NaiveDate, String)>(
+        // language=sqlite
+        "SELECT booking_id, participant_id, name, dob, notes
+         FROM booking
+             JOIN booking_participant USING (booking_id)
+             JOIN participant USING (participant_id)
+         WHERE booking.trip_id = $1
+           AND booking.booking_id <> $2
+           AND booking.status <> 'cancelled'
+           AND NOT (booking.status = 'held' AND booking.hold_expires_at <= $3)",
+    )
+    .bind(booking.trip.0)
+    .bind(booking.id.0)
+    .bind(now)
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|(booking_id, participant_id, name, dob, notes)| {
+        let participant = Participant {
+            id: ParticipantId(participant_id),
+            name,
+            dob,
+            notes,
+            waiver: None,
+        };
+        (BookingId(booking_id), participant)
+    })
+    .collect::<Vec<_>>();
+    booking.ensure_not_double_booked(others.iter().map(|(id, p)| (id, p)))?;
+
+    let (capacity, taken, offered, current) = query_as::<_, (Option<i32>, i64, i64, i64)>(
+        // language=sqlite
+        "SELECT
+            COALESCE(trip.max_participants, trip_kind.max_participants),
+            (SELECT COUNT(*)
+             FROM booking JOIN booking_participant USING (booking_id)
+             WHERE booking.trip_id = trip.trip_id
+               AND booking.booking_id <> $2
+               AND booking.status <> 'cancelled'
+               AND NOT (booking.status = 'held' AND booking.hold_expires_at <= $4)),
+            (SELECT COALESCE(SUM(party_size), 0)
+             FROM waitlist_entry
+             WHERE waitlist_entry.trip_id = trip.trip_id
+               AND waitlist_entry.waitlist_entry_id IS NOT $3
+               AND waitlist_entry.status = 'offered'
+               AND waitlist_entry.offer_expires_at > $4),
+            (SELECT COUNT(*)
+             FROM booking_participant JOIN booking USING (booking_id)
+             WHERE booking_participant.booking_id = $2
+               AND booking.trip_id = trip.trip_id)
+         FROM trip JOIN trip_kind USING (trip_kind_id)
+         WHERE trip_id = $1",
+    )
+    .bind(booking.trip.0)
+    .bind(booking.id.0)
+    .bind(booking.waitlist_entry.as_ref().map(|e| e.0))
+    .bind(now)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if let Some(capacity) = capacity {
+        let requested = booking.participants.len() as i64;
+        let taken = taken + offered;
+        let available = (capacity as i64 - taken).max(0);
+        if requested > available && requested > current {
+            match overbooking {
+                OverbookingPolicy::Reject => {
+                    return Err(BookingError::TripFull {
+                        trip: booking.trip.clone(),
+                        capacity,
+                        available: available as i32,
+                    });
+                }
+                OverbookingPolicy::Flag => {
+                    tracing::warn!(
+                        trip_id = %booking.trip.0,
+                        booking_id = %booking.id.0,
+                        capacity,
+                        booked = taken + requested,
+                        "trip is overbooked"
+                    );
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Checks enough of each rented item is left for a booking's rentals across all trips
+/// overlapping `start_time..end_time`, besides what the booking itself rents.
+pub(super) async fn reserve_inventory(
+    conn: &mut SqliteConnection,
+    booking: &BookingId,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    rentals: &HashMap<EquipmentId, i32>,
+) -> Result<(), EquipmentError> {
+    let now = Utc::now();
+
+    for (equipment_id, quantity) in rentals {
+        let stock = query_as::<_, (i32, i64)>(
+            // language=sqlite
+            "SELECT
+                total_inventory,
+                (SELECT COALESCE(SUM(quantity), 0)
+                 FROM booking_equipment
+                    JOIN booking USING (booking_id)
+                    JOIN trip USING (trip_id)
+                 WHERE booking_equipment.equipment_id = equipment.equipment_id
+                   AND booking_equipment.booking_id <> $2
+                   AND booking.status <> 'cancelled'
+                   AND NOT (booking.status = 'held' AND booking.hold_expires_at <= $5)
+                   AND trip.start_time < $4
+                   AND trip.end_time > $3)
+             FROM equipment
+             WHERE equipment_id = $1",
+        )
+        .bind(equipment_id.0)
+        .bind(booking.0)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(now)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let Some((total_inventory, reserved)) = stock else {
+            return Err(EquipmentError::NotFound(equipment_id.clone()));
+        };
+
+        let available = (total_inventory as i64 - reserved).max(0) as i32;
+        if *quantity > available {
+            return Err(EquipmentError::InsufficientInventory {
+                equipment: equipment_id.clone(),
+                requested: *quantity,
+                available,
+            });
+        }
+    }
+
+    Ok(())
+}
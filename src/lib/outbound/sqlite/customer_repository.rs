@@ -0,0 +1,346 @@
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::customer::*;
+use crate::domain::booking::models::waitlist::*;
+use crate::domain::booking::ports::CustomerRepository;
+use crate::outbound::fuzzy;
+use crate::outbound::sqlite::Sqlite;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{query, query_as, query_scalar, Connection, FromRow, SqliteConnection};
+use uuid::Uuid;
+
+/// The unique index on customer emails, ignoring case.
+const CUSTOMER_EMAIL_KEY: &str = "customer_email_lower_key";
+
+impl CustomerRepository for Sqlite {
+    async fn find_customer(&self, id: CustomerId) -> Result<Option<Customer>, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, CustomerDto>(
+            // language=sqlite
+            "SELECT * FROM customer WHERE customer_id = $1",
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(Customer::from))
+    }
+
+    async fn find_customers(&self, page: Page) -> Result<Vec<Customer>, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, CustomerDto>(
+            // language=sqlite
+            "SELECT * FROM customer ORDER BY customer_id LIMIT $1 OFFSET $2",
+        )
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(result.into_iter().map(Customer::from).collect())
+    }
+
+    async fn save_customer(&self, customer: &Customer) -> Result<(), CustomerError> {
+        let mut conn = self.connection().await?;
+
+        query(
+            // language=sqlite
+            "INSERT INTO customer (customer_id, name, email, phone)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (customer_id)
+             DO UPDATE SET
+                name = excluded.name,
+                email = excluded.email,
+                phone = excluded.phone",
+        )
+        .bind(customer.id.0)
+        .bind(&customer.name.0)
+        .bind(&customer.email.0)
+        .bind(&customer.phone.0)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| email_taken(e, &customer.email))?;
+
+        Ok(())
+    }
+
+    async fn search_customers(
+        &self,
+        search: &CustomerSearch,
+    ) -> Result<CustomerSearchResults, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let customers = all_customers(&mut conn).await?;
+
+        Ok(fuzzy::search_customers(&customers, search))
+    }
+
+    async fn find_duplicate_customers(
+        &self,
+        page: Page,
+    ) -> Result<Vec<DuplicateCandidate>, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let customers = all_customers(&mut conn).await?;
+
+        Ok(fuzzy::find_duplicate_customers(&customers, page))
+    }
+
+    async fn merge_customers(
+        &self,
+        request: &MergeCustomersRequest,
+    ) -> Result<CustomerMerge, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let customers = query_as::<_, CustomerDto>(
+            // language=sqlite
+            "SELECT * FROM customer WHERE customer_id IN ($1, $2)",
+        )
+        .bind(request.survivor.0)
+        .bind(request.merged.0)
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(Customer::from)
+        .collect::<Vec<_>>();
+
+        let before = customers
+            .iter()
+            .find(|c| c.id == request.survivor)
+            .cloned()
+            .ok_or_else(|| CustomerError::NotFound(request.survivor.clone()))?;
+        let merged = customers
+            .into_iter()
+            .find(|c| c.id == request.merged)
+            .ok_or_else(|| CustomerError::NotFound(request.merged.clone()))?;
+        let survivor = before.clone().absorb(&merged, request);
+
+        let mut bookings = query_scalar::<_, Uuid>(
+            // language=sqlite
+            "UPDATE booking SET customer_id = $1 WHERE customer_id = $2 RETURNING booking_id",
+        )
+        .bind(survivor.id.0)
+        .bind(merged.id.0)
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(BookingId)
+        .collect::<Vec<_>>();
+        bookings.sort();
+
+        let mut waitlist_entries = query_scalar::<_, Uuid>(
+            // language=sqlite
+            "UPDATE waitlist_entry SET customer_id = $1
+             WHERE customer_id = $2
+             RETURNING waitlist_entry_id",
+        )
+        .bind(survivor.id.0)
+        .bind(merged.id.0)
+        .fetch_all(&mut *txn)
+        .await?
+        .into_iter()
+        .map(WaitlistEntryId)
+        .collect::<Vec<_>>();
+        waitlist_entries.sort();
+
+        // The merged customer goes first, so the survivor can take its email address.
+        query(
+            // language=sqlite
+            "DELETE FROM customer WHERE customer_id = $1",
+        )
+        .bind(merged.id.0)
+        .execute(&mut *txn)
+        .await?;
+
+        query(
+            // language=sqlite
+            "UPDATE customer SET name = $2, email = $3, phone = $4 WHERE customer_id = $1",
+        )
+        .bind(survivor.id.0)
+        .bind(&survivor.name.0)
+        .bind(&survivor.email.0)
+        .bind(&survivor.phone.0)
+        .execute(&mut *txn)
+        .await
+        .map_err(|e| email_taken(e, &survivor.email))?;
+
+        let merge = CustomerMerge {
+            id: CustomerMergeId(Uuid::now_v7()),
+            survivor: survivor.id.clone(),
+            survivor_before: Some(before),
+            merged,
+            bookings,
+            waitlist_entries,
+            merged_at: Utc::now(),
+        };
+        query(
+            // language=sqlite
+            "INSERT INTO customer_merge (
+                customer_merge_id,
+                survivor_id,
+                survivor_name,
+                survivor_email,
+                survivor_phone,
+                merged_id,
+                merged_name,
+                merged_email,
+                merged_phone,
+                booking_ids,
+                waitlist_entry_ids,
+                merged_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(merge.id.0)
+        .bind(merge.survivor.0)
+        .bind(merge.survivor_before.as_ref().map(|c| &c.name.0))
+        .bind(merge.survivor_before.as_ref().map(|c| &c.email.0))
+        .bind(merge.survivor_before.as_ref().map(|c| &c.phone.0))
+        .bind(merge.merged.id.0)
+        .bind(&merge.merged.name.0)
+        .bind(&merge.merged.email.0)
+        .bind(&merge.merged.phone.0)
+        .bind(Json(merge.bookings.iter().map(|b| b.0).collect::<Vec<_>>()))
+        .bind(Json(
+            merge
+                .waitlist_entries
+                .iter()
+                .map(|e| e.0)
+                .collect::<Vec<_>>(),
+        ))
+        .bind(merge.merged_at)
+        .execute(&mut *txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(merge)
+    }
+
+    async fn find_customer_merges(
+        &self,
+        id: CustomerId,
+    ) -> Result<Vec<CustomerMerge>, CustomerError> {
+        let mut conn = self.connection().await?;
+
+        let rows = query_as::<_, CustomerMergeDto>(
+            // language=sqlite
+            "SELECT * FROM customer_merge
+             WHERE survivor_id = $1 OR merged_id = $1
+             ORDER BY merged_at, customer_merge_id",
+        )
+        .bind(id.0)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(rows.into_iter().map(CustomerMerge::from).collect())
+    }
+
+    async fn delete_customer(&self, id: CustomerId) -> Result<(), CustomerError> {
+        let mut conn = self.connection().await?;
+
+        query(
+            // language=sqlite
+            "DELETE FROM customer WHERE customer_id = $1",
+        )
+        .bind(id.0)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(FromRow)]
+struct CustomerDto {
+    customer_id: Uuid,
+    name: String,
+    email: String,
+    phone: String,
+}
+
+impl From<CustomerDto> for Customer {
+    fn from(dto: CustomerDto) -> Self {
+        Self {
+            id: CustomerId(dto.customer_id),
+            name: CustomerName(dto.name),
+            email: EmailAddress(dto.email),
+            phone: PhoneNumber(dto.phone),
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct CustomerMergeDto {
+    customer_merge_id: Uuid,
+    survivor_id: Uuid,
+    survivor_name: Option<String>,
+    survivor_email: Option<String>,
+    survivor_phone: Option<String>,
+    merged_id: Uuid,
+    merged_name: String,
+    merged_email: String,
+    merged_phone: String,
+    booking_ids: Json<Vec<Uuid>>,
+    waitlist_entry_ids: Json<Vec<Uuid>>,
+    merged_at: DateTime<Utc>,
+}
+
+impl From<CustomerMergeDto> for CustomerMerge {
+    fn from(dto: CustomerMergeDto) -> Self {
+        Self {
+            id: CustomerMergeId(dto.customer_merge_id),
+            survivor: CustomerId(dto.survivor_id),
+            survivor_before: match (dto.survivor_name, dto.survivor_email, dto.survivor_phone) {
+                (Some(name), Some(email), Some(phone)) => Some(Customer {
+                    id: CustomerId(dto.survivor_id),
+                    name: CustomerName(name),
+                    email: EmailAddress(email),
+                    phone: PhoneNumber(phone),
+                }),
+                _ => None,
+            },
+            merged: Customer {
+                id: CustomerId(dto.merged_id),
+                name: CustomerName(dto.merged_name),
+                email: EmailAddress(dto.merged_email),
+                phone: PhoneNumber(dto.merged_phone),
+            },
+            bookings: dto.booking_ids.0.into_iter().map(BookingId).collect(),
+            waitlist_entries: dto
+                .waitlist_entry_ids
+                .0
+                .into_iter()
+                .map(WaitlistEntryId)
+                .collect(),
+            merged_at: dto.merged_at,
+        }
+    }
+}
+
+/// Maps a violation of the unique index on customer emails to [CustomerError::EmailTaken].
+fn email_taken(error: sqlx::Error, email: &EmailAddress) -> CustomerError {
+    match error {
+        sqlx::Error::Database(db)
+            if db.is_unique_violation() && db.message().contains(CUSTOMER_EMAIL_KEY) =>
+        {
+            CustomerError::EmailTaken(email.0.clone())
+        }
+        e => e.into(),
+    }
+}
+
+async fn all_customers(conn: &mut SqliteConnection) -> Result<Vec<Customer>, CustomerError> {
+    let customers = query_as::<_, CustomerDto>(
+        // language=sqlite
+        "SELECT * FROM customer",
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(customers.into_iter().map(Customer::from).collect())
+}
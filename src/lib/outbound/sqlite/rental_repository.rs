@@ -0,0 +1,126 @@
+use super::booking_repository::{reserve_inventory, RentalDto};
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::equipment::*;
+use crate::domain::booking::ports::RentalRepository;
+use crate::outbound::sqlite::Sqlite;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, Connection};
+use uuid::Uuid;
+
+impl RentalRepository for Sqlite {
+    async fn find_booking_rentals(
+        &self,
+        booking_id: BookingId,
+    ) -> Result<BookingRentals, EquipmentError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, RentalDto>(
+            // language=sqlite
+            "SELECT equipment_id, quantity FROM booking_equipment WHERE booking_id = $1",
+        )
+        .bind(booking_id.0)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(BookingRentals {
+            booking_id,
+            rentals: result
+                .into_iter()
+                .map(|dto| (EquipmentId(dto.equipment_id), dto.quantity))
+                .collect(),
+        })
+    }
+
+    async fn save_booking_rentals(
+        &self,
+        booking_rentals: &BookingRentals,
+    ) -> Result<(), EquipmentError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let (start_time, end_time) = query_as::<_, (DateTime<Utc>, DateTime<Utc>)>(
+            // language=sqlite
+            "SELECT start_time, end_time
+             FROM booking JOIN trip USING (trip_id)
+             WHERE booking_id = $1",
+        )
+        .bind(booking_rentals.booking_id.0)
+        .fetch_optional(&mut *txn)
+        .await?
+        .ok_or_else(|| EquipmentError::BookingNotFound(booking_rentals.booking_id.clone()))?;
+
+        reserve_inventory(
+            &mut txn,
+            &booking_rentals.booking_id,
+            start_time,
+            end_time,
+            &booking_rentals.rentals,
+        )
+        .await?;
+
+        query(
+            // language=sqlite
+            "DELETE FROM booking_equipment WHERE booking_id = $1",
+        )
+        .bind(booking_rentals.booking_id.0)
+        .execute(&mut *txn)
+        .await?;
+
+        for (equipment_id, quantity) in &booking_rentals.rentals {
+            query(
+                // language=sqlite
+                "INSERT INTO booking_equipment (booking_id, equipment_id, quantity)
+                 VALUES ($1, $2, $3)",
+            )
+            .bind(booking_rentals.booking_id.0)
+            .bind(equipment_id.0)
+            .bind(quantity)
+            .execute(&mut *txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn find_equipment_availability(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<EquipmentAvailability, EquipmentError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, (Uuid, i64)>(
+            // language=sqlite
+            "SELECT
+                equipment_id,
+                total_inventory - (
+                    SELECT COALESCE(SUM(quantity), 0)
+                    FROM booking_equipment
+                       JOIN booking USING (booking_id)
+                       JOIN trip USING (trip_id)
+                    WHERE booking_equipment.equipment_id = equipment.equipment_id
+                      AND booking.status <> 'cancelled'
+                      AND NOT (booking.status = 'held' AND booking.hold_expires_at <= $3)
+                      AND trip.start_time < $2
+                      AND trip.end_time > $1
+                )
+             FROM equipment",
+        )
+        .bind(start_time)
+        .bind(end_time)
+        .bind(Utc::now())
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(EquipmentAvailability {
+            start_time,
+            end_time,
+            available: result
+                .into_iter()
+                .map(|(id, available)| (EquipmentId(id), available.max(0) as i32))
+                .collect(),
+        })
+    }
+}
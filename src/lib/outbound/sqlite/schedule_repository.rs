@@ -0,0 +1,403 @@
+use crate::domain::booking::models::trip::{LocationId, TripId, TripKindId};
+use crate::domain::scheduling::models::schedule::*;
+use crate::domain::scheduling::ports::ScheduleRepository;
+use crate::outbound::sqlite::Sqlite;
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use sqlx::types::Json;
+use sqlx::{query, query_as, Connection, FromRow, SqliteConnection};
+use uuid::Uuid;
+
+impl ScheduleRepository for Sqlite {
+    async fn find_schedule(&self, id: ScheduleId) -> Result<Option<Schedule>, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, ScheduleDto>(
+            // language=sqlite
+            "SELECT * FROM schedule WHERE schedule_id = $1",
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        result.map(Schedule::try_from).transpose()
+    }
+
+    async fn find_active_schedules(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Schedule>, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, ScheduleDto>(
+            // language=sqlite
+            "SELECT *
+             FROM schedule
+             WHERE cancelled_at IS NULL
+               AND (ends_on IS NULL OR ends_on >= $1)",
+        )
+        .bind(now.date_naive())
+        .fetch_all(&mut *conn)
+        .await?;
+
+        result.into_iter().map(Schedule::try_from).collect()
+    }
+
+    async fn save_schedule(&self, schedule: &Schedule) -> Result<(), ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let rule = &schedule.recurrence;
+
+        query(
+            // language=sqlite
+            "INSERT INTO schedule (
+                schedule_id,
+                trip_kind_id,
+                location_id,
+                timezone,
+                starts_on,
+                ends_on,
+                weekdays,
+                months,
+                times,
+                duration_minutes,
+                max_participants,
+                cancelled_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT (schedule_id)
+             DO UPDATE SET
+                location_id = excluded.location_id,
+                timezone = excluded.timezone,
+                starts_on = excluded.starts_on,
+                ends_on = excluded.ends_on,
+                weekdays = excluded.weekdays,
+                months = excluded.months,
+                times = excluded.times,
+                duration_minutes = excluded.duration_minutes,
+                max_participants = excluded.max_participants,
+                cancelled_at = excluded.cancelled_at",
+        )
+        .bind(schedule.id.0)
+        .bind(schedule.trip_kind.0)
+        .bind(schedule.location.0)
+        .bind(rule.timezone.name())
+        .bind(rule.starts_on)
+        .bind(rule.ends_on)
+        .bind(Json(
+            rule.weekdays
+                .iter()
+                .map(|day| day.number_from_monday())
+                .collect::<Vec<_>>(),
+        ))
+        .bind(Json(&rule.months))
+        .bind(Json(&rule.times))
+        .bind(rule.duration.num_minutes())
+        .bind(schedule.max_participants)
+        .bind(schedule.cancelled_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn save_occurrences(
+        &self,
+        schedule: &Schedule,
+        occurrences: &[Occurrence],
+    ) -> Result<u64, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let mut written = 0;
+        for occurrence in occurrences {
+            let result = query(
+                // language=sqlite
+                "INSERT INTO trip (
+                    trip_id,
+                    trip_kind_id,
+                    location_id,
+                    start_time,
+                    end_time,
+                    max_participants,
+                    schedule_id,
+                    occurrence,
+                    timezone
+                 )
+                 SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9
+                 WHERE NOT EXISTS (
+                    SELECT 1
+                    FROM schedule_exception
+                    WHERE schedule_exception.schedule_id = $7
+                      AND schedule_exception.occurrence = $8
+                 )
+                 ON CONFLICT (schedule_id, occurrence)
+                 DO UPDATE SET
+                    location_id = excluded.location_id,
+                    start_time = excluded.start_time,
+                    end_time = excluded.end_time,
+                    max_participants = excluded.max_participants,
+                    timezone = excluded.timezone
+                 WHERE NOT trip.detached
+                   AND NOT EXISTS (SELECT 1 FROM booking WHERE booking.trip_id = trip.trip_id)",
+            )
+            .bind(Uuid::now_v7())
+            .bind(schedule.trip_kind.0)
+            .bind(schedule.location.0)
+            .bind(occurrence.start_time)
+            .bind(occurrence.end_time)
+            .bind(schedule.max_participants)
+            .bind(schedule.id.0)
+            .bind(occurrence.scheduled_start)
+            .bind(schedule.recurrence.timezone.name())
+            .execute(&mut *txn)
+            .await?;
+
+            written += result.rows_affected();
+        }
+        txn.commit().await?;
+
+        Ok(written)
+    }
+
+    async fn prune_occurrences(
+        &self,
+        id: &ScheduleId,
+        after: DateTime<Utc>,
+        keep: &[Occurrence],
+    ) -> Result<Vec<TripId>, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let stale = upcoming_occurrences(&mut txn, id, after)
+            .await?
+            .into_iter()
+            .filter(|trip| !trip.detached)
+            .filter(|trip| keep.iter().all(|o| o.scheduled_start != trip.occurrence))
+            .collect();
+        let booked = delete_unbooked(&mut txn, stale).await?;
+
+        txn.commit().await?;
+
+        Ok(booked)
+    }
+
+    async fn delete_upcoming_occurrences(
+        &self,
+        id: &ScheduleId,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<TripId>, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let upcoming = upcoming_occurrences(&mut txn, id, after).await?;
+        let booked = delete_unbooked(&mut txn, upcoming).await?;
+
+        txn.commit().await?;
+
+        Ok(booked)
+    }
+
+    async fn save_detached_occurrence(
+        &self,
+        schedule: &Schedule,
+        occurrence: &Occurrence,
+    ) -> Result<TripId, ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let (trip_id,) = query_as::<_, (Uuid,)>(
+            // language=sqlite
+            "INSERT INTO trip (
+                trip_id,
+                trip_kind_id,
+                location_id,
+                start_time,
+                end_time,
+                max_participants,
+                schedule_id,
+                occurrence,
+                detached,
+                timezone
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, TRUE, $9)
+             ON CONFLICT (schedule_id, occurrence)
+             DO UPDATE SET
+                start_time = excluded.start_time,
+                end_time = excluded.end_time,
+                detached = TRUE
+             RETURNING trip_id",
+        )
+        .bind(Uuid::now_v7())
+        .bind(schedule.trip_kind.0)
+        .bind(schedule.location.0)
+        .bind(occurrence.start_time)
+        .bind(occurrence.end_time)
+        .bind(schedule.max_participants)
+        .bind(schedule.id.0)
+        .bind(occurrence.scheduled_start)
+        .bind(schedule.recurrence.timezone.name())
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(TripId(trip_id))
+    }
+
+    async fn cancel_occurrence(
+        &self,
+        id: &ScheduleId,
+        scheduled_start: DateTime<Utc>,
+    ) -> Result<(), ScheduleError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        let booked = query_as::<_, (Uuid,)>(
+            // language=sqlite
+            "SELECT trip_id
+             FROM trip
+             WHERE schedule_id = $1
+               AND occurrence = $2
+               AND EXISTS (SELECT 1 FROM booking WHERE booking.trip_id = trip.trip_id)",
+        )
+        .bind(id.0)
+        .bind(scheduled_start)
+        .fetch_optional(&mut *txn)
+        .await?;
+
+        if let Some((trip_id,)) = booked {
+            return Err(ScheduleError::OccurrenceHasBookings(TripId(trip_id)));
+        }
+
+        for command in [
+            // language=sqlite
+            "INSERT INTO schedule_exception (schedule_id, occurrence)
+             VALUES ($1, $2)
+             ON CONFLICT DO NOTHING",
+            // language=sqlite
+            "DELETE FROM trip WHERE schedule_id = $1 AND occurrence = $2",
+        ] {
+            query(command)
+                .bind(id.0)
+                .bind(scheduled_start)
+                .execute(&mut *txn)
+                .await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(FromRow)]
+struct OccurrenceTripDto {
+    trip_id: Uuid,
+    occurrence: DateTime<Utc>,
+    detached: bool,
+    booked: bool,
+}
+
+/// Gets the trips materialized from a schedule's occurrences after `after`.
+async fn upcoming_occurrences(
+    conn: &mut SqliteConnection,
+    id: &ScheduleId,
+    after: DateTime<Utc>,
+) -> Result<Vec<OccurrenceTripDto>, ScheduleError> {
+    let result = query_as::<_, OccurrenceTripDto>(
+        // language=sqlite
+        "SELECT
+            trip_id,
+            occurrence,
+            detached,
+            EXISTS (SELECT 1 FROM booking WHERE booking.trip_id = trip.trip_id) AS booked
+         FROM trip
+         WHERE schedule_id = $1 AND occurrence > $2",
+    )
+    .bind(id.0)
+    .bind(after)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(result)
+}
+
+/// Deletes the given trips that have no bookings, and returns the ones that do.
+async fn delete_unbooked(
+    conn: &mut SqliteConnection,
+    trips: Vec<OccurrenceTripDto>,
+) -> Result<Vec<TripId>, ScheduleError> {
+    let mut booked = vec![];
+    for trip in trips {
+        if trip.booked {
+            booked.push(TripId(trip.trip_id));
+            continue;
+        }
+
+        query(
+            // language=sqlite
+            "DELETE FROM trip WHERE trip_id = $1",
+        )
+        .bind(trip.trip_id)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(booked)
+}
+
+#[derive(FromRow)]
+struct ScheduleDto {
+    schedule_id: Uuid,
+    trip_kind_id: Uuid,
+    location_id: Uuid,
+    timezone: String,
+    starts_on: NaiveDate,
+    ends_on: Option<NaiveDate>,
+    weekdays: Json<Vec<u8>>,
+    months: Json<Vec<u32>>,
+    times: Json<Vec<NaiveTime>>,
+    duration_minutes: i32,
+    max_participants: Option<i32>,
+    cancelled_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<ScheduleDto> for Schedule {
+    type Error = ScheduleError;
+
+    fn try_from(dto: ScheduleDto) -> Result<Self, Self::Error> {
+        let timezone = dto
+            .timezone
+            .parse::<Tz>()
+            .map_err(|e| ScheduleError::Unknown(anyhow!("invalid stored timezone: {e}")))?;
+        let weekdays = dto
+            .weekdays
+            .0
+            .into_iter()
+            .map(|day| {
+                day.checked_sub(1)
+                    .and_then(|day| Weekday::try_from(day).ok())
+                    .ok_or_else(|| ScheduleError::Unknown(anyhow!("invalid stored weekday {day}")))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            id: ScheduleId(dto.schedule_id),
+            trip_kind: TripKindId(dto.trip_kind_id),
+            location: LocationId(dto.location_id),
+            recurrence: RecurrenceRule {
+                timezone,
+                starts_on: dto.starts_on,
+                ends_on: dto.ends_on,
+                weekdays,
+                months: dto.months.0,
+                times: dto.times.0,
+                duration: Duration::minutes(dto.duration_minutes.into()),
+            },
+            max_participants: dto.max_participants,
+            cancelled_at: dto.cancelled_at,
+        })
+    }
+}
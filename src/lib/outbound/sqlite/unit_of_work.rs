@@ -0,0 +1,32 @@
+use crate::domain::booking::ports::UnitOfWork;
+use crate::outbound::sqlite::Sqlite;
+use crate::outbound::transaction::SharedTransaction;
+use anyhow::{anyhow, Context};
+
+impl UnitOfWork for Sqlite {
+    async fn begin(&self) -> anyhow::Result<Self> {
+        if self.transaction.is_some() {
+            return Err(anyhow!("a unit of work is already open"));
+        }
+
+        let transaction = SharedTransaction::begin(&self.pool)
+            .await
+            .context("failed to begin transaction")?;
+
+        Ok(Self {
+            pool: self.pool.clone(),
+            transaction: Some(transaction),
+        })
+    }
+
+    async fn commit(self) -> anyhow::Result<()> {
+        let Some(transaction) = self.transaction else {
+            return Ok(());
+        };
+
+        transaction
+            .commit()
+            .await
+            .context("failed to commit transaction")
+    }
+}
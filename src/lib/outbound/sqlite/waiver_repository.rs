@@ -0,0 +1,218 @@
+use crate::domain::booking::models::blob::BlobKey;
+use crate::domain::booking::models::booking::*;
+use crate::domain::booking::models::waiver::*;
+use crate::domain::booking::ports::WaiverRepository;
+use crate::outbound::sqlite::Sqlite;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, Connection, FromRow, QueryBuilder};
+use uuid::Uuid;
+
+impl WaiverRepository for Sqlite {
+    async fn find_current_waiver(
+        &self,
+        id: WaiverId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<Waiver>, WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, WaiverDto>(
+            // language=sqlite
+            "SELECT *
+             FROM waiver_version
+             WHERE waiver_id = $1 AND effective_at <= $2
+             ORDER BY version DESC
+             LIMIT 1",
+        )
+        .bind(id.0)
+        .bind(at)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(Waiver::from))
+    }
+
+    async fn find_latest_waiver(&self, id: WaiverId) -> Result<Option<Waiver>, WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let result = query_as::<_, WaiverDto>(
+            // language=sqlite
+            "SELECT *
+             FROM waiver_version
+             WHERE waiver_id = $1
+             ORDER BY version DESC
+             LIMIT 1",
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(Waiver::from))
+    }
+
+    async fn save_waiver(&self, waiver: &Waiver) -> Result<(), WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let mut txn = conn.begin().await?;
+
+        query(
+            // language=sqlite
+            "INSERT INTO waiver (waiver_id) VALUES ($1) ON CONFLICT DO NOTHING",
+        )
+        .bind(waiver.id.0)
+        .execute(&mut *txn)
+        .await?;
+
+        query(
+            // language=sqlite
+            "INSERT INTO waiver_version (waiver_id, version, content, effective_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(waiver.id.0)
+        .bind(waiver.version)
+        .bind(&waiver.content)
+        .bind(waiver.effective_at)
+        .execute(&mut *txn)
+        .await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn save_waiver_signature(&self, signature: &WaiverSignature) -> Result<(), WaiverError> {
+        let mut conn = self.connection().await?;
+
+        let signer = signature.signer.as_ref();
+
+        query(
+            // language=sqlite
+            "INSERT INTO participant_waiver (
+                participant_waiver_id,
+                participant_id,
+                waiver_id,
+                version,
+                date_signed,
+                content_hash,
+                signed_at,
+                signer_name,
+                signer_ip,
+                signer_user_agent,
+                guardian_relationship,
+                signature_blob
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(signature.id.0)
+        .bind(signature.participant.0)
+        .bind(signature.waiver.0)
+        .bind(signature.version)
+        .bind(signature.signed_at.date_naive())
+        .bind(&signature.content_hash)
+        .bind(signature.signed_at)
+        .bind(signer.map(|s| s.name.clone()))
+        .bind(signer.and_then(|s| s.ip).map(|ip| ip.to_string()))
+        .bind(signer.and_then(|s| s.user_agent.clone()))
+        .bind(signer.and_then(|s| s.guardian_relationship.clone()))
+        .bind(signature.signature_image.as_ref().map(|key| key.0.clone()))
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_waiver_signatures(
+        &self,
+        participants: &[ParticipantId],
+    ) -> Result<Vec<WaiverSignature>, WaiverError> {
+        let mut conn = self.connection().await?;
+
+        if participants.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut qb = QueryBuilder::<sqlx::Sqlite>::new(
+            // language=sqlite
+            "SELECT
+                participant_waiver_id,
+                participant_id,
+                waiver_id,
+                version,
+                content_hash,
+                signed_at,
+                signer_name,
+                signer_ip,
+                signer_user_agent,
+                guardian_relationship,
+                signature_blob
+             FROM participant_waiver
+             WHERE participant_id IN (",
+        );
+        let mut ids = qb.separated(", ");
+        for participant in participants {
+            ids.push_bind(participant.0);
+        }
+        qb.push(")");
+
+        let result = qb
+            .build_query_as::<WaiverSignatureDto>()
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(result.into_iter().map(WaiverSignature::from).collect())
+    }
+}
+
+#[derive(FromRow)]
+struct WaiverDto {
+    waiver_id: Uuid,
+    version: i32,
+    content: String,
+    effective_at: DateTime<Utc>,
+}
+
+impl From<WaiverDto> for Waiver {
+    fn from(dto: WaiverDto) -> Self {
+        Self {
+            id: WaiverId(dto.waiver_id),
+            version: dto.version,
+            content: dto.content,
+            effective_at: dto.effective_at,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct WaiverSignatureDto {
+    participant_waiver_id: Uuid,
+    participant_id: Uuid,
+    waiver_id: Uuid,
+    version: i32,
+    content_hash: Option<String>,
+    signed_at: DateTime<Utc>,
+    signer_name: Option<String>,
+    signer_ip: Option<String>,
+    signer_user_agent: Option<String>,
+    guardian_relationship: Option<String>,
+    signature_blob: Option<String>,
+}
+
+impl From<WaiverSignatureDto> for WaiverSignature {
+    fn from(dto: WaiverSignatureDto) -> Self {
+        let signer = dto.signer_name.map(|name| Signer {
+            name,
+            ip: dto.signer_ip.and_then(|ip| ip.parse().ok()),
+            user_agent: dto.signer_user_agent,
+            guardian_relationship: dto.guardian_relationship,
+        });
+
+        Self {
+            id: WaiverSignatureId(dto.participant_waiver_id),
+            participant: ParticipantId(dto.participant_id),
+            waiver: WaiverId(dto.waiver_id),
+            version: dto.version,
+            content_hash: dto.content_hash,
+            signed_at: dto.signed_at,
+            signer,
+            signature_image: dto.signature_blob.map(BlobKey),
+        }
+    }
+}
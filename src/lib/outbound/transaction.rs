@@ -0,0 +1,89 @@
+//! Module [transaction] lets the clones of a SQL adapter share one open transaction, so every
+//! repository bound to a [UnitOfWork](crate::domain::booking::ports::UnitOfWork) runs on it.
+
+use sqlx::pool::PoolConnection;
+use sqlx::{Database, Pool, Transaction};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// [SharedTransaction] is a transaction held open until it is committed, or rolled back once
+/// its last clone is dropped.
+pub(crate) struct SharedTransaction<DB: Database>(Arc<Mutex<Option<Transaction<'static, DB>>>>);
+
+impl<DB: Database> SharedTransaction<DB> {
+    pub(crate) async fn begin(pool: &Pool<DB>) -> Result<Self, sqlx::Error> {
+        let transaction = pool.begin().await?;
+
+        Ok(Self(Arc::new(Mutex::new(Some(transaction)))))
+    }
+
+    /// Commits the transaction. Clones used afterwards fail to get a connection.
+    pub(crate) async fn commit(&self) -> Result<(), sqlx::Error> {
+        match self.0.lock().await.take() {
+            Some(transaction) => transaction.commit().await,
+            None => Err(committed()),
+        }
+    }
+}
+
+impl<DB: Database> Clone for SharedTransaction<DB> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<DB: Database> fmt::Debug for SharedTransaction<DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedTransaction").finish_non_exhaustive()
+    }
+}
+
+/// [Connection] is the connection a query runs on: the shared transaction's, which is locked
+/// until the [Connection] is dropped, or one from the pool.
+pub(crate) enum Connection<'a, DB: Database> {
+    Pooled(PoolConnection<DB>),
+    Shared(MutexGuard<'a, Option<Transaction<'static, DB>>>),
+}
+
+/// Gets a connection from `transaction` if given, or else from `pool`.
+pub(crate) async fn connection<'a, DB: Database>(
+    pool: &Pool<DB>,
+    transaction: Option<&'a SharedTransaction<DB>>,
+) -> Result<Connection<'a, DB>, sqlx::Error> {
+    let Some(transaction) = transaction else {
+        return Ok(Connection::Pooled(pool.acquire().await?));
+    };
+
+    let guard = transaction.0.lock().await;
+    if guard.is_none() {
+        return Err(committed());
+    }
+
+    Ok(Connection::Shared(guard))
+}
+
+impl<DB: Database> Deref for Connection<'_, DB> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Connection::Pooled(connection) => connection,
+            Connection::Shared(guard) => guard.as_ref().expect("checked when locked"),
+        }
+    }
+}
+
+impl<DB: Database> DerefMut for Connection<'_, DB> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Connection::Pooled(connection) => connection,
+            Connection::Shared(guard) => guard.as_mut().expect("checked when locked"),
+        }
+    }
+}
+
+fn committed() -> sqlx::Error {
+    sqlx::Error::Protocol("the unit of work was already committed".to_string())
+}